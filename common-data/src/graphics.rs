@@ -1,18 +1,45 @@
 use core::slice;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[repr(u8)]
 pub enum PixelFormat {
     Bgr,
     Rgb,
+    /// An arbitrary per-channel bit layout (GOP's `PixelBitMask`), e.g.
+    /// 5-6-5 or 10-10-10 channel widths some virtual GPUs report.
+    /// Each mask is the set of bits that channel occupies within the
+    /// packed 32-bit pixel; [`crate::graphics`]'s drawer derives a
+    /// shift/width per channel from these once, at construction, rather
+    /// than re-deriving them on every pixel.
+    Bitmask {
+        r_mask: u32,
+        g_mask: u32,
+        b_mask: u32,
+    },
+}
+
+impl PixelFormat {
+    /// Every format this represents -- the packed 32bpp `Rgb`/`Bgr`
+    /// layouts and GOP's `PixelBitMask`, which is also always a 32-bit
+    /// pixel even though its channels don't fill all of it -- is 4
+    /// bytes per pixel. `BltOnly`, the one GOP format genuinely outside
+    /// that, has no representation here at all: it's filtered out
+    /// before a [`FrameBuffer`] is ever built (see the loader's mode
+    /// selection).
+    pub const fn bytes_per_pixel(&self) -> usize {
+        4
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[repr(C)]
 pub struct FrameBuffer {
     base: *mut u8,
     pub size: usize,
     pub stride: usize,
     pub resolution: (usize, usize),
     pub format: PixelFormat,
+    pub bytes_per_pixel: usize,
 }
 
 impl FrameBuffer {
@@ -21,6 +48,37 @@ impl FrameBuffer {
     pub unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
         slice::from_raw_parts_mut(self.base, self.size)
     }
+
+    /// Builds a `FrameBuffer` directly from its fields, for tests
+    /// elsewhere in the workspace that need a fake framebuffer over a
+    /// plain byte buffer (e.g. `kernel::graphics::screenshot`'s) -- the
+    /// `uefi_imp` `From<&mut GraphicsOutput>` impl is the only other
+    /// constructor, and it's feature-gated to real UEFI runs.
+    ///
+    /// # Safety
+    /// `base` must point to at least `size` valid, mutable bytes for as
+    /// long as this `FrameBuffer` (and anything built from it) is used.
+    pub unsafe fn from_raw_parts(
+        base: *mut u8,
+        size: usize,
+        stride: usize,
+        resolution: (usize, usize),
+        format: PixelFormat,
+    ) -> Self {
+        Self {
+            base,
+            size,
+            stride,
+            resolution,
+            format,
+            bytes_per_pixel: format.bytes_per_pixel(),
+        }
+    }
+
+    /// The physical address backing this framebuffer.
+    pub fn base_addr(&self) -> usize {
+        self.base as usize
+    }
 }
 
 /// Note that FrameBuffer can live longer than GraphicsOutput or its boot services
@@ -32,24 +90,41 @@ impl<'gop> From<&mut uefi::proto::console::gop::GraphicsOutput<'gop>> for FrameB
         let base = fb.as_mut_ptr();
         let size = fb.size();
         let mode = gop.current_mode_info();
+        let format = PixelFormat::from_gop_mode(&mode).expect(
+            "current GOP mode is not drawable -- caller must select a drawable mode first",
+        );
         Self {
             base,
             size,
             stride: mode.stride(),
             resolution: mode.resolution(),
-            format: mode.pixel_format().into(),
+            format,
+            bytes_per_pixel: format.bytes_per_pixel(),
         }
     }
 }
 
 #[cfg(feature = "uefi_imp")]
-impl From<uefi::proto::console::gop::PixelFormat> for PixelFormat {
-    fn from(fmt: uefi::proto::console::gop::PixelFormat) -> Self {
+impl PixelFormat {
+    /// Fails only on `BltOnly`, the one GOP format that exposes no
+    /// linear framebuffer at all (it draws through `Blt()`, which this
+    /// kernel never calls). Callers should steer the adapter away from
+    /// it before ever building a [`FrameBuffer`] -- see the loader's
+    /// mode selection.
+    fn from_gop_mode(mode: &uefi::proto::console::gop::ModeInfo) -> Result<Self, ()> {
         use uefi::proto::console::gop::PixelFormat as UefiPixelFormat;
-        match fmt {
-            UefiPixelFormat::Bgr => PixelFormat::Bgr,
-            UefiPixelFormat::Rgb => PixelFormat::Rgb,
-            _ => unimplemented!(),
+        match mode.pixel_format() {
+            UefiPixelFormat::Bgr => Ok(PixelFormat::Bgr),
+            UefiPixelFormat::Rgb => Ok(PixelFormat::Rgb),
+            UefiPixelFormat::Bitmask => {
+                let mask = mode.pixel_bitmask();
+                Ok(PixelFormat::Bitmask {
+                    r_mask: mask.red,
+                    g_mask: mask.green,
+                    b_mask: mask.blue,
+                })
+            }
+            UefiPixelFormat::BltOnly => Err(()),
         }
     }
 }
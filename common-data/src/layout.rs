@@ -0,0 +1,57 @@
+//! Compile-time layout checks for the `#[repr(C)]` structs the loader
+//! and kernel exchange across the boot entry ABI, plus the version
+//! that layout is at.
+//!
+//! The loader and kernel are separate binaries built from this
+//! workspace, not one crate -- a field reorder in [`crate::graphics`],
+//! [`crate::mmap`], or [`crate::module`] would silently change what
+//! one side writes and the other reads, since nothing else checks the
+//! two sides agree. These `const_assert_eq!`s pin each handoff
+//! struct's current `size_of`/`align_of` so such a change fails
+//! `cargo build` right here, in the one crate both sides already
+//! depend on, instead of showing up as a corrupted framebuffer or
+//! memory map at boot.
+//!
+//! Per-field offsets aren't asserted here too: the natural way to get
+//! them at const-eval time is the `offset_of!` trick `memoffset` and
+//! similar crates use (computing a byte difference between two raw
+//! pointers), and this toolchain's `rustc` rejects any pointer-to-
+//! integer cast in a const context, nightly or not -- there's no
+//! feature flag here to turn back on. `size_of`/`align_of` alone still
+//! catch a field being added, removed, or changed to a differently
+//! sized type, which covers the failure mode this is guarding against
+//! (a silent layout drift between the two binaries), just not a pure
+//! field reorder that happens to leave size and align unchanged.
+//!
+//! There's no single `BootInfo` struct in this tree for
+//! [`LAYOUT_VERSION`] to be embedded into and checked against at
+//! runtime -- the loader calls the kernel entry point with three
+//! separate pointers (`MemMap`, `FrameBuffer`, `BootModule`), not one
+//! struct with a magic/version header, and there's also no second
+//! `graphic`/`graphics` module pair to de-duplicate: `graphics` is the
+//! only one that has ever existed here. [`LAYOUT_VERSION`] is kept
+//! here, unused for now, for whenever a real handoff struct exists to
+//! carry it.
+use core::mem::{align_of, size_of};
+
+use static_assertions::const_assert_eq;
+
+use crate::graphics::FrameBuffer;
+use crate::mmap::{MemDesc, MemMap};
+use crate::module::BootModule;
+
+/// Bump this whenever a handoff struct's `#[repr(C)]` layout changes,
+/// and update the assertions below to match.
+pub const LAYOUT_VERSION: u32 = 3;
+
+const_assert_eq!(size_of::<FrameBuffer>(), 64);
+const_assert_eq!(align_of::<FrameBuffer>(), 8);
+
+const_assert_eq!(size_of::<MemDesc>(), 40);
+const_assert_eq!(align_of::<MemDesc>(), 8);
+
+const_assert_eq!(size_of::<MemMap>(), 16);
+const_assert_eq!(align_of::<MemMap>(), 8);
+
+const_assert_eq!(size_of::<BootModule>(), 16);
+const_assert_eq!(align_of::<BootModule>(), 8);
@@ -1,4 +1,6 @@
 #![no_std]
 
 pub mod graphics;
+pub mod layout;
 pub mod mmap;
+pub mod module;
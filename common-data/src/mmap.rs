@@ -1,6 +1,7 @@
 use core::slice;
 
 #[derive(Debug, Clone, Copy)]
+#[repr(C)]
 pub struct MemDesc {
     pub ty: u32,
     pub phys_start: u64,
@@ -12,6 +13,7 @@ pub struct MemDesc {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[repr(C)]
 pub struct MemMap {
     descs: *const MemDesc,
     count: usize,
@@ -32,6 +34,68 @@ impl MemMap {
     pub fn count(&self) -> usize {
         self.count
     }
+
+    /// Coalesces adjacent descriptors into larger free ranges, for a
+    /// physical allocator that wants to carve out whole regions rather
+    /// than juggle every individual descriptor `exit_boot_services` saw.
+    /// Every descriptor in a [`MemMap`] is already a free one (that's
+    /// what `exit_boot_services` filters to before building it), so
+    /// this merges the whole thing rather than filtering by `ty` again.
+    pub fn merged_free_regions(&self) -> MergedFreeRegions<'_> {
+        MergedFreeRegions {
+            descs: self.as_slice(),
+            index: 0,
+        }
+    }
+}
+
+/// A physically contiguous span of free memory, possibly covering
+/// several descriptors whose original UEFI memory types differed
+/// (e.g. `CONVENTIONAL` right after `BOOT_SERVICES_DATA`) but who were
+/// still adjacent and equally usable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FreeRegion {
+    pub phys_start: u64,
+    pub phys_end: u64,
+    pub offset: u64,
+}
+
+impl FreeRegion {
+    pub fn len(&self) -> u64 {
+        self.phys_end - self.phys_start
+    }
+}
+
+/// Yields [`MemMap`]'s descriptors merged wherever one's `phys_end`
+/// meets the next one's `phys_start` with the same virt/phys `offset`;
+/// a gap or an offset change (so the merged range couldn't be
+/// described by one linear virt-to-phys mapping) starts a new region.
+#[derive(Debug, Clone)]
+pub struct MergedFreeRegions<'a> {
+    descs: &'a [MemDesc],
+    index: usize,
+}
+
+impl<'a> Iterator for MergedFreeRegions<'a> {
+    type Item = FreeRegion;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = *self.descs.get(self.index)?;
+        self.index += 1;
+        let mut region = FreeRegion {
+            phys_start: first.phys_start,
+            phys_end: first.phys_end,
+            offset: first.offset,
+        };
+        while let Some(&next) = self.descs.get(self.index) {
+            if next.phys_start != region.phys_end || next.offset != region.offset {
+                break;
+            }
+            region.phys_end = next.phys_end;
+            self.index += 1;
+        }
+        Some(region)
+    }
 }
 
 #[cfg(feature = "uefi_imp")]
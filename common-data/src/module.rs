@@ -0,0 +1,28 @@
+use core::slice;
+
+/// Location of a boot module (e.g. an initrd) handed off by the loader.
+///
+/// Mirrors [`crate::mmap::MemMap`]: the loader places the module's bytes in
+/// page-allocated memory that survives `exit_boot_services`, then passes a
+/// pointer to this struct across the kernel entry ABI so the kernel can
+/// parse it on its own terms.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct BootModule {
+    base: *const u8,
+    size: usize,
+}
+
+impl BootModule {
+    pub fn new(base: *const u8, size: usize) -> Self {
+        Self { base, size }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.base, self.size) }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
@@ -11,7 +11,19 @@ use thiserror::Error;
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 type LineResult<T> = std::result::Result<T, LineError>;
 
+const PCI_VENDORS_SRC: &str = "assets/pci_vendors.txt";
+const PCI_VENDORS_OUT: &str = "pci_names.rs";
+
 const SRC: &str = "assets/hankaku.txt";
+/// Optional second font file for glyphs outside the 0..=255 range that
+/// `hankaku.txt` covers (box-drawing characters, accented Latin letters,
+/// ...). Headered by codepoint (`U+XXXX`) instead of a byte value.
+const EXT_SRC: &str = "assets/extended.txt";
+/// If either of these exists, it's parsed as a PSF1/PSF2 console font
+/// instead of the homegrown `hankaku.txt`/`extended.txt` text format --
+/// standard Linux console fonts ship in this binary format at sizes text
+/// files are tedious to author by hand.
+const PSF_SRC_CANDIDATES: [&str; 2] = ["assets/console.psf", "assets/console.psfu"];
 const OUT: &str = "ascii.rs";
 
 #[derive(Error, Debug)]
@@ -24,11 +36,87 @@ enum LineError {
     UnexpectedChar(char),
     #[error("Unexpected char found: {0:?}")]
     Parse(#[from] ParseIntError),
+    #[error("hex definition missing \"0x\" prefix: {0:?}")]
+    MissingHexPrefix(String),
+    #[error("duplicate font definition for {0:#04x}")]
+    DuplicateDefinition(u8),
+    #[error("duplicate codepoint definition: U+{0:04X}")]
+    DuplicateCodepoint(u32),
+    #[error("ran out of lines while still expecting glyph rows")]
+    InsufficientLines,
+    #[error("codepoint U+{0:04X} is missing glyph rows")]
+    MissingGlyphRows(u32),
+    #[error("unexpected line: {0:?}")]
+    UnexpectedLine(String),
+    #[error("{0} font definitions never showed up in the file")]
+    MissingDefinitions(usize),
+}
+
+#[derive(Error, Debug)]
+enum PciNameError {
+    #[error("vendor ID {0:?} isn't 4 hex digits")]
+    BadVendorId(String),
+    #[error("line has no tab separating the vendor ID from its name: {0:?}")]
+    MissingSeparator(String),
+    #[error("duplicate entry for vendor ID {0:#06x}")]
+    DuplicateVendorId(u16),
+}
+
+#[derive(Error, Debug)]
+enum PsfError {
+    #[error("not a PSF1/PSF2 font file (unrecognized magic bytes)")]
+    BadMagic,
+    #[error("PSF font header is truncated")]
+    TruncatedHeader,
+    #[error("PSF font data is shorter than its header promises")]
+    TruncatedGlyphs,
+    #[error("PSF font has only {0} glyphs, need at least 256")]
+    TooFewGlyphs(usize),
+    #[error("font width {0} requires building with the `wide-font` feature")]
+    WidthRequiresFeature(usize),
+}
+
+/// Wraps a [`LineError`] (or any other error encountered while reading a
+/// font file) with the 1-based line number it came from, so a malformed
+/// `assets/hankaku.txt` points straight at the offending line instead of
+/// leaving it to trial and error.
+#[derive(Debug)]
+struct LocatedError {
+    file: &'static str,
+    line: usize,
+    source: Box<dyn std::error::Error>,
+}
+
+impl std::fmt::Display for LocatedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.file, self.line, self.source)
+    }
+}
+
+impl std::error::Error for LocatedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// `line_no` is 0-based (as produced by `Iterator::enumerate`); reported
+/// 1-based to match what an editor would show.
+fn locate(
+    file: &'static str,
+    line_no: usize,
+    source: impl Into<Box<dyn std::error::Error>>,
+) -> Box<dyn std::error::Error> {
+    Box::new(LocatedError {
+        file,
+        line: line_no + 1,
+        source: source.into(),
+    })
 }
 
 #[derive(Debug)]
 enum Line {
     Definition(u8),
+    Codepoint(u32),
     Body(u8),
 }
 
@@ -36,7 +124,7 @@ impl Line {
     const ASCII_WIDTH: usize = 8;
     const ASCII_HEIGHT: usize = 16;
 
-    pub fn from_str<S>(string: S) -> Result<Option<Self>>
+    pub fn from_str<S>(string: S) -> LineResult<Option<Self>>
     where
         S: AsRef<str>,
     {
@@ -48,7 +136,7 @@ impl Line {
         let line = match tokens.len() {
             0 => None,
             _i @ 1..=2 => Some(Self::parse(tokens[0])?),
-            _ => return Err(Box::new(LineError::ExcessiveElements(string.into()))),
+            _ => return Err(LineError::ExcessiveElements(string.into())),
         };
         Ok(line)
     }
@@ -60,9 +148,13 @@ impl Line {
             '0' => {
                 let s = s
                     .strip_prefix("0x")
-                    .unwrap_or_else(|| panic!("invalid hex: {:?}", s));
+                    .ok_or_else(|| LineError::MissingHexPrefix(s.into()))?;
                 Ok(Self::Definition(u8::from_str_radix(s, 16)?))
             }
+            'U' => {
+                let s = s.strip_prefix("U+").ok_or(LineError::UnexpectedChar('U'))?;
+                Ok(Self::Codepoint(u32::from_str_radix(s, 16)?))
+            }
             '.' | '@' => match s.chars().count() {
                 Self::ASCII_WIDTH => {
                     let mut pos = 0;
@@ -83,6 +175,349 @@ impl Line {
     }
 }
 
+#[derive(Debug)]
+struct AsciiFontEntry {
+    code: u8,
+    /// Flat glyph bytes, `height * row_bytes` long. For the 8-wide text
+    /// format that's always one byte per row; a PSF font wider than 8
+    /// pixels packs more than one byte per row here.
+    rows: Vec<u8>,
+}
+
+/// Parse `0xXX`-headered glyph blocks out of `file`, reporting the
+/// offending 1-based line number via [`LocatedError`] on any failure:
+/// a malformed body row, a duplicate definition, or running out of
+/// lines mid-glyph. Entries come back in file order, which must already
+/// run `0x00..=0xff` since that's the order they land in `ASCII_FONT`.
+fn parse_ascii_fonts<R: BufRead>(file: &'static str, reader: R) -> Result<Vec<AsciiFontEntry>> {
+    let mut entries = Vec::new();
+    let mut remains: HashSet<u8> = (u8::MIN..=u8::MAX).collect();
+
+    let mut lines = reader.lines().enumerate();
+    while let Some((line_no, line)) = lines.next() {
+        let line = line.map_err(|e| locate(file, line_no, e))?;
+        match Line::from_str(&line).map_err(|e| locate(file, line_no, e))? {
+            Some(Line::Definition(code)) => {
+                if !remains.remove(&code) {
+                    return Err(locate(file, line_no, LineError::DuplicateDefinition(code)));
+                }
+
+                let mut rows = vec![0; Line::ASCII_HEIGHT];
+                for row in rows.iter_mut() {
+                    let (body_no, body_line) = lines
+                        .next()
+                        .ok_or_else(|| locate(file, line_no, LineError::InsufficientLines))?;
+                    let body_line = body_line.map_err(|e| locate(file, body_no, e))?;
+                    match Line::from_str(&body_line).map_err(|e| locate(file, body_no, e))? {
+                        Some(Line::Body(layout)) => *row = layout,
+                        l => {
+                            return Err(locate(
+                                file,
+                                body_no,
+                                LineError::UnexpectedLine(format!("{:?}", l)),
+                            ))
+                        }
+                    }
+                }
+                entries.push(AsciiFontEntry { code, rows });
+            }
+            Some(l) => return Err(locate(file, line_no, LineError::UnexpectedLine(format!("{:?}", l)))),
+            None => continue,
+        }
+    }
+
+    if !remains.is_empty() {
+        return Err(locate(file, 0, LineError::MissingDefinitions(remains.len())));
+    }
+    Ok(entries)
+}
+
+/// Parse `U+XXXX`-headered glyph blocks (same `.`/`@` body rows as
+/// [`Line::Body`]) out of `file`, sorted by codepoint so
+/// `font::get_font` can binary-search the generated table. Reports the
+/// offending 1-based line number the same way [`parse_ascii_fonts`] does.
+fn parse_extended_fonts<R: BufRead>(file: &'static str, reader: R) -> Result<Vec<(u32, Vec<u8>)>> {
+    let mut entries = Vec::new();
+    let mut seen = HashSet::new();
+
+    let mut lines = reader.lines().enumerate();
+    while let Some((line_no, line)) = lines.next() {
+        let line = line.map_err(|e| locate(file, line_no, e))?;
+        match Line::from_str(&line).map_err(|e| locate(file, line_no, e))? {
+            Some(Line::Codepoint(cp)) => {
+                if !seen.insert(cp) {
+                    return Err(locate(file, line_no, LineError::DuplicateCodepoint(cp)));
+                }
+
+                let mut font = vec![0; Line::ASCII_HEIGHT];
+                for f in font.iter_mut() {
+                    let (body_no, body_line) = lines
+                        .next()
+                        .ok_or_else(|| locate(file, line_no, LineError::MissingGlyphRows(cp)))?;
+                    let body_line = body_line.map_err(|e| locate(file, body_no, e))?;
+                    match Line::from_str(&body_line).map_err(|e| locate(file, body_no, e))? {
+                        Some(Line::Body(layout)) => *f = layout,
+                        _ => return Err(locate(file, body_no, LineError::MissingGlyphRows(cp))),
+                    }
+                }
+                entries.push((cp, font));
+            }
+            Some(l) => return Err(locate(file, line_no, LineError::UnexpectedLine(format!("{:?}", l)))),
+            None => continue,
+        }
+    }
+
+    entries.sort_unstable_by_key(|&(cp, _)| cp);
+    Ok(entries)
+}
+
+fn write_ascii_fonts(out: &mut impl Write, entries: &[AsciiFontEntry], glyph_len: usize) -> Result<()> {
+    writeln!(out, "pub(crate) const ASCII_FONT: [[u8; {}]; 256] = [", glyph_len)?;
+    for entry in entries {
+        writeln!(out, "\t// {:#08x}", entry.code)?;
+        writeln!(out, "\t[")?;
+        for &layout in entry.rows.iter() {
+            writeln!(out, "\t\t{:#010b},", layout)?;
+        }
+        writeln!(out, "\t],")?;
+    }
+    writeln!(out, "];")?;
+    Ok(())
+}
+
+fn write_extended_fonts(out: &mut impl Write, entries: &[(u32, Vec<u8>)], glyph_len: usize) -> Result<()> {
+    writeln!(out, "pub(crate) const EXT_FONT: &[(u32, [u8; {}])] = &[", glyph_len)?;
+    for (cp, font) in entries {
+        writeln!(out, "\t// U+{:04X}", cp)?;
+        writeln!(out, "\t({:#010x}, [", cp)?;
+        for &layout in font.iter() {
+            writeln!(out, "\t\t{:#010b},", layout)?;
+        }
+        writeln!(out, "\t]),")?;
+    }
+    writeln!(out, "];")?;
+    Ok(())
+}
+
+/// Magic bytes identifying a PSF1 font (two bytes) or PSF2 font (four
+/// bytes); see [`parse_psf`].
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xb5, 0x4a, 0x86];
+
+/// A PSF1/PSF2 font, decoded down to what the generated `ascii.rs` needs:
+/// flat per-glyph row bytes plus an optional codepoint-to-glyph mapping
+/// for glyphs beyond the base 256-entry codepage.
+#[derive(Debug)]
+struct PsfFont {
+    width: usize,
+    height: usize,
+    row_bytes: usize,
+    glyphs: Vec<Vec<u8>>,
+    unicode: Option<Vec<(u32, usize)>>,
+}
+
+fn parse_psf(bytes: &[u8]) -> std::result::Result<PsfFont, PsfError> {
+    if bytes.starts_with(&PSF2_MAGIC) {
+        parse_psf2(bytes)
+    } else if bytes.starts_with(&PSF1_MAGIC) {
+        parse_psf1(bytes)
+    } else {
+        Err(PsfError::BadMagic)
+    }
+}
+
+fn parse_psf1(bytes: &[u8]) -> std::result::Result<PsfFont, PsfError> {
+    let header = bytes.get(..4).ok_or(PsfError::TruncatedHeader)?;
+    let mode = header[2];
+    let height = header[3] as usize;
+    let num_glyphs = if mode & 0x01 != 0 { 512 } else { 256 };
+    let has_unicode_table = mode & 0x02 != 0;
+
+    let glyphs_start = 4;
+    let glyphs_end = glyphs_start + num_glyphs * height;
+    let glyph_bytes = bytes.get(glyphs_start..glyphs_end).ok_or(PsfError::TruncatedGlyphs)?;
+    let glyphs = glyph_bytes.chunks(height).map(<[u8]>::to_vec).collect();
+
+    let unicode = has_unicode_table.then(|| parse_psf1_unicode_table(&bytes[glyphs_end..]));
+
+    Ok(PsfFont {
+        width: 8,
+        height,
+        row_bytes: 1,
+        glyphs,
+        unicode,
+    })
+}
+
+/// PSF1's unicode table is a flat run of little-endian `u16`s: `0xFFFF`
+/// closes out the current glyph's entries and moves to the next glyph,
+/// `0xFFFE` opens a combining-character sequence (its codepoints are
+/// skipped, none of them is mapped), anything else maps that codepoint
+/// onto the current glyph.
+fn parse_psf1_unicode_table(bytes: &[u8]) -> Vec<(u32, usize)> {
+    let mut mapping = Vec::new();
+    let mut glyph_index = 0;
+    let mut in_sequence = false;
+    for chunk in bytes.chunks_exact(2) {
+        match u16::from_le_bytes([chunk[0], chunk[1]]) {
+            0xffff => {
+                glyph_index += 1;
+                in_sequence = false;
+            }
+            0xfffe => in_sequence = true,
+            cp if !in_sequence => mapping.push((cp as u32, glyph_index)),
+            _ => {}
+        }
+    }
+    mapping
+}
+
+fn parse_psf2(bytes: &[u8]) -> std::result::Result<PsfFont, PsfError> {
+    let header = bytes.get(..32).ok_or(PsfError::TruncatedHeader)?;
+    let read_u32 = |offset: usize| u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap());
+    let header_size = read_u32(8) as usize;
+    let flags = read_u32(12);
+    let num_glyphs = read_u32(16) as usize;
+    let bytes_per_glyph = read_u32(20) as usize;
+    let height = read_u32(24) as usize;
+    let width = read_u32(28) as usize;
+    let row_bytes = (width + 7) / 8;
+
+    let glyphs_start = header_size;
+    let glyphs_end = glyphs_start + num_glyphs * bytes_per_glyph;
+    let glyph_bytes = bytes.get(glyphs_start..glyphs_end).ok_or(PsfError::TruncatedGlyphs)?;
+    let glyphs = glyph_bytes
+        .chunks(bytes_per_glyph)
+        .map(|glyph| glyph[..height * row_bytes].to_vec())
+        .collect();
+
+    let has_unicode_table = flags & 0x01 != 0;
+    let unicode = has_unicode_table.then(|| parse_psf2_unicode_table(&bytes[glyphs_end..]));
+
+    Ok(PsfFont {
+        width,
+        height,
+        row_bytes,
+        glyphs,
+        unicode,
+    })
+}
+
+/// PSF2's unicode table is UTF-8 text: `0xFF` closes out the current
+/// glyph's entries and moves to the next glyph, `0xFE` opens a
+/// combining-character sequence (its codepoints are skipped, none of
+/// them is mapped), anything else is a UTF-8 codepoint mapped onto the
+/// current glyph.
+fn parse_psf2_unicode_table(bytes: &[u8]) -> Vec<(u32, usize)> {
+    let mut mapping = Vec::new();
+    let mut glyph_index = 0;
+    let mut in_sequence = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            0xff => {
+                glyph_index += 1;
+                in_sequence = false;
+                i += 1;
+            }
+            0xfe => {
+                in_sequence = true;
+                i += 1;
+            }
+            _ => {
+                // 0xFE/0xFF are never valid UTF-8, so validation of the
+                // rest of the buffer always stops there; decode just the
+                // valid prefix rather than requiring everything after
+                // `i` to be valid UTF-8 too.
+                let valid = match std::str::from_utf8(&bytes[i..]) {
+                    Ok(s) => s,
+                    Err(e) => std::str::from_utf8(&bytes[i..i + e.valid_up_to()]).unwrap(),
+                };
+                match valid.chars().next() {
+                    Some(c) => {
+                        if !in_sequence {
+                            mapping.push((c as u32, glyph_index));
+                        }
+                        i += c.len_utf8();
+                    }
+                    None => i += 1,
+                }
+            }
+        }
+    }
+    mapping
+}
+
+/// Parse `path` as a PSF1/PSF2 font and write it out in the same
+/// `FONT_H`/`FONT_W`/`ASCII_FONT`/`EXT_FONT` shape the text-format path
+/// produces, so `graphics::font` doesn't need to know which source the
+/// active font came from.
+fn load_psf_font(out: &mut impl Write, path: &Path) -> Result<()> {
+    let bytes = std::fs::read(path)?;
+    let psf = parse_psf(&bytes).map_err(|e| -> Box<dyn std::error::Error> { Box::new(e) })?;
+
+    if psf.width != Line::ASCII_WIDTH && env::var("CARGO_FEATURE_WIDE_FONT").is_err() {
+        return Err(Box::new(PsfError::WidthRequiresFeature(psf.width)));
+    }
+    if psf.glyphs.len() < 256 {
+        return Err(Box::new(PsfError::TooFewGlyphs(psf.glyphs.len())));
+    }
+
+    writeln!(out, "pub(crate) const FONT_H: usize = {};", psf.height)?;
+    writeln!(out, "pub(crate) const FONT_W: usize = {};", psf.width)?;
+    writeln!(out, "pub(crate) const FONT_ROW_BYTES: usize = {};", psf.row_bytes)?;
+
+    let glyph_len = psf.height * psf.row_bytes;
+    let ascii_entries: Vec<AsciiFontEntry> = psf
+        .glyphs
+        .iter()
+        .take(256)
+        .enumerate()
+        .map(|(code, rows)| AsciiFontEntry {
+            code: code as u8,
+            rows: rows.clone(),
+        })
+        .collect();
+    write_ascii_fonts(out, &ascii_entries, glyph_len)?;
+
+    // Glyphs past the base codepage are only reachable through the
+    // unicode table, so they surface as EXT_FONT entries keyed by
+    // whatever codepoint the table maps onto them.
+    let mut ext_entries: Vec<(u32, Vec<u8>)> = psf
+        .unicode
+        .iter()
+        .flatten()
+        .filter(|&&(_, glyph_index)| glyph_index >= 256)
+        .filter_map(|&(cp, glyph_index)| psf.glyphs.get(glyph_index).map(|rows| (cp, rows.clone())))
+        .collect();
+    ext_entries.sort_unstable_by_key(|&(cp, _)| cp);
+    ext_entries.dedup_by_key(|&mut (cp, _)| cp);
+    write_extended_fonts(out, &ext_entries, glyph_len)?;
+
+    Ok(())
+}
+
+fn load_text_fonts(out: &mut impl Write) -> Result<()> {
+    writeln!(out, "pub(crate) const FONT_H: usize = {};", Line::ASCII_HEIGHT)?;
+    writeln!(out, "pub(crate) const FONT_W: usize = {};", Line::ASCII_WIDTH)?;
+    writeln!(out, "pub(crate) const FONT_ROW_BYTES: usize = 1;")?;
+
+    let src = OpenOptions::new().read(true).open(SRC)?;
+    let ascii_entries = parse_ascii_fonts(SRC, BufReader::new(src))?;
+    write_ascii_fonts(out, &ascii_entries, Line::ASCII_HEIGHT)?;
+
+    let ext_entries = if Path::new(EXT_SRC).exists() {
+        println!("cargo:rerun-if-changed={}", EXT_SRC);
+        let ext_src = OpenOptions::new().read(true).open(EXT_SRC)?;
+        parse_extended_fonts(EXT_SRC, BufReader::new(ext_src))?
+    } else {
+        Vec::new()
+    };
+    write_extended_fonts(out, &ext_entries, Line::ASCII_HEIGHT)?;
+
+    Ok(())
+}
+
 fn load_fonts() -> Result<()> {
     let out_dir = env::var("OUT_DIR")?;
     let out_path = Path::new(&out_dir).join(OUT);
@@ -92,66 +527,82 @@ fn load_fonts() -> Result<()> {
         .truncate(true)
         .open(&out_path)?;
 
-    let src = OpenOptions::new().read(true).open(SRC)?;
-    let mut lines = BufReader::new(src).lines();
-
-    let mut remains = HashSet::new();
-    for c in u8::MIN..=u8::MAX {
-        let _ = remains.insert(c);
-    }
-
     writeln!(
         &mut out,
         "// This is auto generated module and do not modify."
     )?;
     writeln!(&mut out, "#[allow(dead_code)]")?;
-    writeln!(
-        &mut out,
-        "pub(crate) const FONT_H: usize = {};",
-        Line::ASCII_HEIGHT
-    )?;
-    writeln!(
-        &mut out,
-        "pub(crate) const FONT_W: usize = {};",
-        Line::ASCII_WIDTH
-    )?;
 
-    writeln!(
-        &mut out,
-        "pub(crate) const ASCII_FONT: [[u8; {}]; 256] = [",
-        Line::ASCII_HEIGHT
-    )?;
+    match PSF_SRC_CANDIDATES.iter().map(Path::new).find(|p| p.exists()) {
+        Some(psf_path) => {
+            println!("cargo:rerun-if-changed={}", psf_path.display());
+            load_psf_font(&mut out, psf_path)?;
+        }
+        None => load_text_fonts(&mut out)?,
+    }
 
-    while let Some(line) = lines.next() {
-        let line = line?;
-        match Line::from_str(&line)? {
-            Some(Line::Definition(c)) => {
-                if !remains.remove(&c) {
-                    panic!("duplicating font definition for {:#02x} found", c);
-                }
-                let mut font = [0; Line::ASCII_HEIGHT];
-                for f in font.iter_mut() {
-                    let line = lines.next().expect("insufficient lines provided.")?;
-                    match Line::from_str(&line)? {
-                        Some(Line::Body(layout)) => *f = layout,
-                        l => panic!("unexpected line: {:?}", l),
-                    }
-                }
+    Ok(())
+}
 
-                // ensure font written in binary style
-                writeln!(&mut out, "\t// {:#08x}", c)?;
-                writeln!(&mut out, "\t[")?;
-                for &layout in font.iter() {
-                    writeln!(&mut out, "\t\t{:#010b},", layout)?;
-                }
-                writeln!(&mut out, "\t],")?;
-            }
-            Some(l) => panic!("invalid format found near: {:?}", l),
-            None => continue,
+/// Parses `assets/pci_vendors.txt` (same two-column shape as upstream
+/// pci.ids' vendor lines: a 4-hex-digit ID, a tab, then the name) into
+/// `(id, name)` pairs sorted by ID, so the generated table can be
+/// binary-searched at runtime. Reports the offending 1-based line number
+/// the same way the font parsers above do.
+fn parse_pci_vendors<R: BufRead>(file: &'static str, reader: R) -> Result<Vec<(u16, String)>> {
+    let mut entries = Vec::new();
+    let mut seen = HashSet::new();
+
+    for (line_no, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| locate(file, line_no, e))?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
         }
+
+        let (id, name) = line
+            .split_once('\t')
+            .ok_or_else(|| locate(file, line_no, PciNameError::MissingSeparator(line.into())))?;
+        if id.len() != 4 {
+            return Err(locate(file, line_no, PciNameError::BadVendorId(id.into())));
+        }
+        let id = u16::from_str_radix(id, 16).map_err(|e| locate(file, line_no, e))?;
+        if !seen.insert(id) {
+            return Err(locate(file, line_no, PciNameError::DuplicateVendorId(id)));
+        }
+        entries.push((id, name.to_string()));
+    }
+
+    entries.sort_unstable_by_key(|&(id, _)| id);
+    Ok(entries)
+}
+
+fn write_pci_vendors(out: &mut impl Write, entries: &[(u16, String)]) -> Result<()> {
+    writeln!(out, "pub(crate) const PCI_VENDOR_NAMES: &[(u16, &str)] = &[")?;
+    for (id, name) in entries {
+        writeln!(out, "\t({:#06x}, {:?}),", id, name)?;
     }
-    assert!(remains.is_empty());
-    writeln!(&mut out, "];")?;
+    writeln!(out, "];")?;
+    Ok(())
+}
+
+fn load_pci_names() -> Result<()> {
+    println!("cargo:rerun-if-changed={}", PCI_VENDORS_SRC);
+
+    let out_dir = env::var("OUT_DIR")?;
+    let out_path = Path::new(&out_dir).join(PCI_VENDORS_OUT);
+    let mut out = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&out_path)?;
+
+    writeln!(&mut out, "// This is auto generated module and do not modify.")?;
+
+    let src = OpenOptions::new().read(true).open(PCI_VENDORS_SRC)?;
+    let entries = parse_pci_vendors(PCI_VENDORS_SRC, BufReader::new(src))?;
+    write_pci_vendors(&mut out, &entries)?;
+
     Ok(())
 }
 
@@ -180,7 +631,218 @@ fn build_asm() -> Result<()> {
     Ok(())
 }
 
-fn main() -> Result<()> {
+fn run() -> Result<()> {
     load_fonts()?;
+    load_pci_names()?;
     build_asm()
 }
+
+fn main() {
+    if let Err(e) = run() {
+        println!("cargo:warning={}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn parse_vendors(text: &str) -> Result<Vec<(u16, String)>> {
+        parse_pci_vendors(TEST_FILE, Cursor::new(text.as_bytes()))
+    }
+
+    #[test]
+    fn sorts_vendors_by_id() {
+        let text = "10de\tNVIDIA Corporation\n8086\tIntel Corporation\n";
+        let entries = parse_vendors(text).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                (0x10de, "NVIDIA Corporation".to_string()),
+                (0x8086, "Intel Corporation".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_blank_lines_and_comments() {
+        let text = "# comment\n\n8086\tIntel Corporation\n";
+        let entries = parse_vendors(text).unwrap();
+        assert_eq!(entries, vec![(0x8086, "Intel Corporation".to_string())]);
+    }
+
+    #[test]
+    fn rejects_lines_without_a_tab() {
+        let err = parse_vendors("8086 Intel Corporation\n").unwrap_err();
+        assert!(err.to_string().contains("no tab"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_vendor_ids_that_arent_four_hex_digits() {
+        let err = parse_vendors("808\tIntel Corporation\n").unwrap_err();
+        assert!(err.to_string().contains("isn't 4 hex digits"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_duplicate_vendor_ids() {
+        let text = "8086\tIntel Corporation\n8086\tIntel Corp (dup)\n";
+        let err = parse_vendors(text).unwrap_err();
+        assert!(err.to_string().contains("duplicate entry"), "{}", err);
+    }
+
+    const TEST_FILE: &str = "test.txt";
+    const GLYPH: &str = "\
+........
+........
+........
+........
+........
+........
+........
+........
+........
+........
+........
+........
+........
+........
+........
+........
+";
+
+    fn parse_ascii(text: &str) -> Result<Vec<AsciiFontEntry>> {
+        parse_ascii_fonts(TEST_FILE, Cursor::new(text.as_bytes()))
+    }
+
+    fn parse_ext(text: &str) -> Result<Vec<(u32, Vec<u8>)>> {
+        parse_extended_fonts(TEST_FILE, Cursor::new(text.as_bytes()))
+    }
+
+    #[test]
+    fn ascii_rejects_duplicate_definitions() {
+        let text = format!("0x00\n{}\n0x00\n{}", GLYPH, GLYPH);
+        let err = parse_ascii(&text).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("duplicate font definition"), "{}", msg);
+        // second "0x00" header is line 19 (1-based): header (1) + 16
+        // glyph rows + 1 blank separator line before it.
+        assert!(msg.starts_with("test.txt:19:"), "{}", msg);
+    }
+
+    #[test]
+    fn ascii_rejects_missing_glyph_rows() {
+        let text = "0x00\n........\n........\n";
+        let err = parse_ascii(text).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("ran out of lines"), "{}", msg);
+    }
+
+    #[test]
+    fn ascii_rejects_malformed_body_rows() {
+        let text = "0x00\nXXXXXXXX\n";
+        let err = parse_ascii(text).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("Unexpected char"), "{}", msg);
+        assert!(msg.starts_with("test.txt:2:"), "{}", msg);
+    }
+
+    #[test]
+    fn parses_a_single_codepoint_block() {
+        let text = format!("U+251C\n{}", GLYPH);
+        let entries = parse_ext(&text).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, 0x251c);
+    }
+
+    #[test]
+    fn sorts_entries_by_codepoint() {
+        let text = format!("U+00E9\n{}\nU+251C\n{}", GLYPH, GLYPH);
+        let entries = parse_ext(&text).unwrap();
+        assert_eq!(
+            entries.iter().map(|&(cp, _)| cp).collect::<Vec<_>>(),
+            vec![0xe9, 0x251c]
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_codepoints() {
+        let text = format!("U+251C\n{}\nU+251C\n{}", GLYPH, GLYPH);
+        let err = parse_ext(&text).unwrap_err();
+        assert!(err.to_string().contains("duplicate codepoint"));
+    }
+
+    #[test]
+    fn rejects_missing_glyph_rows() {
+        let text = "U+251C\n........\n........\n";
+        let err = parse_ext(text).unwrap_err();
+        assert!(err.to_string().contains("missing glyph rows"));
+    }
+
+    #[test]
+    fn rejects_malformed_body_rows() {
+        let text = "U+251C\nXXXXXXXX\n";
+        let err = parse_ext(text).unwrap_err();
+        assert!(err.to_string().contains("Unexpected char"));
+    }
+
+    #[test]
+    fn psf_rejects_bad_magic() {
+        let err = parse_psf(&[0, 0, 0, 0]).unwrap_err();
+        assert!(matches!(err, PsfError::BadMagic));
+    }
+
+    #[test]
+    fn parses_psf1_header_and_unicode_table() {
+        let mode = 0x02; // has a unicode table
+        let height = 1u8;
+        let mut bytes = vec![PSF1_MAGIC[0], PSF1_MAGIC[1], mode, height];
+        bytes.extend(std::iter::repeat(0u8).take(256)); // 256 one-byte glyphs
+        bytes.extend([0x41, 0x00, 0xff, 0xff]); // glyph 0 -> U+0041, then next glyph
+        let psf = parse_psf(&bytes).unwrap();
+
+        assert_eq!(psf.width, 8);
+        assert_eq!(psf.height, 1);
+        assert_eq!(psf.glyphs.len(), 256);
+        assert_eq!(psf.unicode.unwrap(), vec![(0x41, 0)]);
+    }
+
+    #[test]
+    fn parses_psf2_header_and_unicode_table() {
+        let num_glyphs = 256u32;
+        let bytes_per_glyph = 1u32;
+        let height = 1u32;
+        let width = 8u32;
+        let header_size = 32u32;
+        let flags = 0x01u32; // has a unicode table
+
+        let mut bytes = Vec::new();
+        bytes.extend(PSF2_MAGIC);
+        bytes.extend(0u32.to_le_bytes()); // version
+        bytes.extend(header_size.to_le_bytes());
+        bytes.extend(flags.to_le_bytes());
+        bytes.extend(num_glyphs.to_le_bytes());
+        bytes.extend(bytes_per_glyph.to_le_bytes());
+        bytes.extend(height.to_le_bytes());
+        bytes.extend(width.to_le_bytes());
+        bytes.extend(std::iter::repeat(0u8).take(256));
+        bytes.extend("A".as_bytes());
+        bytes.push(0xff); // end of glyph 0's entries
+
+        let psf = parse_psf(&bytes).unwrap();
+
+        assert_eq!(psf.width, 8);
+        assert_eq!(psf.row_bytes, 1);
+        assert_eq!(psf.glyphs.len(), 256);
+        assert_eq!(psf.unicode.unwrap(), vec![('A' as u32, 0)]);
+    }
+
+    #[test]
+    fn psf_rejects_truncated_glyphs() {
+        let mut bytes = vec![PSF1_MAGIC[0], PSF1_MAGIC[1], 0, 16];
+        bytes.extend(std::iter::repeat(0u8).take(10)); // far short of 256*16
+        let err = parse_psf(&bytes).unwrap_err();
+        assert!(matches!(err, PsfError::TruncatedGlyphs));
+    }
+}
@@ -0,0 +1,273 @@
+//! Multiple APIC Description Table (ACPI spec §5.2.12): every Local
+//! APIC, I/O APIC, and interrupt source override the platform reports.
+//!
+//! Nothing in this tree reads any of this yet -- `interrupts.rs`'s own
+//! module doc already says there's no IDT/APIC wiring here at all, so
+//! there's no hard-coded IOAPIC address for this to replace and no
+//! `interrupts::setup_handler` for it to feed. This is the standalone
+//! parser such a handler would read from once it exists.
+
+use super::sdt::{verify_checksum, AcpiError, Result, SdtHeader};
+
+pub const MAX_LOCAL_APICS: usize = 16;
+pub const MAX_IO_APICS: usize = 4;
+pub const MAX_INTERRUPT_OVERRIDES: usize = 16;
+
+const TYPE_LOCAL_APIC: u8 = 0;
+const TYPE_IO_APIC: u8 = 1;
+const TYPE_INTERRUPT_SOURCE_OVERRIDE: u8 = 2;
+const TYPE_LOCAL_APIC_ADDRESS_OVERRIDE: u8 = 5;
+
+const ENTRY_HEADER_LEN: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalApicEntry {
+    pub processor_id: u8,
+    pub apic_id: u8,
+    /// Whether the processor is usable -- firmware lists disabled cores
+    /// here too, still occupying an entry (ACPI spec §5.2.12.2).
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IoApicEntry {
+    pub id: u8,
+    pub address: u32,
+    /// First Global System Interrupt this I/O APIC handles; its other
+    /// inputs are `gsi_base + 0..redirection_entry_count`.
+    pub gsi_base: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterruptSourceOverride {
+    pub bus_source: u8,
+    pub irq_source: u8,
+    pub gsi: u32,
+}
+
+/// Parsed MADT contents, bounded the same way [`crate::devices::pci::PciDeviceService`]
+/// bounds its device list -- there's no heap in this kernel to grow these
+/// into, and real hardware never comes close to these counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Madt {
+    /// The Local APIC's physical base address -- the fixed field's
+    /// value, unless a Local APIC Address Override entry replaced it
+    /// (ACPI spec §5.2.12.5). That entry's address is 64-bit; only its
+    /// low 32 bits are kept here.
+    pub local_apic_addr: u32,
+    local_apics: [Option<LocalApicEntry>; MAX_LOCAL_APICS],
+    local_apic_count: usize,
+    io_apics: [Option<IoApicEntry>; MAX_IO_APICS],
+    io_apic_count: usize,
+    overrides: [Option<InterruptSourceOverride>; MAX_INTERRUPT_OVERRIDES],
+    override_count: usize,
+}
+
+impl Madt {
+    const FIXED_FIELDS_LEN: usize = 8; // local_apic_addr: u32, flags: u32
+    const ENTRIES_OFFSET: usize = SdtHeader::LEN + Self::FIXED_FIELDS_LEN;
+
+    /// Parses and checksum-validates a MADT (signature `"APIC"`) out of
+    /// `bytes`, which must be the whole table (header included).
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        verify_checksum(bytes)?;
+        let header = SdtHeader::parse(bytes)?;
+        if &header.signature != b"APIC" {
+            return Err(AcpiError::UnexpectedSignature);
+        }
+        if bytes.len() < Self::ENTRIES_OFFSET {
+            return Err(AcpiError::TooShort);
+        }
+
+        let mut madt = Self {
+            local_apic_addr: u32::from_le_bytes(
+                bytes[SdtHeader::LEN..SdtHeader::LEN + 4].try_into().unwrap(),
+            ),
+            local_apics: [None; MAX_LOCAL_APICS],
+            local_apic_count: 0,
+            io_apics: [None; MAX_IO_APICS],
+            io_apic_count: 0,
+            overrides: [None; MAX_INTERRUPT_OVERRIDES],
+            override_count: 0,
+        };
+
+        let mut offset = Self::ENTRIES_OFFSET;
+        while offset + ENTRY_HEADER_LEN <= bytes.len() {
+            let entry_type = bytes[offset];
+            let entry_len = bytes[offset + 1] as usize;
+            if entry_len < ENTRY_HEADER_LEN || offset + entry_len > bytes.len() {
+                return Err(AcpiError::TooShort);
+            }
+            let entry = &bytes[offset..offset + entry_len];
+
+            match entry_type {
+                TYPE_LOCAL_APIC if entry.len() >= 8 => {
+                    madt.push_local_apic(LocalApicEntry {
+                        processor_id: entry[2],
+                        apic_id: entry[3],
+                        enabled: entry[4] & 0x1 != 0,
+                    });
+                }
+                TYPE_IO_APIC if entry.len() >= 12 => {
+                    madt.push_io_apic(IoApicEntry {
+                        id: entry[2],
+                        address: u32::from_le_bytes(entry[4..8].try_into().unwrap()),
+                        gsi_base: u32::from_le_bytes(entry[8..12].try_into().unwrap()),
+                    });
+                }
+                TYPE_INTERRUPT_SOURCE_OVERRIDE if entry.len() >= 10 => {
+                    madt.push_override(InterruptSourceOverride {
+                        bus_source: entry[2],
+                        irq_source: entry[3],
+                        gsi: u32::from_le_bytes(entry[4..8].try_into().unwrap()),
+                    });
+                }
+                TYPE_LOCAL_APIC_ADDRESS_OVERRIDE if entry.len() >= 12 => {
+                    madt.local_apic_addr =
+                        u64::from_le_bytes(entry[4..12].try_into().unwrap()) as u32;
+                }
+                other => {
+                    log::debug!("acpi: skipping unknown MADT entry type {:#x}", other);
+                }
+            }
+
+            offset += entry_len;
+        }
+
+        Ok(madt)
+    }
+
+    fn push_local_apic(&mut self, entry: LocalApicEntry) {
+        if self.local_apic_count < self.local_apics.len() {
+            self.local_apics[self.local_apic_count] = Some(entry);
+            self.local_apic_count += 1;
+        }
+    }
+
+    fn push_io_apic(&mut self, entry: IoApicEntry) {
+        if self.io_apic_count < self.io_apics.len() {
+            self.io_apics[self.io_apic_count] = Some(entry);
+            self.io_apic_count += 1;
+        }
+    }
+
+    fn push_override(&mut self, entry: InterruptSourceOverride) {
+        if self.override_count < self.overrides.len() {
+            self.overrides[self.override_count] = Some(entry);
+            self.override_count += 1;
+        }
+    }
+
+    pub fn local_apics(&self) -> &[Option<LocalApicEntry>] {
+        &self.local_apics[0..self.local_apic_count]
+    }
+
+    pub fn io_apics(&self) -> &[Option<IoApicEntry>] {
+        &self.io_apics[0..self.io_apic_count]
+    }
+
+    pub fn interrupt_source_overrides(&self) -> &[Option<InterruptSourceOverride>] {
+        &self.overrides[0..self.override_count]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_entry(bytes: &mut std::vec::Vec<u8>, entry_type: u8, data: &[u8]) {
+        bytes.push(entry_type);
+        bytes.push((ENTRY_HEADER_LEN + data.len()) as u8);
+        bytes.extend_from_slice(data);
+    }
+
+    fn madt_bytes(entries: impl FnOnce(&mut std::vec::Vec<u8>)) -> std::vec::Vec<u8> {
+        let mut bytes = std::vec![0u8; SdtHeader::LEN];
+        bytes[0..4].copy_from_slice(b"APIC");
+        bytes.extend_from_slice(&0xfee0_0000u32.to_le_bytes()); // local_apic_addr
+        bytes.extend_from_slice(&0u32.to_le_bytes()); // flags
+        entries(&mut bytes);
+
+        let length = bytes.len() as u32;
+        bytes[4..8].copy_from_slice(&length.to_le_bytes());
+
+        bytes[9] = 0;
+        let sum = bytes.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        bytes[9] = 0u8.wrapping_sub(sum);
+        bytes
+    }
+
+    #[test]
+    fn parses_local_apic_entries() {
+        let bytes = madt_bytes(|b| {
+            push_entry(b, TYPE_LOCAL_APIC, &[0, 1, 1, 0, 0, 0]);
+            push_entry(b, TYPE_LOCAL_APIC, &[1, 2, 0, 0, 0, 0]);
+        });
+        let madt = Madt::parse(&bytes).unwrap();
+        assert_eq!(
+            madt.local_apics(),
+            &[
+                Some(LocalApicEntry { processor_id: 0, apic_id: 1, enabled: true }),
+                Some(LocalApicEntry { processor_id: 1, apic_id: 2, enabled: false }),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_io_apic_entries_with_gsi_base() {
+        let mut data = std::vec![1u8, 0]; // id, reserved
+        data.extend_from_slice(&0xfec0_0000u32.to_le_bytes());
+        data.extend_from_slice(&24u32.to_le_bytes());
+        let bytes = madt_bytes(|b| push_entry(b, TYPE_IO_APIC, &data));
+
+        let madt = Madt::parse(&bytes).unwrap();
+        assert_eq!(
+            madt.io_apics(),
+            &[Some(IoApicEntry { id: 1, address: 0xfec0_0000, gsi_base: 24 })]
+        );
+    }
+
+    #[test]
+    fn parses_interrupt_source_overrides() {
+        let mut data = std::vec![0u8, 4]; // bus_source, irq_source
+        data.extend_from_slice(&4u32.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes()); // flags
+        let bytes = madt_bytes(|b| push_entry(b, TYPE_INTERRUPT_SOURCE_OVERRIDE, &data));
+
+        let madt = Madt::parse(&bytes).unwrap();
+        assert_eq!(
+            madt.interrupt_source_overrides(),
+            &[Some(InterruptSourceOverride { bus_source: 0, irq_source: 4, gsi: 4 })]
+        );
+    }
+
+    #[test]
+    fn local_apic_address_override_replaces_the_fixed_field() {
+        let mut data = std::vec![0u8, 0]; // reserved
+        data.extend_from_slice(&0x1_fee0_1000u64.to_le_bytes());
+        let bytes = madt_bytes(|b| push_entry(b, TYPE_LOCAL_APIC_ADDRESS_OVERRIDE, &data));
+
+        let madt = Madt::parse(&bytes).unwrap();
+        assert_eq!(madt.local_apic_addr, 0xfee0_1000); // truncated, as documented
+    }
+
+    #[test]
+    fn unknown_entry_types_are_skipped_without_failing_the_parse() {
+        let bytes = madt_bytes(|b| {
+            push_entry(b, 0xaa, &[1, 2, 3, 4]);
+            push_entry(b, TYPE_LOCAL_APIC, &[0, 1, 1, 0, 0, 0]);
+        });
+        let madt = Madt::parse(&bytes).unwrap();
+        assert_eq!(madt.local_apics().len(), 1);
+    }
+
+    #[test]
+    fn rejects_a_non_apic_signature() {
+        let mut bytes = madt_bytes(|_| {});
+        bytes[0..4].copy_from_slice(b"XXXX");
+        bytes[9] = 0;
+        let sum = bytes.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        bytes[9] = 0u8.wrapping_sub(sum);
+        assert_eq!(Madt::parse(&bytes), Err(AcpiError::UnexpectedSignature));
+    }
+}
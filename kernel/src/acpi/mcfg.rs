@@ -0,0 +1,151 @@
+//! Memory-mapped Configuration Space table (PCI Firmware spec §4): the
+//! ECAM segments a platform exposes for accessing PCI configuration
+//! space by memory-mapped address instead of the legacy 0xCF8/0xCFC I/O
+//! ports.
+//!
+//! `devices::pci::common::PciConfig::read`/`write` only ever do that
+//! legacy port I/O -- there's no ECAM path in this tree for this to feed
+//! yet. This is the standalone parser a real ECAM-based `PciConfig`
+//! would read segments from once one exists.
+
+use super::sdt::{verify_checksum, AcpiError, Result, SdtHeader};
+
+pub const MAX_SEGMENTS: usize = 8;
+
+const RESERVED_LEN: usize = 8;
+const ALLOCATION_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EcamSegment {
+    pub base_address: u64,
+    pub segment_group: u16,
+    pub start_bus: u8,
+    pub end_bus: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mcfg {
+    segments: [Option<EcamSegment>; MAX_SEGMENTS],
+    count: usize,
+}
+
+impl Mcfg {
+    const ALLOCATIONS_OFFSET: usize = SdtHeader::LEN + RESERVED_LEN;
+
+    /// Parses and checksum-validates an MCFG (signature `"MCFG"`) out of
+    /// `bytes`, which must be the whole table (header included).
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        verify_checksum(bytes)?;
+        let header = SdtHeader::parse(bytes)?;
+        if &header.signature != b"MCFG" {
+            return Err(AcpiError::UnexpectedSignature);
+        }
+        if bytes.len() < Self::ALLOCATIONS_OFFSET {
+            return Err(AcpiError::TooShort);
+        }
+
+        let mut mcfg = Self {
+            segments: [None; MAX_SEGMENTS],
+            count: 0,
+        };
+
+        let mut offset = Self::ALLOCATIONS_OFFSET;
+        while offset + ALLOCATION_LEN <= bytes.len() {
+            let allocation = &bytes[offset..offset + ALLOCATION_LEN];
+            mcfg.push(EcamSegment {
+                base_address: u64::from_le_bytes(allocation[0..8].try_into().unwrap()),
+                segment_group: u16::from_le_bytes(allocation[8..10].try_into().unwrap()),
+                start_bus: allocation[10],
+                end_bus: allocation[11],
+            });
+            offset += ALLOCATION_LEN;
+        }
+
+        Ok(mcfg)
+    }
+
+    fn push(&mut self, segment: EcamSegment) {
+        if self.count < self.segments.len() {
+            self.segments[self.count] = Some(segment);
+            self.count += 1;
+        }
+    }
+
+    pub fn segments(&self) -> &[Option<EcamSegment>] {
+        &self.segments[0..self.count]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mcfg_bytes(segments: &[EcamSegment]) -> std::vec::Vec<u8> {
+        let mut bytes = std::vec![0u8; SdtHeader::LEN];
+        bytes[0..4].copy_from_slice(b"MCFG");
+        bytes.extend_from_slice(&[0u8; RESERVED_LEN]);
+        for segment in segments {
+            bytes.extend_from_slice(&segment.base_address.to_le_bytes());
+            bytes.extend_from_slice(&segment.segment_group.to_le_bytes());
+            bytes.push(segment.start_bus);
+            bytes.push(segment.end_bus);
+            bytes.extend_from_slice(&[0u8; 4]); // reserved
+        }
+
+        let length = bytes.len() as u32;
+        bytes[4..8].copy_from_slice(&length.to_le_bytes());
+
+        bytes[9] = 0;
+        let sum = bytes.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        bytes[9] = 0u8.wrapping_sub(sum);
+        bytes
+    }
+
+    #[test]
+    fn parses_a_single_ecam_segment() {
+        let segment = EcamSegment {
+            base_address: 0xe000_0000,
+            segment_group: 0,
+            start_bus: 0,
+            end_bus: 255,
+        };
+        let bytes = mcfg_bytes(&[segment]);
+        let mcfg = Mcfg::parse(&bytes).unwrap();
+        assert_eq!(mcfg.segments(), &[Some(segment)]);
+    }
+
+    #[test]
+    fn parses_multiple_segment_groups() {
+        let segments = [
+            EcamSegment { base_address: 0xe000_0000, segment_group: 0, start_bus: 0, end_bus: 255 },
+            EcamSegment { base_address: 0xf000_0000, segment_group: 1, start_bus: 0, end_bus: 127 },
+        ];
+        let bytes = mcfg_bytes(&segments);
+        let mcfg = Mcfg::parse(&bytes).unwrap();
+        assert_eq!(mcfg.segments(), &[Some(segments[0]), Some(segments[1])]);
+    }
+
+    #[test]
+    fn an_empty_table_parses_with_no_segments() {
+        let bytes = mcfg_bytes(&[]);
+        let mcfg = Mcfg::parse(&bytes).unwrap();
+        assert!(mcfg.segments().is_empty());
+    }
+
+    #[test]
+    fn rejects_a_non_mcfg_signature() {
+        let mut bytes = mcfg_bytes(&[]);
+        bytes[0..4].copy_from_slice(b"XXXX");
+        bytes[9] = 0;
+        let sum = bytes.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        bytes[9] = 0u8.wrapping_sub(sum);
+        assert_eq!(Mcfg::parse(&bytes), Err(AcpiError::UnexpectedSignature));
+    }
+
+    #[test]
+    fn rejects_a_bad_checksum() {
+        let mut bytes = mcfg_bytes(&[]);
+        bytes[20] ^= 0xff;
+        assert_eq!(Mcfg::parse(&bytes), Err(AcpiError::BadChecksum));
+    }
+}
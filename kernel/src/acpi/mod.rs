@@ -0,0 +1,139 @@
+//! ACPI table discovery.
+//!
+//! Parses and checksum-validates the RSDP/XSDT, then dispatches each
+//! table it finds to the submodule that knows its signature (MADT,
+//! MCFG). Unknown signatures are skipped, logged at debug level, rather
+//! than failing the walk -- most ACPI tables aren't ones this kernel has
+//! any use for yet.
+//!
+//! Nothing calls any of this from `services::init` yet: there's no RSDP
+//! handoff from the loader (see [`rsdp`]'s module doc), no APIC code for
+//! [`Madt`] to feed (see [`madt`]'s), and no ECAM-based `PciConfig` for
+//! [`Mcfg`] to feed (see [`mcfg`]'s). This is standalone, host-tested
+//! parsing logic ready for all three once they exist.
+
+pub mod madt;
+pub mod mcfg;
+pub mod rsdp;
+pub mod sdt;
+
+pub use madt::Madt;
+pub use mcfg::Mcfg;
+pub use rsdp::{Rsdp, RsdpVersion};
+pub use sdt::{AcpiError, SdtHeader};
+
+/// XSDT (ACPI spec §5.2.8): the 64-bit-pointer table list an ACPI 2.0+
+/// [`Rsdp`] points at. Each entry is a physical address of another
+/// table's [`SdtHeader`]-prefixed bytes, to be handed to [`parse_table`]
+/// once something can turn that physical address into a byte slice (see
+/// [`rsdp`]'s module doc for why that boundary doesn't exist in this
+/// tree yet).
+pub struct Xsdt<'a> {
+    entries: &'a [u8],
+}
+
+impl<'a> Xsdt<'a> {
+    /// Parses and checksum-validates an XSDT (signature `"XSDT"`) out of
+    /// `bytes`, which must be the whole table (header included).
+    pub fn parse(bytes: &'a [u8]) -> sdt::Result<Self> {
+        sdt::verify_checksum(bytes)?;
+        let header = SdtHeader::parse(bytes)?;
+        if &header.signature != b"XSDT" {
+            return Err(AcpiError::UnexpectedSignature);
+        }
+        Ok(Self {
+            entries: &bytes[SdtHeader::LEN..],
+        })
+    }
+
+    pub fn table_count(&self) -> usize {
+        self.entries.len() / 8
+    }
+
+    /// The physical address of the `index`th table, or `None` if there
+    /// isn't one.
+    pub fn table_addr(&self, index: usize) -> Option<u64> {
+        let start = index.checked_mul(8)?;
+        let chunk = self.entries.get(start..start + 8)?;
+        Some(u64::from_le_bytes(chunk.try_into().unwrap()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParsedTable {
+    Madt(Madt),
+    Mcfg(Mcfg),
+}
+
+/// Parses a single ACPI table (checksum-validated header and body) into
+/// whichever [`ParsedTable`] variant its signature names, or returns
+/// `None` -- logging the raw signature at debug level -- for a table
+/// this kernel doesn't know yet.
+pub fn parse_table(bytes: &[u8]) -> sdt::Result<Option<ParsedTable>> {
+    sdt::verify_checksum(bytes)?;
+    let header = SdtHeader::parse(bytes)?;
+    match &header.signature {
+        b"APIC" => Ok(Some(ParsedTable::Madt(Madt::parse(bytes)?))),
+        b"MCFG" => Ok(Some(ParsedTable::Mcfg(Mcfg::parse(bytes)?))),
+        _ => {
+            log::debug!("acpi: skipping unknown table {:?}", header.signature_str());
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xsdt_bytes(table_addrs: &[u64]) -> std::vec::Vec<u8> {
+        let mut bytes = std::vec![0u8; SdtHeader::LEN];
+        bytes[0..4].copy_from_slice(b"XSDT");
+        for addr in table_addrs {
+            bytes.extend_from_slice(&addr.to_le_bytes());
+        }
+
+        let length = bytes.len() as u32;
+        bytes[4..8].copy_from_slice(&length.to_le_bytes());
+
+        bytes[9] = 0;
+        let sum = bytes.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        bytes[9] = 0u8.wrapping_sub(sum);
+        bytes
+    }
+
+    #[test]
+    fn xsdt_lists_each_table_pointer() {
+        let bytes = xsdt_bytes(&[0x1000, 0x2000, 0x3000]);
+        let xsdt = Xsdt::parse(&bytes).unwrap();
+        assert_eq!(xsdt.table_count(), 3);
+        assert_eq!(xsdt.table_addr(0), Some(0x1000));
+        assert_eq!(xsdt.table_addr(2), Some(0x3000));
+        assert_eq!(xsdt.table_addr(3), None);
+    }
+
+    #[test]
+    fn parse_table_dispatches_a_madt_by_signature() {
+        let mut bytes = std::vec![0u8; SdtHeader::LEN + 8];
+        bytes[0..4].copy_from_slice(b"APIC");
+        let length = bytes.len() as u32;
+        bytes[4..8].copy_from_slice(&length.to_le_bytes());
+        bytes[9] = 0;
+        let sum = bytes.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        bytes[9] = 0u8.wrapping_sub(sum);
+
+        let parsed = parse_table(&bytes).unwrap();
+        assert!(matches!(parsed, Some(ParsedTable::Madt(_))));
+    }
+
+    #[test]
+    fn parse_table_skips_an_unrecognized_signature_instead_of_failing() {
+        let mut bytes = std::vec![0u8; SdtHeader::LEN];
+        bytes[0..4].copy_from_slice(b"DSDT");
+        bytes[9] = 0;
+        let sum = bytes.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        bytes[9] = 0u8.wrapping_sub(sum);
+
+        assert_eq!(parse_table(&bytes), Ok(None));
+    }
+}
@@ -0,0 +1,144 @@
+//! Root System Description Pointer (ACPI spec §5.2.5): the structure
+//! firmware hands the OS to find every other ACPI table from.
+//!
+//! There's no RSDP handoff from the loader in this tree yet -- the boot
+//! info `services::init` receives (`MemMap`, the framebuffer, the
+//! initrd module) has no ACPI pointer field for one to ride in on -- so
+//! [`Rsdp::parse`] takes a byte slice rather than a physical address.
+//! Once the loader grows one, the `unsafe` cast from that address to a
+//! slice belongs at the call site, the same place `devices::pci::common`
+//! keeps the unsafety around `read_pci_config`/`write_pci_config` rather
+//! than inside the parser itself.
+
+use super::sdt::{verify_checksum, AcpiError, Result};
+
+const SIGNATURE: &[u8; 8] = b"RSD PTR ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RsdpVersion {
+    /// ACPI 1.0: only an RSDT pointer.
+    V1,
+    /// ACPI 2.0+: also carries an XSDT pointer, which this kernel
+    /// prefers (64-bit table pointers instead of the RSDT's 32-bit
+    /// ones).
+    V2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rsdp {
+    pub version: RsdpVersion,
+    pub rsdt_addr: u32,
+    /// Only meaningful when `version` is [`RsdpVersion::V2`]; `0` on V1,
+    /// same as an absent field.
+    pub xsdt_addr: u64,
+}
+
+impl Rsdp {
+    const V1_LEN: usize = 20;
+    const V2_LEN: usize = 36;
+
+    /// Parses and checksum-validates an RSDP out of `bytes`.
+    ///
+    /// ACPI 1.0's checksum covers only the first 20 bytes; ACPI 2.0+
+    /// tables carry a second checksum over the full (36-byte) extended
+    /// structure, which this validates in addition to the 1.0 one, not
+    /// instead of it (ACPI spec §5.2.5.3).
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < Self::V1_LEN || &bytes[0..8] != SIGNATURE {
+            return Err(AcpiError::UnexpectedSignature);
+        }
+        verify_checksum(&bytes[0..Self::V1_LEN])?;
+
+        let revision = bytes[15];
+        let rsdt_addr = u32::from_le_bytes(bytes[16..20].try_into().unwrap());
+
+        if revision == 0 {
+            return Ok(Self {
+                version: RsdpVersion::V1,
+                rsdt_addr,
+                xsdt_addr: 0,
+            });
+        }
+
+        if bytes.len() < Self::V2_LEN {
+            return Err(AcpiError::TooShort);
+        }
+        verify_checksum(&bytes[0..Self::V2_LEN])?;
+        let xsdt_addr = u64::from_le_bytes(bytes[24..32].try_into().unwrap());
+        Ok(Self {
+            version: RsdpVersion::V2,
+            rsdt_addr,
+            xsdt_addr,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v1_bytes(rsdt_addr: u32) -> std::vec::Vec<u8> {
+        let mut bytes = std::vec![0u8; Rsdp::V1_LEN];
+        bytes[0..8].copy_from_slice(SIGNATURE);
+        bytes[15] = 0; // revision
+        bytes[16..20].copy_from_slice(&rsdt_addr.to_le_bytes());
+        let sum = bytes.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        bytes[8] = 0u8.wrapping_sub(sum);
+        bytes
+    }
+
+    fn v2_bytes(rsdt_addr: u32, xsdt_addr: u64) -> std::vec::Vec<u8> {
+        let mut bytes = std::vec![0u8; Rsdp::V2_LEN];
+        bytes[0..8].copy_from_slice(SIGNATURE);
+        bytes[15] = 2; // revision
+        bytes[16..20].copy_from_slice(&rsdt_addr.to_le_bytes());
+        bytes[20..24].copy_from_slice(&(Rsdp::V2_LEN as u32).to_le_bytes());
+        bytes[24..32].copy_from_slice(&xsdt_addr.to_le_bytes());
+        let v1_sum = bytes[0..Rsdp::V1_LEN]
+            .iter()
+            .fold(0u8, |acc, &b| acc.wrapping_add(b));
+        bytes[8] = 0u8.wrapping_sub(v1_sum);
+        let full_sum = bytes.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        bytes[32] = 0u8.wrapping_sub(full_sum);
+        bytes
+    }
+
+    #[test]
+    fn parses_a_v1_rsdp() {
+        let bytes = v1_bytes(0x000e_0000);
+        let rsdp = Rsdp::parse(&bytes).unwrap();
+        assert_eq!(rsdp.version, RsdpVersion::V1);
+        assert_eq!(rsdp.rsdt_addr, 0x000e_0000);
+        assert_eq!(rsdp.xsdt_addr, 0);
+    }
+
+    #[test]
+    fn parses_a_v2_rsdp_with_both_checksums() {
+        let bytes = v2_bytes(0x000e_0000, 0x7fff_0000);
+        let rsdp = Rsdp::parse(&bytes).unwrap();
+        assert_eq!(rsdp.version, RsdpVersion::V2);
+        assert_eq!(rsdp.rsdt_addr, 0x000e_0000);
+        assert_eq!(rsdp.xsdt_addr, 0x7fff_0000);
+    }
+
+    #[test]
+    fn rejects_a_wrong_signature() {
+        let mut bytes = v1_bytes(0);
+        bytes[0] = b'X';
+        assert_eq!(Rsdp::parse(&bytes), Err(AcpiError::UnexpectedSignature));
+    }
+
+    #[test]
+    fn rejects_a_bad_v1_checksum() {
+        let mut bytes = v1_bytes(0x1000);
+        bytes[9] ^= 0xff;
+        assert_eq!(Rsdp::parse(&bytes), Err(AcpiError::BadChecksum));
+    }
+
+    #[test]
+    fn rejects_a_bad_extended_checksum_even_when_the_v1_one_is_fine() {
+        let mut bytes = v2_bytes(0x1000, 0x2000);
+        bytes[33] ^= 0xff;
+        assert_eq!(Rsdp::parse(&bytes), Err(AcpiError::BadChecksum));
+    }
+}
@@ -0,0 +1,120 @@
+//! The System Description Table header every ACPI table starts with
+//! (ACPI spec §5.2.6), and the checksum rule shared by all of them.
+
+pub type Result<T> = core::result::Result<T, AcpiError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcpiError {
+    /// Fewer bytes than the structure being parsed needs.
+    TooShort,
+    /// The table's bytes don't sum to `0 mod 256`.
+    BadChecksum,
+    /// A fixed signature field didn't hold the expected value.
+    UnexpectedSignature,
+}
+
+/// Sums every byte of `table` (header included) and checks it comes out
+/// to `0 mod 256` -- the checksum rule every ACPI structure in this
+/// module follows (ACPI spec §5.2.6).
+pub fn verify_checksum(table: &[u8]) -> Result<()> {
+    if table.is_empty() {
+        return Err(AcpiError::TooShort);
+    }
+    let sum = table.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+    if sum == 0 {
+        Ok(())
+    } else {
+        Err(AcpiError::BadChecksum)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SdtHeader {
+    pub signature: [u8; 4],
+    pub length: u32,
+    pub revision: u8,
+}
+
+impl SdtHeader {
+    /// Size of the header itself; every table's type-specific fields
+    /// start right after this many bytes.
+    pub const LEN: usize = 36;
+
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < Self::LEN {
+            return Err(AcpiError::TooShort);
+        }
+        let mut signature = [0u8; 4];
+        signature.copy_from_slice(&bytes[0..4]);
+        Ok(Self {
+            signature,
+            length: u32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+            revision: bytes[8],
+        })
+    }
+
+    /// `signature` decoded as ASCII, for a debug log of a table this
+    /// kernel doesn't know how to parse -- falls back to `"????"` rather
+    /// than failing outright, since a malformed signature shouldn't stop
+    /// the rest of the table walk from logging which one it was.
+    pub fn signature_str(&self) -> &str {
+        core::str::from_utf8(&self.signature).unwrap_or("????")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_valid_checksum(mut bytes: std::vec::Vec<u8>) -> std::vec::Vec<u8> {
+        bytes[9] = 0;
+        let sum = bytes.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        bytes[9] = 0u8.wrapping_sub(sum);
+        bytes
+    }
+
+    fn header_bytes(signature: &[u8; 4], length: u32) -> std::vec::Vec<u8> {
+        let mut bytes = std::vec![0u8; SdtHeader::LEN];
+        bytes[0..4].copy_from_slice(signature);
+        bytes[4..8].copy_from_slice(&length.to_le_bytes());
+        bytes[8] = 1; // revision
+        with_valid_checksum(bytes)
+    }
+
+    #[test]
+    fn parses_signature_length_and_revision() {
+        let bytes = header_bytes(b"TEST", 36);
+        let header = SdtHeader::parse(&bytes).unwrap();
+        assert_eq!(&header.signature, b"TEST");
+        assert_eq!(header.length, 36);
+        assert_eq!(header.revision, 1);
+    }
+
+    #[test]
+    fn rejects_a_buffer_shorter_than_the_header() {
+        assert_eq!(SdtHeader::parse(&[0u8; 10]), Err(AcpiError::TooShort));
+    }
+
+    #[test]
+    fn checksum_accepts_bytes_summing_to_zero() {
+        let bytes = header_bytes(b"TEST", 36);
+        assert_eq!(verify_checksum(&bytes), Ok(()));
+    }
+
+    #[test]
+    fn checksum_rejects_a_tampered_byte() {
+        let mut bytes = header_bytes(b"TEST", 36);
+        bytes[4] ^= 0xff;
+        assert_eq!(verify_checksum(&bytes), Err(AcpiError::BadChecksum));
+    }
+
+    #[test]
+    fn signature_str_falls_back_on_invalid_utf8() {
+        let header = SdtHeader {
+            signature: [0xff, 0xff, 0xff, 0xff],
+            length: 0,
+            revision: 0,
+        };
+        assert_eq!(header.signature_str(), "????");
+    }
+}
@@ -1,8 +1,9 @@
+use core::cmp::PartialOrd;
 use core::ops::{Div, DivAssign, Mul, MulAssign};
 use derive_more::{Add, AddAssign, Neg, Sub, SubAssign};
 use num_traits::NumAssign;
 
-#[derive(Debug, Default, Clone, Copy, Add, AddAssign, Sub, SubAssign, Neg)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Add, AddAssign, Sub, SubAssign, Neg)]
 pub struct Vec2D<T: NumAssign + core::marker::Copy> {
     pub x: T,
     pub y: T,
@@ -61,3 +62,112 @@ impl<T: NumAssign + core::marker::Copy> From<(T, T)> for Vec2D<T> {
         }
     }
 }
+
+impl<T: NumAssign + core::marker::Copy> Vec2D<T> {
+    /// Apply `f` component-wise, possibly changing the underlying type.
+    pub fn map<U: NumAssign + core::marker::Copy>(self, f: impl Fn(T) -> U) -> Vec2D<U> {
+        Vec2D {
+            x: f(self.x),
+            y: f(self.y),
+        }
+    }
+
+    /// Convert component-wise via `as`-style numeric casts, e.g. `usize` <-> `i32`
+    /// for mouse deltas applied on top of a `Position`.
+    pub fn cast<U>(self) -> Vec2D<U>
+    where
+        T: num_traits::AsPrimitive<U>,
+        U: NumAssign + core::marker::Copy + 'static,
+    {
+        self.map(num_traits::AsPrimitive::as_)
+    }
+}
+
+impl Vec2D<usize> {
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Self {
+            x: self.x.saturating_add(rhs.x),
+            y: self.y.saturating_add(rhs.y),
+        }
+    }
+
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self {
+            x: self.x.saturating_sub(rhs.x),
+            y: self.y.saturating_sub(rhs.y),
+        }
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_sub(rhs.x)?,
+            y: self.y.checked_sub(rhs.y)?,
+        })
+    }
+}
+
+impl<T: NumAssign + core::marker::Copy + PartialOrd> Vec2D<T> {
+    /// Componentwise minimum, e.g. clamping a rect's lower-right corner
+    /// to the edge of another rect when intersecting them.
+    pub fn min(self, rhs: Self) -> Self {
+        Self {
+            x: if self.x < rhs.x { self.x } else { rhs.x },
+            y: if self.y < rhs.y { self.y } else { rhs.y },
+        }
+    }
+
+    /// Componentwise maximum.
+    pub fn max(self, rhs: Self) -> Self {
+        Self {
+            x: if self.x > rhs.x { self.x } else { rhs.x },
+            y: if self.y > rhs.y { self.y } else { rhs.y },
+        }
+    }
+
+    /// Componentwise clamp, e.g. keeping a mouse cursor within `0..resolution`.
+    pub fn clamp(self, lo: Self, hi: Self) -> Self {
+        self.max(lo).min(hi)
+    }
+}
+
+impl<T: NumAssign + core::marker::Copy> From<Vec2D<T>> for (T, T) {
+    fn from(v: Vec2D<T>) -> Self {
+        (v.x, v.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_sub_fails_on_underflow_per_component() {
+        let a = Vec2D::new(5usize, 5);
+        assert_eq!(a.checked_sub(Vec2D::new(3, 3)), Some(Vec2D::new(2, 2)));
+        assert_eq!(a.checked_sub(Vec2D::new(10, 0)), None);
+    }
+
+    #[test]
+    fn min_max_are_componentwise() {
+        let a = Vec2D::new(1, 10);
+        let b = Vec2D::new(5, 2);
+        assert_eq!(a.min(b), Vec2D::new(1, 2));
+        assert_eq!(a.max(b), Vec2D::new(5, 10));
+    }
+
+    #[test]
+    fn clamp_bounds_each_component_independently() {
+        let lo = Vec2D::new(0, 0);
+        let hi = Vec2D::new(10, 10);
+        assert_eq!(Vec2D::new(-5, 20).clamp(lo, hi), Vec2D::new(0, 10));
+        assert_eq!(Vec2D::new(3, 3).clamp(lo, hi), Vec2D::new(3, 3));
+    }
+
+    #[test]
+    fn tuple_round_trip() {
+        let v = Vec2D::new(3, 4);
+        let t: (i32, i32) = v.into();
+        assert_eq!(t, (3, 4));
+        assert_eq!(Vec2D::from(t), v);
+    }
+}
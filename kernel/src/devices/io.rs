@@ -1,4 +1,39 @@
 use core::arch::global_asm;
+use core::marker::PhantomData;
+
+global_asm!(
+    ".global io_write8",
+    "io_write8:",
+    "mov dx, di",
+    "mov al, sil",
+    "out dx, al",
+    "ret",
+);
+
+global_asm!(
+    ".global io_read8",
+    "io_read8:",
+    "mov dx, di",
+    "in al, dx",
+    "ret",
+);
+
+global_asm!(
+    ".global io_write16",
+    "io_write16:",
+    "mov dx, di",
+    "mov ax, si",
+    "out dx, ax",
+    "ret",
+);
+
+global_asm!(
+    ".global io_read16",
+    "io_read16:",
+    "mov dx, di",
+    "in ax, dx",
+    "ret",
+);
 
 global_asm!(
     ".global io_write32",
@@ -18,41 +53,139 @@ global_asm!(
 );
 
 extern "sysv64" {
+    fn io_write8(addr: u16, value: u8);
+    fn io_read8(addr: u16) -> u8;
+    fn io_write16(addr: u16, value: u16);
+    fn io_read16(addr: u16) -> u16;
     fn io_write32(addr: u16, value: u32);
     fn io_read32(addr: u16) -> u32;
 }
 
+/// Implemented for the widths x86's `in`/`out` instructions support, so
+/// [`IoPort<W>`] can stay generic while each width still compiles down to
+/// its own dedicated instruction rather than a truncating/zero-extending
+/// wrapper around the 32-bit one.
+pub trait PortWidth: Copy {
+    unsafe fn port_write(addr: u16, value: Self);
+    unsafe fn port_read(addr: u16) -> Self;
+}
+
+impl PortWidth for u8 {
+    unsafe fn port_write(addr: u16, value: Self) {
+        io_write8(addr, value)
+    }
+
+    unsafe fn port_read(addr: u16) -> Self {
+        io_read8(addr)
+    }
+}
+
+impl PortWidth for u16 {
+    unsafe fn port_write(addr: u16, value: Self) {
+        io_write16(addr, value)
+    }
+
+    unsafe fn port_read(addr: u16) -> Self {
+        io_read16(addr)
+    }
+}
+
+impl PortWidth for u32 {
+    unsafe fn port_write(addr: u16, value: Self) {
+        io_write32(addr, value)
+    }
+
+    unsafe fn port_read(addr: u16) -> Self {
+        io_read32(addr)
+    }
+}
+
 /// This implementation assumes x86_64.
 pub trait IoAccess {
+    type Width: PortWidth;
+
     fn addr(&self) -> IoAddr;
 
-    unsafe fn write(&self, value: u32) {
-        io_write32(self.addr().0, value)
+    unsafe fn write(&self, value: Self::Width) {
+        Self::Width::port_write(self.addr().0, value)
     }
 
-    unsafe fn read(&self) -> u32 {
-        io_read32(self.addr().0)
+    unsafe fn read(&self) -> Self::Width {
+        Self::Width::port_read(self.addr().0)
     }
 }
 
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub struct IoAddr(u16);
 
-#[derive(Debug, Default)]
-pub struct IoPort {
+/// A port IO address, typed by its access width `W` (`u8`/`u16`/`u32`).
+/// Defaults to `u32` so existing PCI-config-style usage (`IoPort::new(..)`,
+/// `.write(u32)`/`.read() -> u32`) keeps compiling unchanged; 8/16-bit
+/// devices such as serial (COM1) or PS/2 name the width explicitly, e.g.
+/// `IoPort::<u8>::new(0x3f8)`.
+#[derive(Debug)]
+pub struct IoPort<W = u32> {
     addr: IoAddr,
+    _width: PhantomData<W>,
+}
+
+impl<W> IoPort<W> {
+    pub const fn new(addr: u16) -> Self {
+        Self {
+            addr: IoAddr(addr),
+            _width: PhantomData,
+        }
+    }
 }
 
-impl IoPort {
+impl<W> Default for IoPort<W> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl IoPort<u32> {
     pub const PCI_CONFIG_ADDR: Self = Self::new(0x0cf8);
     pub const PCI_CONFIG_DATA: Self = Self::new(0x0cfc);
+}
 
-    pub const fn new(addr: u16) -> Self {
-        Self { addr: IoAddr(addr) }
-    }
+impl IoPort<u8> {
+    /// Serial port COM1's base address. Which register an access to it
+    /// actually hits (data, line status, ...) depends on the UART's
+    /// line-control-register DLAB bit and an offset from this base --
+    /// [`super::serial`] addresses those registers by offset itself.
+    pub const COM1: Self = Self::new(0x3f8);
+
+    /// PS/2 controller's data port: reads/writes a byte to/from
+    /// whichever device (keyboard/mouse) is currently selected.
+    pub const PS2_DATA: Self = Self::new(0x60);
+    /// PS/2 controller's status (read) / command (write) port.
+    pub const PS2_STATUS: Self = Self::new(0x64);
+
+    /// Programmable Interval Timer, channel 0's data port.
+    pub const PIT_CHANNEL0: Self = Self::new(0x40);
+    /// Programmable Interval Timer's mode/command register.
+    pub const PIT_COMMAND: Self = Self::new(0x43);
+
+    /// CMOS/RTC's index (a.k.a. address) register: write the register
+    /// number here, then read or write [`Self::CMOS_DATA`] to access it.
+    ///
+    /// ```ignore
+    /// // Read the RTC's "seconds" register (CMOS register 0x00, BCD-encoded).
+    /// unsafe {
+    ///     IoPort::<u8>::CMOS_INDEX.write(0x00);
+    ///     let seconds_bcd = IoPort::<u8>::CMOS_DATA.read();
+    /// }
+    /// ```
+    pub const CMOS_INDEX: Self = Self::new(0x70);
+    /// CMOS/RTC's data register: holds the byte named by whichever
+    /// register number was last written to [`Self::CMOS_INDEX`].
+    pub const CMOS_DATA: Self = Self::new(0x71);
 }
 
-impl IoAccess for IoPort {
+impl<W: PortWidth> IoAccess for IoPort<W> {
+    type Width = W;
+
     fn addr(&self) -> IoAddr {
         self.addr
     }
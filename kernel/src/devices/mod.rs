@@ -1,4 +1,7 @@
-pub mod error;
 pub mod io;
 pub mod pci;
+pub mod ps2;
+pub mod rtc;
+pub mod serial;
 pub mod usb;
+pub mod volatile;
@@ -1,4 +1,6 @@
 #![allow(dead_code)]
+use core::fmt;
+
 use bit_field::BitField;
 
 use crate::devices::io::{IoAccess, IoPort};
@@ -58,9 +60,63 @@ unsafe fn write_pci_config(addr: PciAddr, value: u32) {
     IoPort::PCI_CONFIG_DATA.write(value);
 }
 
-type DeviceId = u16;
-type Status = u16;
-type Command = u16;
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct DeviceId(u16);
+
+impl DeviceId {
+    #[inline]
+    pub fn as_raw(&self) -> u16 {
+        self.0
+    }
+}
+
+/// The PCI Command register (config space offset `0x04`, lower 16 bits).
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct Command(u16);
+
+impl Command {
+    #[inline]
+    pub fn io_space_enabled(&self) -> bool {
+        self.0.get_bit(0)
+    }
+
+    #[inline]
+    pub fn memory_space_enabled(&self) -> bool {
+        self.0.get_bit(1)
+    }
+
+    #[inline]
+    pub fn bus_master_enabled(&self) -> bool {
+        self.0.get_bit(2)
+    }
+
+    #[inline]
+    pub fn as_raw(&self) -> u16 {
+        self.0
+    }
+}
+
+/// The PCI Status register (config space offset `0x04`, upper 16 bits).
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct Status(u16);
+
+impl Status {
+    /// Bit 4: set when this function implements a linked capability
+    /// list (MSI, MSI-X, PCIe, ...), reachable from the Capabilities
+    /// Pointer register. Clear on plain conventional-PCI functions,
+    /// which don't have a Capabilities Pointer register at all -- a
+    /// capability walk must check this before following that pointer,
+    /// rather than dereferencing whatever garbage sits there.
+    #[inline]
+    pub fn has_capability_list(&self) -> bool {
+        self.0.get_bit(4)
+    }
+
+    #[inline]
+    pub fn as_raw(&self) -> u16 {
+        self.0
+    }
+}
 
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub struct HeaderType(u8);
@@ -145,6 +201,68 @@ impl ClassCode {
     }
 }
 
+/// One entry of [`CLASS_CODE_TABLE`]. `interface: None` matches any
+/// interface byte, so an entry naming just the base/sub-class acts as a
+/// fallback for an interface-specific entry listed above it.
+struct ClassCodeEntry {
+    base_class: u8,
+    sub_class: u8,
+    interface: Option<u8>,
+    name: &'static str,
+}
+
+/// A small, hand-picked subset of the PCI class-code database — just the
+/// devices this kernel actually cares about identifying at a glance.
+/// Not exhaustive; anything else falls back to [`fmt::Display`]'s
+/// `Unknown(..)` form rather than guessing.
+///
+/// Interface-specific entries are listed before their base/sub-class
+/// fallback so [`ClassCode`]'s `Display` impl, which takes the first
+/// match, prefers the more specific name.
+const CLASS_CODE_TABLE: &[ClassCodeEntry] = &[
+    ClassCodeEntry { base_class: 0x01, sub_class: 0x06, interface: None, name: "Mass Storage Controller / SATA" },
+    ClassCodeEntry { base_class: 0x01, sub_class: 0x08, interface: None, name: "Mass Storage Controller / NVMe" },
+    ClassCodeEntry { base_class: 0x02, sub_class: 0x00, interface: None, name: "Network Controller / Ethernet" },
+    ClassCodeEntry { base_class: 0x03, sub_class: 0x00, interface: None, name: "Display Controller / VGA" },
+    ClassCodeEntry { base_class: 0x06, sub_class: 0x00, interface: None, name: "Bridge / Host" },
+    ClassCodeEntry { base_class: 0x06, sub_class: 0x01, interface: None, name: "Bridge / ISA" },
+    ClassCodeEntry { base_class: 0x06, sub_class: 0x04, interface: None, name: "Bridge / PCI-to-PCI" },
+    ClassCodeEntry { base_class: 0x0c, sub_class: 0x03, interface: Some(0x30), name: "Serial Bus Controller / USB / xHCI" },
+    ClassCodeEntry { base_class: 0x0c, sub_class: 0x03, interface: Some(0x20), name: "Serial Bus Controller / USB / EHCI" },
+    ClassCodeEntry { base_class: 0x0c, sub_class: 0x03, interface: None, name: "Serial Bus Controller / USB" },
+];
+
+impl ClassCode {
+    fn matching_entry(&self) -> Option<&'static ClassCodeEntry> {
+        CLASS_CODE_TABLE.iter().find(|e| {
+            e.base_class == self.base_class
+                && e.sub_class == self.sub_class
+                && e.interface.map_or(true, |i| i == self.interface)
+        })
+    }
+
+    /// The same lookup [`fmt::Display`] uses, as a plain string for
+    /// callers that want just the name (e.g. `pci::names::class_name`)
+    /// without `Display`'s `Unknown(..)` fallback formatting.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.matching_entry().map_or("Unknown", |e| e.name)
+    }
+}
+
+impl fmt::Display for ClassCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.matching_entry() {
+            Some(e) => write!(f, "{}", e.name),
+            None => write!(
+                f,
+                "Unknown({:#04x}.{:#04x}.{:#04x})",
+                self.base_class, self.sub_class, self.interface
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub struct VendorId(u16);
 
@@ -171,7 +289,50 @@ pub enum Bar {
     Memory64 { addr: u64, prefetchable: bool },
 }
 
+impl Bar {
+    /// The BAR's address, widened to `u64` regardless of variant, for
+    /// callers that only care where the device is mapped and not how
+    /// many address bits it took to say so.
+    #[inline]
+    pub fn address(&self) -> u64 {
+        match *self {
+            Bar::Memory32 { addr, .. } => addr as u64,
+            Bar::Memory64 { addr, .. } => addr,
+        }
+    }
+
+    #[inline]
+    pub fn is_64bit(&self) -> bool {
+        matches!(self, Bar::Memory64 { .. })
+    }
+
+    #[inline]
+    pub fn prefetchable(&self) -> bool {
+        match *self {
+            Bar::Memory32 { prefetchable, .. } => prefetchable,
+            Bar::Memory64 { prefetchable, .. } => prefetchable,
+        }
+    }
+}
+
+/// Why [`PciConfig::try_bar`] couldn't decode a BAR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarError {
+    /// `id` isn't one of the up to [`MAX_BARS`] BAR slots a PCI function has.
+    IdOutOfRange { id: u8 },
+    /// `id`'s type bits claim a 64-bit BAR, but `id` is the last slot,
+    /// leaving no following register for the upper 32 address bits.
+    Missing64BitPair { id: u8 },
+    /// `id`'s type bits (bits 1-2 of the low dword) aren't one of the
+    /// two values this kernel understands (`00` = 32-bit, `10` = 64-bit);
+    /// `01` is reserved and `11` doesn't exist.
+    InvalidTypeBits { id: u8, bits: u8 },
+}
+
 const MAX_BARS: u8 = 6;
+/// Upper bound on how many entries [`PciConfig::capability_ids`] collects;
+/// real devices rarely chain more than a handful (MSI, MSI-X, PCIe, ...).
+const MAX_CAPABILITIES: usize = 8;
 /// Struct that represents base parameters for PCI devices
 #[derive(Debug, Default, Clone, Copy)]
 pub struct PciConfig {
@@ -190,11 +351,26 @@ impl PciConfig {
         unsafe { read_pci_config(PciAddr::new(self.bus, self.device, self.func, reg_addr)) }
     }
 
+    #[inline]
+    fn write(&self, reg_addr: u8, value: u32) {
+        unsafe { write_pci_config(PciAddr::new(self.bus, self.device, self.func, reg_addr), value) }
+    }
+
     #[inline]
     pub fn vendor_id(&self) -> VendorId {
         VendorId(self.read(0x00).get_bits(0..16) as u16)
     }
 
+    #[inline]
+    pub fn device_id(&self) -> DeviceId {
+        DeviceId(self.read(0x00).get_bits(16..32) as u16)
+    }
+
+    #[inline]
+    pub fn command(&self) -> Command {
+        Command(self.read(0x04).get_bits(0..16) as u16)
+    }
+
     #[inline]
     pub fn header_type(&self) -> HeaderType {
         HeaderType(self.read(0x0c).get_bits(16..24) as u8)
@@ -205,29 +381,52 @@ impl PciConfig {
         ClassCode::from_u32(self.read(0x08))
     }
 
+    /// Read before walking a capability list: [`Status::has_capability_list`]
+    /// tells you whether the Capabilities Pointer register (read only by
+    /// [`Self::capability_ids`], not exposed on its own) points at
+    /// anything at all.
+    #[inline]
+    pub fn status(&self) -> Status {
+        Status(self.read(0x04).get_bits(16..32) as u16)
+    }
+
+    /// Convenience wrapper over [`Self::try_bar`] for call sites that
+    /// treat every failure mode the same way (skip this BAR). Prefer
+    /// [`Self::try_bar`] anywhere the distinction is useful, e.g. to log
+    /// *why* a device's BAR couldn't be used.
     #[inline]
     pub fn bar(&self, id: u8) -> Option<Bar> {
+        self.try_bar(id).ok()
+    }
+
+    /// Reads and decodes BAR `id`, or reports which part of it was
+    /// malformed. Config-space contents come straight from the device,
+    /// so on real (non-QEMU) hardware any of these can legitimately
+    /// happen; this lets a caller like device scanning skip the device
+    /// with a clear reason instead of treating every failure alike.
+    #[inline]
+    pub fn try_bar(&self, id: u8) -> Result<Bar, BarError> {
         if id >= MAX_BARS {
-            return None;
+            return Err(BarError::IdOutOfRange { id });
         }
 
         let bar = self.read(bar_addr(id));
         let addr = bar & !0x0f; // removing flags (4 bits from LSB)
         let prefetchable = bar.get_bit(3);
         match bar.get_bits(1..3) {
-            0b00 => Some(Bar::Memory32 { addr, prefetchable }),
+            0b00 => Ok(Bar::Memory32 { addr, prefetchable }),
             0b10 => {
                 if id == MAX_BARS - 1 {
                     // Expected to be 32 bit address (implied with location, no space for 64 bit)
                     // but flag specifies 64 bit, thus invalid.
-                    None
+                    Err(BarError::Missing64BitPair { id })
                 } else {
                     let upper = self.read(bar_addr(id + 1));
                     let addr = (upper as u64) << 32 | addr as u64;
-                    Some(Bar::Memory64 { addr, prefetchable })
+                    Ok(Bar::Memory64 { addr, prefetchable })
                 }
             }
-            _ => None, // invalid
+            bits => Err(BarError::InvalidTypeBits { id, bits: bits as u8 }),
         }
     }
 
@@ -240,6 +439,104 @@ impl PciConfig {
     pub fn secondary_bus(&self) -> u8 {
         self.bus_numbers().get_bits(8..16) as u8
     }
+
+    #[inline]
+    pub fn interrupt_line(&self) -> u8 {
+        self.read(0x3c).get_bits(0..8) as u8
+    }
+
+    #[inline]
+    pub fn interrupt_pin(&self) -> u8 {
+        self.read(0x3c).get_bits(8..16) as u8
+    }
+
+    /// The size in bytes of the BAR at `id` (already decoded as `bar`
+    /// by the caller), found by temporarily writing all ones into it
+    /// and reading back which low bits the hardware forced to zero
+    /// (those are the ones the BAR's address can't adjust, so they
+    /// encode the size) -- the standard PCI BAR-sizing probe. Restores
+    /// the BAR's original value before returning. `None` if `bar`
+    /// decodes to one that isn't implemented at all (an all-zero size
+    /// mask).
+    fn bar_size(&self, id: u8, bar: Bar) -> Option<u64> {
+        let reg = bar_addr(id);
+        let original_low = self.read(reg);
+        self.write(reg, 0xffff_ffff);
+        let mask_low = self.read(reg) & !0x0f;
+        self.write(reg, original_low);
+
+        let mask = match bar {
+            // A 32-bit BAR has no upper address bits to probe; treat them
+            // as all ones so the two's complement below is the 32-bit size.
+            Bar::Memory32 { .. } if mask_low == 0 => 0,
+            Bar::Memory32 { .. } => 0xffff_ffff_0000_0000 | mask_low as u64,
+            Bar::Memory64 { .. } => {
+                let reg_high = bar_addr(id + 1);
+                let original_high = self.read(reg_high);
+                self.write(reg_high, 0xffff_ffff);
+                let mask_high = self.read(reg_high);
+                self.write(reg_high, original_high);
+                (mask_high as u64) << 32 | mask_low as u64
+            }
+        };
+
+        if mask == 0 {
+            return None;
+        }
+        Some((!mask).wrapping_add(1))
+    }
+
+    /// Walks the linked capability list starting at the Capabilities
+    /// Pointer register (offset `0x34`), returning the capability IDs
+    /// found in order. Empty if [`Status::has_capability_list`] is
+    /// clear. Bounded by the returned array's capacity rather than the
+    /// list's own next-pointer chain, so a malformed (e.g. cyclic) list
+    /// on real hardware can't hang this instead of just truncating.
+    fn capability_ids(&self) -> ([u8; MAX_CAPABILITIES], usize) {
+        let mut ids = [0u8; MAX_CAPABILITIES];
+        let mut count = 0;
+        if !self.status().has_capability_list() {
+            return (ids, count);
+        }
+
+        let mut ptr = self.read(0x34).get_bits(0..8) as u8 & !0x03;
+        while ptr != 0 && count < MAX_CAPABILITIES {
+            let entry = self.read(ptr);
+            ids[count] = entry.get_bits(0..8) as u8;
+            count += 1;
+            ptr = entry.get_bits(8..16) as u8 & !0x03;
+        }
+        (ids, count)
+    }
+
+    /// Snapshots the full standard header for [`PciDevice::dump`]. Reads
+    /// (and, for BAR sizing, briefly overwrites-then-restores) config
+    /// space up front rather than on every [`HeaderDump`] print, so that
+    /// mutation happens exactly once, at the call site that asked for
+    /// it -- not hidden inside a `Display` impl.
+    fn dump(&self, device: PciDevice) -> HeaderDump {
+        let mut bars = [None; MAX_BARS as usize];
+        let mut id = 0;
+        while id < MAX_BARS {
+            match self.try_bar(id) {
+                Ok(bar) => {
+                    bars[id as usize] = Some((bar, self.bar_size(id, bar)));
+                    id += if bar.is_64bit() { 2 } else { 1 };
+                }
+                Err(_) => id += 1,
+            }
+        }
+
+        HeaderDump {
+            device,
+            command: self.command(),
+            status: self.status(),
+            interrupt_line: self.interrupt_line(),
+            interrupt_pin: self.interrupt_pin(),
+            bars,
+            capabilities: self.capability_ids(),
+        }
+    }
 }
 
 #[inline]
@@ -301,13 +598,138 @@ impl PciDevice {
         self.vendor_id
     }
 
+    /// Not cached like [`Self::vendor_id`]: unrelated to the vendor ID
+    /// field it shares a register with, but read live for the same
+    /// reason [`Self::status`] is -- there's no strong case for caching
+    /// it that doesn't also apply to half the other live reads here.
+    #[inline]
+    pub fn device_id(&self) -> DeviceId {
+        self.config.device_id()
+    }
+
     #[inline]
     pub fn class_code(&self) -> ClassCode {
         self.class_code
     }
 
+    /// Not cached like [`Self::class_code`]/[`Self::vendor_id`]: unlike
+    /// those, status bits (e.g. Interrupt Status) can change while a
+    /// device is in use, so this re-reads config space every call.
+    #[inline]
+    pub fn status(&self) -> Status {
+        self.config.status()
+    }
+
     #[inline]
     pub fn bar(&self, id: u8) -> Option<Bar> {
         self.config.bar(id)
     }
+
+    #[inline]
+    pub fn try_bar(&self, id: u8) -> Result<Bar, BarError> {
+        self.config.try_bar(id)
+    }
+
+    /// Reads the full 64-byte standard header -- command/status, every
+    /// BAR with its size, interrupt line/pin, and the capability list --
+    /// for detailed printing (the shell's `lspci -v`). See [`HeaderDump`].
+    #[inline]
+    pub fn dump(&self) -> HeaderDump {
+        self.config.dump(*self)
+    }
+}
+
+impl fmt::Display for PciDevice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let class = self.class_code();
+        write!(
+            f,
+            "{:02x}:{:02x}.{:x} {:04x}:{:04x} class {:02x}.{:02x}.{:02x} ({})",
+            self.bus(),
+            self.device_number(),
+            self.function(),
+            self.vendor_id().as_raw(),
+            self.device_id().as_raw(),
+            class.base_class(),
+            class.sub_class(),
+            class.interface(),
+            class,
+        )
+    }
+}
+
+/// A snapshot of [`PciDevice`]'s full standard header, for detailed
+/// printing (the shell's `lspci -v`) via [`PciDevice::dump`]. Captured
+/// once up front rather than read live by `Display`, since computing
+/// each BAR's size briefly overwrites it -- a mutation that belongs to
+/// the explicit `dump()` call a caller asked for, not to formatting.
+pub struct HeaderDump {
+    device: PciDevice,
+    command: Command,
+    status: Status,
+    interrupt_line: u8,
+    interrupt_pin: u8,
+    bars: [Option<(Bar, Option<u64>)>; MAX_BARS as usize],
+    capabilities: ([u8; MAX_CAPABILITIES], usize),
+}
+
+impl fmt::Display for HeaderDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.device)?;
+        writeln!(
+            f,
+            "  Control: I/O{} Mem{} BusMaster{}",
+            if self.command.io_space_enabled() { "+" } else { "-" },
+            if self.command.memory_space_enabled() { "+" } else { "-" },
+            if self.command.bus_master_enabled() { "+" } else { "-" },
+        )?;
+        writeln!(
+            f,
+            "  Status: CapList{}",
+            if self.status.has_capability_list() { "+" } else { "-" },
+        )?;
+        for (id, bar) in self.bars.iter().enumerate() {
+            let Some((bar, size)) = bar else { continue };
+            write!(
+                f,
+                "  BAR{}: {:#010x} ({}-bit{})",
+                id,
+                bar.address(),
+                if bar.is_64bit() { 64 } else { 32 },
+                if bar.prefetchable() { ", prefetchable" } else { "" },
+            )?;
+            match size {
+                Some(size) => writeln!(f, " [size={}]", Size(*size))?,
+                None => writeln!(f, " [size=?]")?,
+            }
+        }
+        writeln!(f, "  Interrupt: line {} pin {}", self.interrupt_line, self.interrupt_pin)?;
+        let (ids, count) = self.capabilities;
+        if count == 0 {
+            writeln!(f, "  Capabilities: none")
+        } else {
+            write!(f, "  Capabilities:")?;
+            for id in &ids[..count] {
+                write!(f, " {:#04x}", id)?;
+            }
+            writeln!(f)
+        }
+    }
+}
+
+/// Formats a byte count the way `lspci` does: `4K`/`1M` when it divides
+/// evenly, the raw byte count otherwise.
+struct Size(u64);
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = self.0;
+        if bytes != 0 && bytes % (1024 * 1024) == 0 {
+            write!(f, "{}M", bytes / (1024 * 1024))
+        } else if bytes != 0 && bytes % 1024 == 0 {
+            write!(f, "{}K", bytes / 1024)
+        } else {
+            write!(f, "{}", bytes)
+        }
+    }
 }
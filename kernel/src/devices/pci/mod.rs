@@ -1,7 +1,8 @@
 pub mod common;
 pub mod error;
+pub mod names;
 pub mod services;
 
 pub use common::*;
 pub use error::{Error, Result};
-pub use services::PciDeviceService;
+pub use services::{PciDeviceService, RescanReport};
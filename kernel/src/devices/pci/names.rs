@@ -0,0 +1,47 @@
+//! Vendor-name lookup generated at build time from a trimmed,
+//! hand-curated `pci.ids`-style asset (`assets/pci_vendors.txt`) --
+//! nowhere near the full upstream pci.ids database (no network access
+//! to fetch one in this environment), just the handful of vendors this
+//! kernel is actually likely to see. See the crate's `build.rs` for how
+//! the table below is generated.
+use super::ClassCode;
+
+include!(concat!(env!("OUT_DIR"), "/pci_names.rs"));
+
+/// Looks up a PCI vendor ID in the build-time-generated table.
+/// `PCI_VENDOR_NAMES` is sorted by ID, so this binary-searches it
+/// instead of scanning linearly.
+pub fn vendor(id: u16) -> Option<&'static str> {
+    PCI_VENDOR_NAMES
+        .binary_search_by_key(&id, |&(vendor_id, _)| vendor_id)
+        .ok()
+        .map(|i| PCI_VENDOR_NAMES[i].1)
+}
+
+/// The standard class/subclass name for `class`, same lookup as
+/// [`ClassCode`]'s `Display` impl, as a plain string for callers that
+/// want just the name (e.g. to pair with already-hex-formatted raw
+/// bytes) without its `Unknown(..)` fallback formatting.
+pub fn class_name(class: ClassCode) -> &'static str {
+    class.name()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_a_known_vendor() {
+        assert_eq!(vendor(0x8086), Some("Intel Corporation"));
+    }
+
+    #[test]
+    fn table_is_sorted_for_binary_search() {
+        assert!(PCI_VENDOR_NAMES.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn unknown_vendor_falls_back_to_none() {
+        assert_eq!(vendor(0xffff), None);
+    }
+}
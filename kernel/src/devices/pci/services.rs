@@ -94,10 +94,90 @@ impl PciDeviceService {
         self.devices[0..self.count].iter()
     }
 
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
     pub fn reset(&mut self) {
         for device in self.devices[0..self.count].iter_mut() {
             let _ = device.take();
         }
         self.count = 0;
     }
+
+    /// Re-scans the bus into a scratch list, diffs it against the
+    /// current one by (bus, device, function), and only then swaps the
+    /// scratch list in -- so a `PciDevice` an earlier caller (e.g.
+    /// `detect_usb`'s xHC copy) took is never invalidated mid-use the
+    /// way `reset` followed by `scan_all_bus` would, and a failed scan
+    /// leaves the existing list untouched instead of half-overwritten.
+    pub fn rescan(&mut self) -> pci::Result<RescanReport> {
+        let mut fresh = Self::new();
+        fresh.scan_all_bus()?;
+
+        let mut report = RescanReport::empty();
+        for device in self.iter().flatten() {
+            if !fresh.iter().flatten().any(|d| same_slot(d, device)) {
+                report.push_removed(*device);
+            }
+        }
+        for device in fresh.iter().flatten() {
+            if !self.iter().flatten().any(|d| same_slot(d, device)) {
+                report.push_added(*device);
+            }
+        }
+
+        *self = fresh;
+        Ok(report)
+    }
+}
+
+#[inline]
+fn same_slot(a: &PciDevice, b: &PciDevice) -> bool {
+    a.bus() == b.bus() && a.device_number() == b.device_number() && a.function() == b.function()
+}
+
+/// Which devices appeared or disappeared since the last scan, returned
+/// by [`PciDeviceService::rescan`]. Devices are matched by (bus, device,
+/// function) slot, not by equality -- a device whose vendor/class
+/// changed in place (unusual, but config space contents come straight
+/// from real hardware) isn't reported as removed-then-added.
+pub struct RescanReport {
+    added: [Option<PciDevice>; CAPACITY],
+    added_count: usize,
+    removed: [Option<PciDevice>; CAPACITY],
+    removed_count: usize,
+}
+
+impl RescanReport {
+    fn empty() -> Self {
+        Self {
+            added: [None; CAPACITY],
+            added_count: 0,
+            removed: [None; CAPACITY],
+            removed_count: 0,
+        }
+    }
+
+    pub fn added(&self) -> &[Option<PciDevice>] {
+        &self.added[0..self.added_count]
+    }
+
+    pub fn removed(&self) -> &[Option<PciDevice>] {
+        &self.removed[0..self.removed_count]
+    }
+
+    fn push_added(&mut self, device: PciDevice) {
+        if self.added_count < self.added.len() {
+            self.added[self.added_count] = Some(device);
+            self.added_count += 1;
+        }
+    }
+
+    fn push_removed(&mut self, device: PciDevice) {
+        if self.removed_count < self.removed.len() {
+            self.removed[self.removed_count] = Some(device);
+            self.removed_count += 1;
+        }
+    }
 }
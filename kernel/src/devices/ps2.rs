@@ -0,0 +1,102 @@
+//! Scancode Set 1 decoding for a legacy PS/2 keyboard (the byte stream
+//! [`super::io::IoPort::PS2_DATA`] hands back one byte per keystroke,
+//! one extra prefix byte for keys added after the original PC/AT
+//! layout).
+//!
+//! There's no generic interrupt-registration module, IOAPIC/PIC IRQ
+//! routing, or `crate::key_push` callback in this tree yet to drive this
+//! from -- [`super::super::interrupts`]'s own module doc already says
+//! there's no IDT/APIC wiring at all, only the xHC's event ring being
+//! polled. This is the standalone decoder a real IRQ1 handler would
+//! feed bytes into: it tracks the one piece of cross-byte state
+//! Scancode Set 1 needs (the `0xE0` extended-key prefix) and turns a
+//! raw byte stream into press/release events keyed by scancode, ready
+//! for whatever translates those to the same key-event type the USB
+//! keyboard path eventually uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    /// The scancode with the break bit (bit 7) stripped off.
+    pub scancode: u8,
+    /// Whether this scancode arrived after an `0xE0` prefix byte (the
+    /// extra keys -- arrow keys, Right Ctrl/Alt, ... -- Scancode Set 1
+    /// added without enough room in the original one-byte codes).
+    pub extended: bool,
+    pub pressed: bool,
+}
+
+/// Holds the one bit of state needed across calls to [`Self::decode`]:
+/// whether the previous byte was the `0xE0` extended-key prefix.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ScancodeDecoder {
+    extended: bool,
+}
+
+const EXTENDED_PREFIX: u8 = 0xe0;
+const BREAK_BIT: u8 = 0x80;
+
+impl ScancodeDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds one byte read from `IoPort::PS2_DATA`. Returns `None` while
+    /// still waiting on more bytes of a multi-byte sequence (currently
+    /// only the `0xE0` prefix); otherwise returns the event the byte (or
+    /// byte pair) decoded to.
+    pub fn decode(&mut self, byte: u8) -> Option<KeyEvent> {
+        if byte == EXTENDED_PREFIX {
+            self.extended = true;
+            return None;
+        }
+
+        let event = KeyEvent {
+            scancode: byte & !BREAK_BIT,
+            extended: self.extended,
+            pressed: byte & BREAK_BIT == 0,
+        };
+        self.extended = false;
+        Some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_make_code_is_a_press() {
+        let mut decoder = ScancodeDecoder::new();
+        let event = decoder.decode(0x1e).unwrap(); // 'A' key, pressed
+        assert_eq!(event, KeyEvent { scancode: 0x1e, extended: false, pressed: true });
+    }
+
+    #[test]
+    fn a_break_code_is_a_release_of_the_same_scancode() {
+        let mut decoder = ScancodeDecoder::new();
+        let event = decoder.decode(0x1e | BREAK_BIT).unwrap();
+        assert_eq!(event, KeyEvent { scancode: 0x1e, extended: false, pressed: false });
+    }
+
+    #[test]
+    fn an_extended_prefix_is_consumed_without_producing_an_event() {
+        let mut decoder = ScancodeDecoder::new();
+        assert_eq!(decoder.decode(EXTENDED_PREFIX), None);
+    }
+
+    #[test]
+    fn the_byte_after_an_extended_prefix_is_marked_extended() {
+        let mut decoder = ScancodeDecoder::new();
+        decoder.decode(EXTENDED_PREFIX);
+        let event = decoder.decode(0x48).unwrap(); // Up arrow
+        assert_eq!(event, KeyEvent { scancode: 0x48, extended: true, pressed: true });
+    }
+
+    #[test]
+    fn the_extended_flag_does_not_leak_into_the_next_unrelated_byte() {
+        let mut decoder = ScancodeDecoder::new();
+        decoder.decode(EXTENDED_PREFIX);
+        decoder.decode(0x48);
+        let event = decoder.decode(0x1e).unwrap();
+        assert!(!event.extended);
+    }
+}
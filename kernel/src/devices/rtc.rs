@@ -0,0 +1,141 @@
+//! CMOS Real-Time Clock (RTC) driver: reads the wall-clock date/time out
+//! of the battery-backed CMOS registers exposed at I/O ports 0x70/0x71
+//! (see [`IoPort::CMOS_INDEX`]/[`IoPort::CMOS_DATA`](crate::devices::io::IoPort)).
+//!
+//! There's no calibrated timer in this kernel to turn an elapsed tick
+//! count into a real duration yet (see `LOOP_TICKS`'s doc comment), so
+//! there's nothing for [`crate::services::wall_now`] to add to a cached
+//! boot time -- it re-reads the RTC through [`now`] on every call
+//! instead.
+
+use crate::devices::io::{IoAccess, IoPort};
+
+const REG_SECONDS: u8 = 0x00;
+const REG_MINUTES: u8 = 0x02;
+const REG_HOURS: u8 = 0x04;
+const REG_DAY: u8 = 0x07;
+const REG_MONTH: u8 = 0x08;
+const REG_YEAR: u8 = 0x09;
+const REG_STATUS_A: u8 = 0x0a;
+const REG_STATUS_B: u8 = 0x0b;
+
+const STATUS_A_UPDATE_IN_PROGRESS: u8 = 1 << 7;
+const STATUS_B_24_HOUR: u8 = 1 << 1;
+const STATUS_B_BINARY: u8 = 1 << 2;
+const HOUR_PM: u8 = 1 << 7;
+
+/// A wall-clock timestamp as read from the RTC, with no timezone (CMOS
+/// itself is usually UTC or local time depending on firmware setup, and
+/// there's nothing in this tree to tell the two apart yet).
+///
+/// `year` assumes the CMOS century register isn't reliably present the
+/// way the others are (real hardware is inconsistent about where, or
+/// whether, it exposes one) -- this just adds 2000 to CMOS's two-digit
+/// year, good until 2100.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl core::fmt::Display for DateTime {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second,
+        )
+    }
+}
+
+unsafe fn read_reg(reg: u8) -> u8 {
+    IoPort::<u8>::CMOS_INDEX.write(reg);
+    IoPort::<u8>::CMOS_DATA.read()
+}
+
+fn bcd_to_binary(value: u8) -> u8 {
+    (value & 0x0f) + ((value >> 4) * 10)
+}
+
+/// Reads the current wall-clock date/time from the CMOS RTC.
+///
+/// Spins on Status Register A's Update-In-Progress flag first, so this
+/// never reads the clock registers mid-tick and gets back a torn
+/// date/time. Handles both of CMOS's output modes -- BCD or binary
+/// digits, 12- or 24-hour hours -- via Status Register B, converting
+/// everything to binary/24-hour before returning.
+pub fn now() -> DateTime {
+    unsafe {
+        while read_reg(REG_STATUS_A) & STATUS_A_UPDATE_IN_PROGRESS != 0 {}
+
+        let mut second = read_reg(REG_SECONDS);
+        let mut minute = read_reg(REG_MINUTES);
+        let raw_hour = read_reg(REG_HOURS);
+        let mut day = read_reg(REG_DAY);
+        let mut month = read_reg(REG_MONTH);
+        let mut year = read_reg(REG_YEAR);
+        let status_b = read_reg(REG_STATUS_B);
+
+        let twenty_four_hour = status_b & STATUS_B_24_HOUR != 0;
+        let binary = status_b & STATUS_B_BINARY != 0;
+        let is_pm = !twenty_four_hour && raw_hour & HOUR_PM != 0;
+        let mut hour = raw_hour & !HOUR_PM;
+
+        if !binary {
+            second = bcd_to_binary(second);
+            minute = bcd_to_binary(minute);
+            hour = bcd_to_binary(hour);
+            day = bcd_to_binary(day);
+            month = bcd_to_binary(month);
+            year = bcd_to_binary(year);
+        }
+
+        if !twenty_four_hour {
+            hour = match (hour, is_pm) {
+                (12, false) => 0,
+                (12, true) => 12,
+                (h, true) => h + 12,
+                (h, false) => h,
+            };
+        }
+
+        DateTime {
+            year: 2000 + year as u16,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bcd_decodes_both_nibbles() {
+        assert_eq!(bcd_to_binary(0x00), 0);
+        assert_eq!(bcd_to_binary(0x09), 9);
+        assert_eq!(bcd_to_binary(0x23), 23);
+        assert_eq!(bcd_to_binary(0x59), 59);
+    }
+
+    #[test]
+    fn date_time_displays_as_a_sortable_timestamp() {
+        let dt = DateTime {
+            year: 2026,
+            month: 8,
+            day: 8,
+            hour: 9,
+            minute: 5,
+            second: 0,
+        };
+        assert_eq!(format!("{}", dt), "2026-08-08 09:05:00");
+    }
+}
@@ -0,0 +1,64 @@
+//! A minimal 8250/16550 UART driver for COM1, polled rather than
+//! interrupt-driven -- there's no IDT/APIC wiring in this tree yet (see
+//! [`super::super::interrupts`]) for an IRQ4 handler to hang off of.
+//!
+//! This exists so the panic handler has somewhere to write that doesn't
+//! depend on the framebuffer, at the same base address as
+//! [`super::io::IoPort::COM1`]. [`init`] programs the UART directly (baud divisor, 8N1,
+//! FIFO) rather than assuming it -- QEMU's default COM1 happens to come
+//! up usable without this, but real hardware doesn't promise that.
+use core::fmt::Write;
+
+use super::io::{IoAccess, IoPort};
+
+const COM1_BASE: u16 = 0x3f8;
+
+fn port(offset: u16) -> IoPort<u8> {
+    IoPort::<u8>::new(COM1_BASE + offset)
+}
+
+/// Programs COM1 for 38400 baud, 8 data bits, no parity, 1 stop bit,
+/// with FIFOs enabled. Safe to call more than once (e.g. speculatively,
+/// the same way [`super::super::graphics::emergency::init_once`] is) --
+/// it only ever reprograms the same fixed configuration.
+///
+/// # Safety
+/// Assumes COM1's I/O ports are present and not otherwise in use, same
+/// requirement as every other [`IoPort`] access in this kernel.
+pub unsafe fn init() {
+    port(1).write(0x00); // disable UART interrupts
+    port(3).write(0x80); // DLAB on to program the baud divisor
+    port(0).write(0x03); // divisor low byte: 115200 / 38400 = 3
+    port(1).write(0x00); // divisor high byte
+    port(3).write(0x03); // DLAB off, 8 data bits, no parity, 1 stop bit
+    port(2).write(0xc7); // enable FIFO, clear it, 14-byte trigger level
+    port(4).write(0x0b); // assert DTR/RTS and OUT2, enable the line for output
+}
+
+/// Blocks until the transmit holding register is empty, then writes one
+/// byte.
+///
+/// # Safety
+/// Same as [`init`]: the caller vouches that COM1 is present.
+pub unsafe fn write_byte(byte: u8) {
+    const THR_EMPTY: u8 = 0x20;
+    while port(5).read() & THR_EMPTY == 0 {}
+    port(0).write(byte);
+}
+
+/// A [`core::fmt::Write`] handle onto COM1, for `write!`/`writeln!` call
+/// sites such as the panic handler.
+///
+/// # Safety
+/// Same as [`init`]/[`write_byte`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Serial;
+
+impl Write for Serial {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for &b in s.as_bytes() {
+            unsafe { write_byte(b) };
+        }
+        Ok(())
+    }
+}
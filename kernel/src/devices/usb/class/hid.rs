@@ -0,0 +1,104 @@
+//! HID class-specific control requests (HID spec v1.11 §7.2) and the
+//! boot keyboard Output report they carry.
+//!
+//! There's no `Hid` type or `ClassDriver` trait in this tree (see
+//! [`super::hid_report`]'s module doc) for a `ClassDriver::set_leds` to
+//! live on, and `Hid::on_interrupt_completed`'s `Direction::Out` case
+//! doesn't exist either since `Hid` itself doesn't. This is the
+//! standalone half such a caller will need: the LED bitmap a boot
+//! keyboard's Output report carries, and the `Set_Report` Setup packet
+//! that would deliver it, built the same way
+//! [`super::super::request::SetupData::set_configuration`] builds the
+//! standard request it's modeled on.
+use super::super::request::{Direction, Recipient, RequestType, RequestTypeKind, SetupData};
+
+/// HID class-specific request codes (HID spec v1.11 §7.2), distinct from
+/// the standard [`super::super::request::RequestCode`] set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HidRequestCode {
+    GetReport = 0x01,
+    GetIdle = 0x02,
+    GetProtocol = 0x03,
+    SetReport = 0x09,
+    SetIdle = 0x0a,
+    SetProtocol = 0x0b,
+}
+
+/// `wValue`'s high byte for a `Get_Report`/`Set_Report` request (HID
+/// spec v1.11 §7.2.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportType {
+    Input = 1,
+    Output = 2,
+    Feature = 3,
+}
+
+/// The boot keyboard's one-byte Output report (HID spec v1.11 Appendix
+/// B.1): each bit is a lock LED's on/off state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeyboardLeds {
+    pub num_lock: bool,
+    pub caps_lock: bool,
+    pub scroll_lock: bool,
+    pub compose: bool,
+    pub kana: bool,
+}
+
+impl From<KeyboardLeds> for u8 {
+    fn from(leds: KeyboardLeds) -> Self {
+        let mut raw = leds.num_lock as u8;
+        raw |= (leds.caps_lock as u8) << 1;
+        raw |= (leds.scroll_lock as u8) << 2;
+        raw |= (leds.compose as u8) << 3;
+        raw |= (leds.kana as u8) << 4;
+        raw
+    }
+}
+
+/// Builds the Setup packet for a `Set_Report(Output, report_id)` request
+/// (HID spec v1.11 §7.2.2) targeting `interface`, with a one-byte data
+/// stage: issue it through a control transfer carrying
+/// `[leds.into()]` as the OUT data.
+pub fn set_report_output(interface: u8, report_id: u8, leds: KeyboardLeds) -> (SetupData, [u8; 1]) {
+    let setup = SetupData {
+        request_type: RequestType::new(Direction::Out, RequestTypeKind::Class, Recipient::Interface),
+        request: HidRequestCode::SetReport as u8,
+        value: ((ReportType::Output as u16) << 8) | report_id as u16,
+        index: interface as u16,
+        length: 1,
+    };
+    (setup, [leds.into()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caps_lock_sets_bit_one() {
+        let leds = KeyboardLeds { caps_lock: true, ..Default::default() };
+        assert_eq!(u8::from(leds), 0b0000_0010);
+    }
+
+    #[test]
+    fn all_leds_combine_without_overlap() {
+        let leds = KeyboardLeds {
+            num_lock: true,
+            caps_lock: true,
+            scroll_lock: true,
+            compose: true,
+            kana: true,
+        };
+        assert_eq!(u8::from(leds), 0b0001_1111);
+    }
+
+    #[test]
+    fn set_report_output_targets_the_given_interface_and_report_id() {
+        let (setup, data) = set_report_output(2, 0, KeyboardLeds { caps_lock: true, ..Default::default() });
+        assert_eq!(setup.request, HidRequestCode::SetReport as u8);
+        assert_eq!(setup.value, (ReportType::Output as u16) << 8);
+        assert_eq!(setup.index, 2);
+        assert_eq!(setup.length, 1);
+        assert_eq!(data, [0b0000_0010]);
+    }
+}
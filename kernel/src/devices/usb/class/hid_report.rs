@@ -0,0 +1,226 @@
+//! HID Report Descriptor parsing (HID spec v1.11 §6.2.2), as opposed to
+//! the class descriptor header (type/length) a `HidDescriptor` type
+//! would expose.
+//!
+//! There's no `HidDescriptor` type, `GetDescriptor(Report)` control
+//! transfer, or boot-protocol-vs-report-descriptor dispatch anywhere in
+//! this tree to fetch these bytes from -- [`super::super::request`] only
+//! knows `GetDescriptor` as a [`super::super::request::RequestCode`]
+//! value, not a built SETUP packet for this specific descriptor type,
+//! and there's no `Hid`/`ClassDriver` to own the fetched bytes either.
+//! This is the standalone parser such a fetch would hand its response
+//! to: given the raw report descriptor bytes, it walks the item stream
+//! (short items only -- long items, rare outside vendor-specific
+//! descriptors, are skipped rather than decoded) and extracts the Main
+//! and Global items a non-boot-protocol device's report layout actually
+//! needs: Usage Page, Report Size, Report Count, Input, and Output.
+
+pub const MAX_ITEMS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HidReportItem {
+    UsagePage(u32),
+    ReportSize(u32),
+    ReportCount(u32),
+    /// The Input item's data bits (HID spec §6.2.2.4): bit 0 =
+    /// Constant, bit 1 = Variable, bit 2 = Relative, ...
+    Input(u32),
+    Output(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HidReportError {
+    /// An item's prefix claimed more data bytes than are left in the
+    /// descriptor.
+    TooShort,
+    /// More items than [`MAX_ITEMS`] would decode to one of the
+    /// variants this parser tracks -- there's no heap to grow into, so
+    /// this caps out instead of overflowing.
+    TooManyItems,
+}
+
+impl core::fmt::Display for HidReportError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TooShort => write!(f, "item claimed more data than the descriptor has left"),
+            Self::TooManyItems => write!(f, "more items than this parser's fixed capacity"),
+        }
+    }
+}
+
+pub type Result<T> = core::result::Result<T, HidReportError>;
+
+/// Parsed items from a HID Report Descriptor, bounded the same way
+/// [`super::super::xhci::port_slot_map::PortSlotMap`] and the `acpi`
+/// module's tables are -- no heap in this kernel to grow a list into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HidReportDescriptor {
+    items: [Option<HidReportItem>; MAX_ITEMS],
+    count: usize,
+}
+
+/// Short item type field values (HID spec §6.2.2.2).
+const TYPE_MAIN: u8 = 0;
+const TYPE_GLOBAL: u8 = 1;
+
+/// Tags this parser cares about, within their respective type (HID spec
+/// §6.2.2.4, §6.2.2.7).
+const TAG_USAGE_PAGE: u8 = 0x0;
+const TAG_REPORT_SIZE: u8 = 0x7;
+const TAG_REPORT_COUNT: u8 = 0x9;
+const TAG_INPUT: u8 = 0x8;
+const TAG_OUTPUT: u8 = 0x9;
+
+/// The long-item escape prefix (HID spec §6.2.2.3): a short item's own
+/// type/tag bits are replaced by a dedicated one-byte data-size field
+/// and a one-byte long item tag, instead of being decoded as
+/// type=Reserved, tag=0xF.
+const LONG_ITEM_PREFIX: u8 = 0xfe;
+
+impl HidReportDescriptor {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let mut desc = Self {
+            items: [None; MAX_ITEMS],
+            count: 0,
+        };
+
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let prefix = bytes[offset];
+            offset += 1;
+
+            if prefix == LONG_ITEM_PREFIX {
+                let header = bytes.get(offset..offset + 2).ok_or(HidReportError::TooShort)?;
+                let data_size = header[0] as usize;
+                offset += 2;
+                offset = offset.checked_add(data_size).ok_or(HidReportError::TooShort)?;
+                if offset > bytes.len() {
+                    return Err(HidReportError::TooShort);
+                }
+                continue;
+            }
+
+            let size = match prefix & 0x3 {
+                0 => 0,
+                1 => 1,
+                2 => 2,
+                _ => 4,
+            };
+            let item_type = (prefix >> 2) & 0x3;
+            let tag = (prefix >> 4) & 0xf;
+
+            let data = bytes.get(offset..offset + size).ok_or(HidReportError::TooShort)?;
+            offset += size;
+            let value = read_le(data);
+
+            let item = match (item_type, tag) {
+                (TYPE_GLOBAL, TAG_USAGE_PAGE) => Some(HidReportItem::UsagePage(value)),
+                (TYPE_GLOBAL, TAG_REPORT_SIZE) => Some(HidReportItem::ReportSize(value)),
+                (TYPE_GLOBAL, TAG_REPORT_COUNT) => Some(HidReportItem::ReportCount(value)),
+                (TYPE_MAIN, TAG_INPUT) => Some(HidReportItem::Input(value)),
+                (TYPE_MAIN, TAG_OUTPUT) => Some(HidReportItem::Output(value)),
+                _ => None,
+            };
+            if let Some(item) = item {
+                desc.push(item)?;
+            }
+        }
+
+        Ok(desc)
+    }
+
+    fn push(&mut self, item: HidReportItem) -> Result<()> {
+        if self.count >= self.items.len() {
+            return Err(HidReportError::TooManyItems);
+        }
+        self.items[self.count] = Some(item);
+        self.count += 1;
+        Ok(())
+    }
+
+    pub fn items(&self) -> &[Option<HidReportItem>] {
+        &self.items[0..self.count]
+    }
+}
+
+fn read_le(data: &[u8]) -> u32 {
+    let mut value = 0u32;
+    for (i, &b) in data.iter().enumerate() {
+        value |= (b as u32) << (8 * i);
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The USB HID spec's Appendix B.1 example: a 6-key-rollover boot
+    // keyboard's report descriptor.
+    const BOOT_KEYBOARD: [u8; 63] = [
+        0x05, 0x01, 0x09, 0x06, 0xa1, 0x01, 0x05, 0x07, 0x19, 0xe0, 0x29, 0xe7, 0x15, 0x00, 0x25,
+        0x01, 0x75, 0x01, 0x95, 0x08, 0x81, 0x02, 0x95, 0x01, 0x75, 0x08, 0x81, 0x03, 0x95, 0x05,
+        0x75, 0x01, 0x05, 0x08, 0x19, 0x01, 0x29, 0x05, 0x91, 0x02, 0x95, 0x01, 0x75, 0x03, 0x91,
+        0x03, 0x95, 0x06, 0x75, 0x08, 0x15, 0x00, 0x25, 0x65, 0x05, 0x07, 0x19, 0x00, 0x29, 0x65,
+        0x81, 0x00, 0xc0,
+    ];
+
+    #[test]
+    fn parses_usage_page_report_size_and_report_count() {
+        let desc = HidReportDescriptor::parse(&BOOT_KEYBOARD).unwrap();
+        assert!(desc.items().contains(&Some(HidReportItem::UsagePage(0x01))));
+        assert!(desc.items().contains(&Some(HidReportItem::UsagePage(0x07))));
+        assert!(desc.items().contains(&Some(HidReportItem::ReportSize(1))));
+        assert!(desc.items().contains(&Some(HidReportItem::ReportSize(8))));
+        assert!(desc.items().contains(&Some(HidReportItem::ReportCount(8))));
+    }
+
+    #[test]
+    fn parses_input_and_output_items() {
+        let desc = HidReportDescriptor::parse(&BOOT_KEYBOARD).unwrap();
+        let inputs = desc.items().iter().filter(|i| matches!(i, Some(HidReportItem::Input(_)))).count();
+        let outputs = desc.items().iter().filter(|i| matches!(i, Some(HidReportItem::Output(_)))).count();
+        assert_eq!(inputs, 3);
+        assert_eq!(outputs, 2);
+    }
+
+    #[test]
+    fn two_byte_items_are_read_little_endian() {
+        // Usage Page (global, tag 0x0), size bits 0b10 (2 bytes), data 0x1234.
+        let bytes = [0b0000_0110u8, 0x34, 0x12];
+        let desc = HidReportDescriptor::parse(&bytes).unwrap();
+        assert_eq!(desc.items(), &[Some(HidReportItem::UsagePage(0x1234))]);
+    }
+
+    #[test]
+    fn truncated_item_data_is_an_error() {
+        let bytes = [0b0000_0110u8, 0x34]; // claims 2 bytes, only has 1
+        assert_eq!(HidReportDescriptor::parse(&bytes), Err(HidReportError::TooShort));
+    }
+
+    #[test]
+    fn display_gives_a_human_message() {
+        assert_eq!(
+            format!("{}", HidReportError::TooShort),
+            "item claimed more data than the descriptor has left"
+        );
+    }
+
+    #[test]
+    fn long_items_are_skipped_rather_than_decoded() {
+        // Long item: prefix 0xFE, data size 2, long tag 0x01, 2 bytes of data,
+        // followed by a normal Report Count(3) short item.
+        let bytes = [0xfe, 0x02, 0x01, 0xaa, 0xbb, 0x95, 0x03];
+        let desc = HidReportDescriptor::parse(&bytes).unwrap();
+        assert_eq!(desc.items(), &[Some(HidReportItem::ReportCount(3))]);
+    }
+
+    #[test]
+    fn unrecognized_items_are_skipped_without_failing_the_parse() {
+        // Collection (main, tag 0xA) then End Collection (main, tag 0xC):
+        // neither is one of the variants this parser tracks.
+        let bytes = [0xa1, 0x01, 0xc0];
+        let desc = HidReportDescriptor::parse(&bytes).unwrap();
+        assert_eq!(desc.items(), &[]);
+    }
+}
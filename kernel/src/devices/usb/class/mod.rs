@@ -1 +1,2 @@
-
+pub mod hid;
+pub mod hid_report;
@@ -0,0 +1,490 @@
+//! Walks a Configuration descriptor bundle (USB 2.0 spec §9.6.3): the
+//! back-to-back set of descriptors returned after a device's Configuration
+//! descriptor by `GET_DESCRIPTOR(Configuration)`.
+use core::fmt;
+
+use super::descriptor::{Configuration, Endpoint, Interface, InterfaceAssociation};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// A descriptor header claims a `bLength` that is too short to be a
+    /// valid header (`< 2`) or to hold its type's fixed fields, or that
+    /// runs past the end of the bundle.
+    MalformedConfigBundle { offset: usize, blength: u8 },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MalformedConfigBundle { offset, blength } => write!(
+                f,
+                "malformed descriptor at offset {offset}: bLength={blength} is too short or runs past the bundle's end"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DescriptorType {
+    Device,
+    Configuration,
+    String,
+    Interface,
+    Endpoint,
+    Hid,
+    Report,
+    InterfaceAssociation,
+}
+
+impl TryFrom<u8> for DescriptorType {
+    type Error = u8;
+
+    fn try_from(raw: u8) -> Result<Self, Self::Error> {
+        Ok(match raw {
+            1 => Self::Device,
+            2 => Self::Configuration,
+            3 => Self::String,
+            4 => Self::Interface,
+            5 => Self::Endpoint,
+            33 => Self::Hid,
+            34 => Self::Report,
+            11 => Self::InterfaceAssociation,
+            other => return Err(other),
+        })
+    }
+}
+
+/// Descriptor types this reader currently surfaces to callers.
+///
+/// Devices interleave class-specific descriptors this reader doesn't
+/// understand yet; those are skipped rather than treated
+/// as errors (see [`ConfigDescReader`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Supported {
+    Configuration(Configuration),
+    Interface(Interface),
+    Endpoint(Endpoint),
+    InterfaceAssociation(InterfaceAssociation),
+}
+
+impl Supported {
+    /// `bytes` is the full declared extent of the descriptor (i.e.
+    /// `bytes.len() == bLength`), already validated to lie within the
+    /// bundle by the caller.
+    fn try_from_bytes(ty: DescriptorType, offset: usize, bytes: &[u8]) -> Result<Option<Self>, Error> {
+        fn fixed<const N: usize>(offset: usize, bytes: &[u8]) -> Result<[u8; N], Error> {
+            if bytes.len() < N {
+                return Err(Error::MalformedConfigBundle {
+                    offset,
+                    blength: bytes.len() as u8,
+                });
+            }
+            let mut arr = [0u8; N];
+            arr.copy_from_slice(&bytes[..N]);
+            Ok(arr)
+        }
+
+        Ok(match ty {
+            DescriptorType::Configuration => {
+                Some(Self::Configuration(Configuration(fixed(offset, bytes)?)))
+            }
+            DescriptorType::Interface => Some(Self::Interface(Interface(fixed(offset, bytes)?))),
+            DescriptorType::Endpoint => Some(Self::Endpoint(Endpoint(fixed(offset, bytes)?))),
+            DescriptorType::InterfaceAssociation => Some(Self::InterfaceAssociation(
+                InterfaceAssociation(fixed(offset, bytes)?),
+            )),
+            // Recognized but not modeled as a struct yet:
+            // the caller skips these rather than erroring.
+            DescriptorType::Device | DescriptorType::String | DescriptorType::Hid | DescriptorType::Report => {
+                None
+            }
+        })
+    }
+}
+
+/// A descriptor yielded by [`ConfigDescReader`], tagged with the Interface
+/// Association Descriptor (if any) most recently seen before it — composite
+/// devices (audio+HID, CDC) group a run of interfaces into one function via
+/// an IAD, and callers need that grouping to instantiate one class driver
+/// per function rather than per interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    pub association: Option<InterfaceAssociation>,
+    pub descriptor: Supported,
+}
+
+/// Iterates the descriptors packed into a Configuration descriptor bundle.
+///
+/// Guards against the two ways a malformed bundle can run the reader off
+/// the rails: a `bLength` of 0 (which would otherwise loop forever on the
+/// same offset) and a `bLength` longer than the remaining buffer (which
+/// would otherwise slice out of bounds). Either stops iteration with a
+/// single [`Error::MalformedConfigBundle`]. Descriptor types this reader
+/// doesn't model yet are skipped, not errored on.
+pub struct ConfigDescReader<'a> {
+    buf: &'a [u8],
+    cursor: usize,
+    current_association: Option<InterfaceAssociation>,
+}
+
+impl<'a> ConfigDescReader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self {
+            buf,
+            cursor: 0,
+            current_association: None,
+        }
+    }
+}
+
+impl<'a> Iterator for ConfigDescReader<'a> {
+    type Item = Result<Entry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.cursor >= self.buf.len() {
+                return None;
+            }
+
+            let base = self.cursor;
+            let blength = self.buf[base];
+            if blength < 2 || base + blength as usize > self.buf.len() {
+                self.cursor = self.buf.len(); // stop iterating after reporting the error once
+                return Some(Err(Error::MalformedConfigBundle { offset: base, blength }));
+            }
+
+            let end = base + blength as usize;
+            self.cursor = end;
+
+            let raw_type = self.buf[base + 1];
+            let bytes = &self.buf[base..end];
+            let ty = match DescriptorType::try_from(raw_type) {
+                Ok(ty) => ty,
+                Err(_unknown) => continue, // not a type we know about at all: skip
+            };
+
+            match Supported::try_from_bytes(ty, base, bytes) {
+                Ok(Some(Supported::InterfaceAssociation(iad))) => {
+                    // Remembered for subsequent interfaces/endpoints rather
+                    // than yielded on its own; it exists purely to group them.
+                    self.current_association = Some(iad);
+                    continue;
+                }
+                Ok(Some(descriptor)) => {
+                    return Some(Ok(Entry {
+                        association: self.current_association,
+                        descriptor,
+                    }))
+                }
+                Ok(None) => continue, // known type we don't model yet: skip
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// One Interface descriptor and up to `N` [`Endpoint`]s that followed it,
+/// before the next Interface (or the bundle's end).
+///
+/// `N` bounds the endpoint count instead of a `Vec` -- there's no heap
+/// allocator in this kernel yet (see `status_bar::Stats`'s doc for the
+/// same tradeoff). An interface reporting more than `N` endpoints has
+/// the excess silently dropped rather than erroring the whole bundle;
+/// callers sizing `N` from `bNumEndpoints` at the high end of what they
+/// expect to see won't hit this in practice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterfaceGroup<const N: usize> {
+    pub association: Option<InterfaceAssociation>,
+    pub interface: Interface,
+    endpoints: [Endpoint; N],
+    endpoint_count: usize,
+}
+
+impl<const N: usize> InterfaceGroup<N> {
+    pub fn endpoints(&self) -> &[Endpoint] {
+        &self.endpoints[..self.endpoint_count]
+    }
+}
+
+/// Groups a [`ConfigDescReader`]'s flat descriptor stream into one
+/// [`InterfaceGroup`] per Interface descriptor.
+///
+/// Unlike [`ConfigDescReader`], which already tolerates interleaved
+/// descriptor types it doesn't model (HID, vendor-specific, ...) by
+/// skipping them, this additionally tolerates them appearing *between*
+/// an interface's endpoints rather than requiring a strict
+/// Interface-then-`bNumEndpoints` ordering: anything that isn't an
+/// `Endpoint` or the next `Interface` simply doesn't end the group. A
+/// stray `Endpoint` before any `Interface` has been seen is dropped --
+/// there's nothing to attach it to -- rather than starting a group with
+/// no interface to go with it.
+pub struct InterfaceGroupReader<'a, const N: usize> {
+    reader: ConfigDescReader<'a>,
+    /// An entry already pulled off `reader` that starts the next group;
+    /// stashed here because a group isn't known to be complete until
+    /// the entry *after* its last endpoint is seen.
+    pending: Option<Entry>,
+}
+
+impl<'a, const N: usize> InterfaceGroupReader<'a, N> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self {
+            reader: ConfigDescReader::new(buf),
+            pending: None,
+        }
+    }
+}
+
+impl<'a, const N: usize> Iterator for InterfaceGroupReader<'a, N> {
+    type Item = Result<InterfaceGroup<N>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = match self.pending.take() {
+            Some(entry) => entry,
+            None => loop {
+                match self.reader.next()? {
+                    Ok(entry) if matches!(entry.descriptor, Supported::Interface(_)) => break entry,
+                    Ok(_) => continue, // no interface open yet to attach this to
+                    Err(e) => return Some(Err(e)),
+                }
+            },
+        };
+        let Supported::Interface(interface) = first.descriptor else {
+            unreachable!("the loop above only breaks on a Supported::Interface entry")
+        };
+
+        let mut group = InterfaceGroup {
+            association: first.association,
+            interface,
+            endpoints: [Endpoint([0; Endpoint::LENGTH]); N],
+            endpoint_count: 0,
+        };
+
+        loop {
+            match self.reader.next() {
+                Some(Ok(entry)) => match entry.descriptor {
+                    Supported::Endpoint(ep) if group.endpoint_count < N => {
+                        group.endpoints[group.endpoint_count] = ep;
+                        group.endpoint_count += 1;
+                    }
+                    Supported::Endpoint(_) => {} // over N for this interface: dropped, see InterfaceGroup's doc
+                    Supported::Interface(_) => {
+                        self.pending = Some(entry);
+                        break;
+                    }
+                    // Not an Endpoint or the next Interface: doesn't end the group.
+                    Supported::Configuration(_) | Supported::InterfaceAssociation(_) => {}
+                },
+                Some(Err(e)) => return Some(Err(e)),
+                None => break,
+            }
+        }
+
+        Some(Ok(group))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interface_bytes() -> [u8; 9] {
+        [9, 4, 0, 0, 1, 0xff, 0, 0, 0]
+    }
+
+    fn endpoint_bytes() -> [u8; 7] {
+        [7, 5, 0x81, 0x03, 0x08, 0x00, 0x0a]
+    }
+
+    #[test]
+    fn zero_length_descriptor_errors_instead_of_looping() {
+        let buf = [0u8, 4, 0, 0];
+        let mut reader = ConfigDescReader::new(&buf);
+        assert_eq!(
+            reader.next(),
+            Some(Err(Error::MalformedConfigBundle { offset: 0, blength: 0 }))
+        );
+        assert_eq!(reader.next(), None, "reader must not loop forever or re-yield");
+    }
+
+    #[test]
+    fn truncated_descriptor_errors_instead_of_slicing_oob() {
+        let mut buf = interface_bytes().to_vec();
+        buf[0] = 200; // claims far more than the buffer actually holds
+        let mut reader = ConfigDescReader::new(&buf);
+        assert_eq!(
+            reader.next(),
+            Some(Err(Error::MalformedConfigBundle { offset: 0, blength: 200 }))
+        );
+        assert_eq!(reader.next(), None);
+    }
+
+    fn iad_bytes(first_interface: u8, interface_count: u8) -> [u8; 8] {
+        [8, 0x0b, first_interface, interface_count, 0x01, 0x02, 0x03, 0]
+    }
+
+    #[test]
+    fn unknown_descriptor_types_are_skipped_not_errored() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&[4, 0x99, 0, 0]); // not a DescriptorType at all
+        buf.extend_from_slice(&interface_bytes());
+        let mut reader = ConfigDescReader::new(&buf);
+        assert_eq!(
+            reader.next(),
+            Some(Ok(Entry {
+                association: None,
+                descriptor: Supported::Interface(Interface(interface_bytes())),
+            }))
+        );
+        assert_eq!(reader.next(), None);
+    }
+
+    #[test]
+    fn reads_interface_then_endpoint() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&interface_bytes());
+        buf.extend_from_slice(&endpoint_bytes());
+        let mut reader = ConfigDescReader::new(&buf);
+        assert_eq!(
+            reader.next(),
+            Some(Ok(Entry {
+                association: None,
+                descriptor: Supported::Interface(Interface(interface_bytes())),
+            }))
+        );
+        assert_eq!(
+            reader.next(),
+            Some(Ok(Entry {
+                association: None,
+                descriptor: Supported::Endpoint(Endpoint(endpoint_bytes())),
+            }))
+        );
+        assert_eq!(reader.next(), None);
+    }
+
+    #[test]
+    fn interface_association_descriptor_tags_following_entries() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&iad_bytes(0, 2));
+        buf.extend_from_slice(&interface_bytes());
+        buf.extend_from_slice(&endpoint_bytes());
+        let mut reader = ConfigDescReader::new(&buf);
+
+        let expected_association = Some(InterfaceAssociation(iad_bytes(0, 2)));
+        assert_eq!(
+            reader.next(),
+            Some(Ok(Entry {
+                association: expected_association,
+                descriptor: Supported::Interface(Interface(interface_bytes())),
+            }))
+        );
+        assert_eq!(
+            reader.next(),
+            Some(Ok(Entry {
+                association: expected_association,
+                descriptor: Supported::Endpoint(Endpoint(endpoint_bytes())),
+            }))
+        );
+        assert_eq!(reader.next(), None);
+    }
+
+    fn endpoint_bytes2() -> [u8; 7] {
+        [7, 5, 0x02, 0x03, 0x08, 0x00, 0x0a]
+    }
+
+    #[test]
+    fn groups_endpoints_under_their_interface() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&interface_bytes());
+        buf.extend_from_slice(&endpoint_bytes());
+        buf.extend_from_slice(&endpoint_bytes2());
+        let mut reader: InterfaceGroupReader<4> = InterfaceGroupReader::new(&buf);
+
+        let group = reader.next().unwrap().unwrap();
+        assert_eq!(group.interface, Interface(interface_bytes()));
+        assert_eq!(
+            group.endpoints(),
+            &[Endpoint(endpoint_bytes()), Endpoint(endpoint_bytes2())]
+        );
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn tolerates_descriptors_interleaved_between_endpoints() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&interface_bytes());
+        buf.extend_from_slice(&endpoint_bytes());
+        buf.extend_from_slice(&[4, 0x99, 0, 0]); // not a DescriptorType at all (e.g. a HID descriptor)
+        buf.extend_from_slice(&endpoint_bytes2());
+        let mut reader: InterfaceGroupReader<4> = InterfaceGroupReader::new(&buf);
+
+        let group = reader.next().unwrap().unwrap();
+        assert_eq!(
+            group.endpoints(),
+            &[Endpoint(endpoint_bytes()), Endpoint(endpoint_bytes2())]
+        );
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn endpoints_beyond_capacity_are_dropped_not_errored() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&interface_bytes());
+        buf.extend_from_slice(&endpoint_bytes());
+        buf.extend_from_slice(&endpoint_bytes2());
+        let mut reader: InterfaceGroupReader<1> = InterfaceGroupReader::new(&buf);
+
+        let group = reader.next().unwrap().unwrap();
+        assert_eq!(group.endpoints(), &[Endpoint(endpoint_bytes())]);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn a_second_interface_starts_a_new_group() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&interface_bytes());
+        buf.extend_from_slice(&endpoint_bytes());
+        buf.extend_from_slice(&interface_bytes());
+        buf.extend_from_slice(&endpoint_bytes2());
+        let mut reader: InterfaceGroupReader<4> = InterfaceGroupReader::new(&buf);
+
+        assert_eq!(
+            reader.next().unwrap().unwrap().endpoints(),
+            &[Endpoint(endpoint_bytes())]
+        );
+        assert_eq!(
+            reader.next().unwrap().unwrap().endpoints(),
+            &[Endpoint(endpoint_bytes2())]
+        );
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn a_stray_endpoint_before_any_interface_is_dropped() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&endpoint_bytes());
+        buf.extend_from_slice(&interface_bytes());
+        buf.extend_from_slice(&endpoint_bytes2());
+        let mut reader: InterfaceGroupReader<4> = InterfaceGroupReader::new(&buf);
+
+        let group = reader.next().unwrap().unwrap();
+        assert_eq!(group.endpoints(), &[Endpoint(endpoint_bytes2())]);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn a_malformed_descriptor_is_reported_and_stops_iteration() {
+        let mut buf = interface_bytes().to_vec();
+        buf.extend_from_slice(&[0, 5, 0, 0, 0, 0, 0]); // blength 0: malformed
+        let mut reader: InterfaceGroupReader<4> = InterfaceGroupReader::new(&buf);
+
+        assert_eq!(
+            reader.next(),
+            Some(Err(Error::MalformedConfigBundle {
+                offset: interface_bytes().len(),
+                blength: 0,
+            }))
+        );
+        assert!(reader.next().is_none());
+    }
+}
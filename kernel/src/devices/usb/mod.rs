@@ -1 +1,14 @@
 mod class;
+pub mod config_desc_reader;
+pub mod descriptor;
+pub mod names;
+pub mod request;
+pub mod utils;
+pub mod xhci;
+
+pub use xhci::HostController;
+
+// Not reached through this re-export by anything outside `xhci` yet --
+// every current caller reaches these through `xhci::endpoint` directly.
+#[allow(unused_imports)]
+pub use xhci::{DeviceContextIndex, EndpointId};
@@ -0,0 +1,77 @@
+//! USB interface class/subclass/protocol naming (USB 2.0 spec Table
+//! B-1-ish -- a small, hand-picked subset, not the full assigned-classes
+//! table), for the same "friendlier than raw hex" reason as
+//! [`crate::devices::pci::names`].
+//!
+//! There's no enumeration loop walking [`super::config_desc_reader::ConfigDescReader`]
+//! and logging the `Interface` descriptors it yields yet (the request
+//! that asked for this assumed one) -- `main.rs` never calls
+//! `ConfigDescReader` today -- so this is the standalone lookup such a
+//! log line would call, ready for when that loop exists.
+
+/// One entry of [`INTERFACE_CLASS_TABLE`]. `sub_class`/`protocol: None`
+/// match any value, the same fallback convention
+/// `devices::pci::common::ClassCodeEntry` uses.
+struct InterfaceClassEntry {
+    class: u8,
+    sub_class: Option<u8>,
+    protocol: Option<u8>,
+    name: &'static str,
+}
+
+/// Interface-specific entries are listed before their class-only
+/// fallback so [`interface_class_name`], which takes the first match,
+/// prefers the more specific name.
+const INTERFACE_CLASS_TABLE: &[InterfaceClassEntry] = &[
+    InterfaceClassEntry { class: 0x03, sub_class: Some(0x01), protocol: Some(0x01), name: "Human Interface Device / Boot Keyboard" },
+    InterfaceClassEntry { class: 0x03, sub_class: Some(0x01), protocol: Some(0x02), name: "Human Interface Device / Boot Mouse" },
+    InterfaceClassEntry { class: 0x01, sub_class: None, protocol: None, name: "Audio" },
+    InterfaceClassEntry { class: 0x02, sub_class: None, protocol: None, name: "Communications and CDC Control" },
+    InterfaceClassEntry { class: 0x03, sub_class: None, protocol: None, name: "Human Interface Device" },
+    InterfaceClassEntry { class: 0x08, sub_class: None, protocol: None, name: "Mass Storage" },
+    InterfaceClassEntry { class: 0x09, sub_class: None, protocol: None, name: "Hub" },
+    InterfaceClassEntry { class: 0x0a, sub_class: None, protocol: None, name: "CDC Data" },
+    InterfaceClassEntry { class: 0x0e, sub_class: None, protocol: None, name: "Video" },
+    InterfaceClassEntry { class: 0xe0, sub_class: None, protocol: None, name: "Wireless Controller" },
+    InterfaceClassEntry { class: 0xef, sub_class: None, protocol: None, name: "Miscellaneous" },
+    InterfaceClassEntry { class: 0xfe, sub_class: None, protocol: None, name: "Application Specific" },
+    InterfaceClassEntry { class: 0xff, sub_class: None, protocol: None, name: "Vendor Specific" },
+];
+
+/// Name for an interface's `(class, sub_class, protocol)` triple
+/// (USB 2.0 spec §9.6.5's `bInterfaceClass`/`bInterfaceSubClass`/
+/// `bInterfaceProtocol`), or `"Unknown"` for anything outside the
+/// hand-picked [`INTERFACE_CLASS_TABLE`] rather than guessing.
+pub fn interface_class_name(class: u8, sub_class: u8, protocol: u8) -> &'static str {
+    INTERFACE_CLASS_TABLE
+        .iter()
+        .find(|e| {
+            e.class == class
+                && e.sub_class.map_or(true, |s| s == sub_class)
+                && e.protocol.map_or(true, |p| p == protocol)
+        })
+        .map_or("Unknown", |e| e.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_a_protocol_specific_entry_before_the_class_fallback() {
+        assert_eq!(
+            interface_class_name(0x03, 0x01, 0x01),
+            "Human Interface Device / Boot Keyboard"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_class_only_entry_for_an_unmatched_protocol() {
+        assert_eq!(interface_class_name(0x03, 0x01, 0x00), "Human Interface Device");
+    }
+
+    #[test]
+    fn unknown_class_falls_back_to_unknown() {
+        assert_eq!(interface_class_name(0x12, 0x00, 0x00), "Unknown");
+    }
+}
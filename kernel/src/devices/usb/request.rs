@@ -0,0 +1,260 @@
+//! USB control-transfer Setup packet (USB 2.0 spec §9.3) and its xHCI
+//! Setup Stage TRB encoding.
+use bit_field::BitField;
+
+/// Transfer direction, bit 7 of `bmRequestType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Out,
+    In,
+}
+
+/// `bmRequestType` bits 5..=6 (USB 2.0 spec Table 9-2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestTypeKind {
+    Standard = 0,
+    Class = 1,
+    Vendor = 2,
+}
+
+/// `bmRequestType` bits 0..=4 (USB 2.0 spec Table 9-2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recipient {
+    Device = 0,
+    Interface = 1,
+    Endpoint = 2,
+    Other = 3,
+}
+
+/// `bmRequestType` of a Setup packet.
+///
+/// Per USB 2.0 spec Table 9-2: bit 7 is direction, bits 5..=6 are the
+/// request type (2 bits), and bits 0..=4 are the recipient (5 bits).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RequestType(u8);
+
+impl RequestType {
+    pub fn new(direction: Direction, kind: RequestTypeKind, recipient: Recipient) -> Self {
+        let mut raw = 0u8;
+        raw.set_bits(0..=4, recipient as u8);
+        raw.set_bits(5..=6, kind as u8);
+        raw.set_bit(7, matches!(direction, Direction::In));
+        Self(raw)
+    }
+
+    pub fn direction(&self) -> Direction {
+        if self.0.get_bit(7) {
+            Direction::In
+        } else {
+            Direction::Out
+        }
+    }
+
+    pub fn as_raw(&self) -> u8 {
+        self.0
+    }
+}
+
+impl From<u8> for RequestType {
+    fn from(raw: u8) -> Self {
+        Self(raw)
+    }
+}
+
+/// Standard request codes (USB 2.0 spec Table 9-4). Class/vendor requests
+/// use the raw `bRequest` byte in [`SetupData::request`] directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestCode {
+    GetStatus = 0,
+    ClearFeature = 1,
+    SetFeature = 3,
+    SetAddress = 5,
+    GetDescriptor = 6,
+    SetDescriptor = 7,
+    GetConfiguration = 8,
+    SetConfiguration = 9,
+    GetInterface = 10,
+    SetInterface = 11,
+    SyncFrame = 12,
+}
+
+impl TryFrom<u8> for RequestCode {
+    type Error = u8;
+
+    fn try_from(raw: u8) -> Result<Self, Self::Error> {
+        Ok(match raw {
+            0 => Self::GetStatus,
+            1 => Self::ClearFeature,
+            3 => Self::SetFeature,
+            5 => Self::SetAddress,
+            6 => Self::GetDescriptor,
+            7 => Self::SetDescriptor,
+            8 => Self::GetConfiguration,
+            9 => Self::SetConfiguration,
+            10 => Self::GetInterface,
+            11 => Self::SetInterface,
+            12 => Self::SyncFrame,
+            other => return Err(other),
+        })
+    }
+}
+
+/// A USB control-transfer Setup packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetupData {
+    pub request_type: RequestType,
+    pub request: u8,
+    pub value: u16,
+    pub index: u16,
+    pub length: u16,
+}
+
+/// The two dwords making up an xHCI Setup Stage TRB's parameter field:
+/// `[bmRequestType | bRequest << 8 | wValue << 16, wIndex | wLength << 16]`.
+impl From<SetupData> for [u32; 2] {
+    fn from(data: SetupData) -> Self {
+        let mut dw0 = 0u32;
+        dw0.set_bits(0..=7, data.request_type.as_raw() as u32);
+        dw0.set_bits(8..=15, data.request as u32);
+        dw0.set_bits(16..=31, data.value as u32);
+
+        let mut dw1 = 0u32;
+        dw1.set_bits(0..=15, data.index as u32);
+        dw1.set_bits(16..=31, data.length as u32);
+
+        [dw0, dw1]
+    }
+}
+
+impl TryFrom<[u32; 2]> for SetupData {
+    type Error = core::convert::Infallible;
+
+    fn try_from(raw: [u32; 2]) -> Result<Self, Self::Error> {
+        Ok(Self {
+            request_type: RequestType::from(raw[0].get_bits(0..=7) as u8),
+            request: raw[0].get_bits(8..=15) as u8,
+            value: raw[0].get_bits(16..=31) as u16,
+            index: raw[1].get_bits(0..=15) as u16,
+            length: raw[1].get_bits(16..=31) as u16,
+        })
+    }
+}
+
+impl SetupData {
+    /// Builds the Setup packet for a standard `SET_CONFIGURATION` request
+    /// (USB 2.0 spec §9.4.7), selecting `configuration_value` (from the
+    /// device's parsed `Configuration` descriptor) as the active
+    /// configuration. Status stage only, no data stage: issue it through
+    /// a control transfer with `buf = None`.
+    ///
+    /// A device's non-default endpoints aren't usable until this has
+    /// been issued and acknowledged.
+    pub fn set_configuration(configuration_value: u8) -> Self {
+        Self {
+            request_type: RequestType::new(Direction::Out, RequestTypeKind::Standard, Recipient::Device),
+            request: RequestCode::SetConfiguration as u8,
+            value: configuration_value as u16,
+            index: 0,
+            length: 0,
+        }
+    }
+}
+
+/// The Setup Stage TRB's parameter field, kept distinct from the raw
+/// `[u32; 2]` so call sites aren't tempted to reinterpret arbitrary dwords.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetupStage(pub [u32; 2]);
+
+impl From<SetupData> for SetupStage {
+    fn from(data: SetupData) -> Self {
+        Self(data.into())
+    }
+}
+
+impl TryFrom<SetupStage> for SetupData {
+    type Error = core::convert::Infallible;
+
+    fn try_from(stage: SetupStage) -> Result<Self, Self::Error> {
+        Self::try_from(stage.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_CODES: [RequestCode; 11] = [
+        RequestCode::GetStatus,
+        RequestCode::ClearFeature,
+        RequestCode::SetFeature,
+        RequestCode::SetAddress,
+        RequestCode::GetDescriptor,
+        RequestCode::SetDescriptor,
+        RequestCode::GetConfiguration,
+        RequestCode::SetConfiguration,
+        RequestCode::GetInterface,
+        RequestCode::SetInterface,
+        RequestCode::SyncFrame,
+    ];
+    const ALL_DIRECTIONS: [Direction; 2] = [Direction::Out, Direction::In];
+
+    fn sample_setup_data(code: RequestCode, direction: Direction) -> SetupData {
+        SetupData {
+            request_type: RequestType::new(direction, RequestTypeKind::Standard, Recipient::Device),
+            request: code as u8,
+            value: 0x1234,
+            index: 0x5678,
+            length: 0x9abc,
+        }
+    }
+
+    #[test]
+    fn roundtrips_through_raw_dwords() {
+        for &code in ALL_CODES.iter() {
+            for &direction in ALL_DIRECTIONS.iter() {
+                let data = sample_setup_data(code, direction);
+                let raw: [u32; 2] = data.into();
+                assert_eq!(SetupData::try_from(raw).unwrap(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn roundtrips_through_setup_stage() {
+        for &code in ALL_CODES.iter() {
+            for &direction in ALL_DIRECTIONS.iter() {
+                let data = sample_setup_data(code, direction);
+                let stage = SetupStage::from(data);
+                assert_eq!(SetupData::try_from(stage).unwrap(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn length_and_index_do_not_alias() {
+        let data = sample_setup_data(RequestCode::GetDescriptor, Direction::In);
+        let raw: [u32; 2] = data.into();
+        let back = SetupData::try_from(raw).unwrap();
+        assert_eq!(back.index, 0x5678);
+        assert_eq!(back.length, 0x9abc);
+    }
+
+    #[test]
+    fn set_configuration_is_a_status_only_out_request() {
+        let setup = SetupData::set_configuration(3);
+        assert_eq!(setup.request, RequestCode::SetConfiguration as u8);
+        assert_eq!(setup.request_type.direction(), Direction::Out);
+        assert_eq!(setup.value, 3);
+        assert_eq!(setup.length, 0, "SET_CONFIGURATION has no data stage");
+    }
+
+    #[test]
+    fn request_type_bit_layout_matches_spec() {
+        let rt = RequestType::new(Direction::In, RequestTypeKind::Class, Recipient::Endpoint);
+        let raw = rt.as_raw();
+        assert_eq!(raw.get_bits(0..=4), Recipient::Endpoint as u8);
+        assert_eq!(raw.get_bits(5..=6), RequestTypeKind::Class as u8);
+        assert!(raw.get_bit(7));
+        assert_eq!(rt.direction(), Direction::In);
+    }
+}
@@ -0,0 +1,79 @@
+//! Small USB enumeration helpers that don't have a more specific home
+//! yet (there's no `Device`/transfer-ring-setup code in this tree to
+//! call them from today).
+
+/// USB port speed, keyed by the xHCI root hub's default Protocol Speed
+/// ID mapping for USB2/3 (xHCI spec Table 5-18): `1` = Full, `2` = Low,
+/// `3` = High, `4` = SuperSpeed. A port reporting a non-default PSIV
+/// (a value outside 1..=4) uses a protocol-specific speed ID table this
+/// kernel doesn't decode yet, hence [`PortSpeed::from_xhci_psiv`]
+/// returning `None` rather than guessing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortSpeed {
+    Low,
+    Full,
+    High,
+    Super,
+}
+
+impl PortSpeed {
+    pub fn from_xhci_psiv(value: u8) -> Option<Self> {
+        Some(match value {
+            1 => Self::Full,
+            2 => Self::Low,
+            3 => Self::High,
+            4 => Self::Super,
+            _ => return None,
+        })
+    }
+}
+
+/// EP0's max packet size for `speed`, per the USB spec.
+///
+/// SuperSpeed (and SuperSpeed+) fixes this at 512 bytes -- unlike USB2,
+/// `bMaxPacketSize0` isn't even consulted to pick it. High Speed is
+/// always 64, Low Speed is always 8, and Full Speed is 8/16/32/64
+/// reported by the device itself in `bMaxPacketSize0`; until the first
+/// 8-byte `GetDescriptor(Device)` comes back, 8 is the only size every
+/// full-speed device is guaranteed to accept, so that's what this
+/// returns for `Full`; the real value is read back afterwards from the
+/// Device descriptor's `bMaxPacketSize0` (see
+/// [`super::xhci::EnumerationPhase`]).
+pub fn get_max_packet_size(speed: PortSpeed) -> u16 {
+    match speed {
+        PortSpeed::Low => 8,
+        PortSpeed::Full => 8,
+        PortSpeed::High => 64,
+        PortSpeed::Super => 512,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn super_speed_is_fixed_at_512() {
+        assert_eq!(get_max_packet_size(PortSpeed::Super), 512);
+    }
+
+    #[test]
+    fn high_speed_is_fixed_at_64() {
+        assert_eq!(get_max_packet_size(PortSpeed::High), 64);
+    }
+
+    #[test]
+    fn low_and_initial_full_speed_are_8() {
+        assert_eq!(get_max_packet_size(PortSpeed::Low), 8);
+        assert_eq!(get_max_packet_size(PortSpeed::Full), 8);
+    }
+
+    #[test]
+    fn psiv_maps_to_the_default_xhci_speed_ids() {
+        assert_eq!(PortSpeed::from_xhci_psiv(1), Some(PortSpeed::Full));
+        assert_eq!(PortSpeed::from_xhci_psiv(2), Some(PortSpeed::Low));
+        assert_eq!(PortSpeed::from_xhci_psiv(3), Some(PortSpeed::High));
+        assert_eq!(PortSpeed::from_xhci_psiv(4), Some(PortSpeed::Super));
+        assert_eq!(PortSpeed::from_xhci_psiv(0), None);
+    }
+}
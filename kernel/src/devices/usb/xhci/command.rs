@@ -0,0 +1,207 @@
+//! Command ring TRBs. There's still no command ring, input context, or
+//! `Device`/`DeviceManager` in this tree to enqueue these onto or to
+//! decode a Command Completion Event for, so, like
+//! [`super::super::request::SetupStage`], this is encoding logic with
+//! nothing downstream of it yet.
+use bit_field::BitField;
+
+use super::endpoint::DeviceContextIndex;
+
+/// TRB Type field values this module knows how to encode (xHCI spec
+/// Table 6-86). Only the ones a command TRB builder here needs.
+const TRB_TYPE_EVALUATE_CONTEXT: u32 = 13;
+const TRB_TYPE_RESET_ENDPOINT: u32 = 14;
+const TRB_TYPE_SET_TR_DEQUEUE_POINTER: u32 = 16;
+
+/// xHCI Evaluate Context Command (xHCI spec §6.4.3.6): re-evaluates a
+/// device slot's Input Context against whichever of its sub-contexts
+/// are marked in the Input Control Context's Add Context flags, without
+/// re-running Address Device or Configure Endpoint. Used for updates
+/// that don't add or remove endpoints -- most commonly applying EP0's
+/// real `bMaxPacketSize0` once it's known (see
+/// [`super::enumeration::EnumerationPhase`]), or updating a hub's Route
+/// String/Route String Depth after a downstream device attaches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvaluateContextCommand {
+    /// Physical address of the Input Context, which the caller must
+    /// have already populated (add-context flags plus the sub-contexts
+    /// they select) and aligned to a 16-byte boundary before this is
+    /// enqueued -- this type only encodes the TRB, it doesn't build or
+    /// validate the context itself.
+    pub input_context_ptr: u64,
+    pub slot_id: u8,
+}
+
+impl EvaluateContextCommand {
+    pub fn new(input_context_ptr: u64, slot_id: u8) -> Self {
+        Self {
+            input_context_ptr,
+            slot_id,
+        }
+    }
+}
+
+/// A command TRB's four dwords: `[ptr_lo, ptr_hi, reserved, control]`.
+/// The cycle bit (control dword, bit 0) is left clear -- it's the
+/// command ring's job to set it when the TRB is actually written into a
+/// ring slot, not this type's, since it depends on the ring's current
+/// cycle state rather than anything about the command itself.
+impl From<EvaluateContextCommand> for [u32; 4] {
+    fn from(cmd: EvaluateContextCommand) -> Self {
+        let mut control = 0u32;
+        control.set_bits(10..=15, TRB_TYPE_EVALUATE_CONTEXT);
+        control.set_bits(24..=31, cmd.slot_id as u32);
+
+        [
+            cmd.input_context_ptr.get_bits(0..=31) as u32,
+            cmd.input_context_ptr.get_bits(32..=63) as u32,
+            0,
+            control,
+        ]
+    }
+}
+
+/// xHCI Reset Endpoint Command (xHCI spec §6.4.3.8): clears an
+/// endpoint's Halted condition -- e.g. after the Stall
+/// [`super::completion::CompletionCode::needs_endpoint_reset`] flags --
+/// and moves its Endpoint Context back to the Stopped state. Pair with
+/// [`SetTrDequeuePointerCommand`] to also skip the transfer ring past
+/// the TRB that caused the Stall; this command alone leaves the ring's
+/// dequeue pointer where it was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResetEndpointCommand {
+    pub dci: DeviceContextIndex,
+    pub slot_id: u8,
+}
+
+impl ResetEndpointCommand {
+    pub fn new(dci: DeviceContextIndex, slot_id: u8) -> Self {
+        Self { dci, slot_id }
+    }
+}
+
+impl From<ResetEndpointCommand> for [u32; 4] {
+    fn from(cmd: ResetEndpointCommand) -> Self {
+        let mut control = 0u32;
+        control.set_bits(10..=15, TRB_TYPE_RESET_ENDPOINT);
+        control.set_bits(16..=20, cmd.dci.raw() as u32);
+        control.set_bits(24..=31, cmd.slot_id as u32);
+
+        [0, 0, 0, control]
+    }
+}
+
+/// xHCI Set TR Dequeue Pointer Command (xHCI spec §6.4.3.9): repoints
+/// an endpoint's transfer ring dequeue pointer, e.g. past the TRB that
+/// caused a Stall once [`ResetEndpointCommand`] has cleared it, so the
+/// next enqueued transfer doesn't resume at the failed one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetTrDequeuePointerCommand {
+    /// Physical address of the TRB to resume dequeuing from. Must
+    /// already be 16-byte aligned -- bits 0..=3 of the low dword are
+    /// reused for the Dequeue Cycle State and Stream Context Type
+    /// fields, not address bits.
+    pub dequeue_ptr: u64,
+    /// The Consumer Cycle State the xHC should expect at `dequeue_ptr`,
+    /// i.e. the target ring's cycle state at that slot -- this type has
+    /// no way to read that off a [`super::TransferRing`] itself, so the
+    /// caller must track and pass it in.
+    pub dequeue_cycle_state: bool,
+    pub dci: DeviceContextIndex,
+    pub slot_id: u8,
+}
+
+impl SetTrDequeuePointerCommand {
+    pub fn new(dequeue_ptr: u64, dequeue_cycle_state: bool, dci: DeviceContextIndex, slot_id: u8) -> Self {
+        Self {
+            dequeue_ptr,
+            dequeue_cycle_state,
+            dci,
+            slot_id,
+        }
+    }
+}
+
+impl From<SetTrDequeuePointerCommand> for [u32; 4] {
+    fn from(cmd: SetTrDequeuePointerCommand) -> Self {
+        let mut control = 0u32;
+        control.set_bits(10..=15, TRB_TYPE_SET_TR_DEQUEUE_POINTER);
+        control.set_bits(16..=20, cmd.dci.raw() as u32);
+        control.set_bits(24..=31, cmd.slot_id as u32);
+
+        let mut ptr_lo = cmd.dequeue_ptr.get_bits(0..=31) as u32;
+        ptr_lo.set_bit(0, cmd.dequeue_cycle_state);
+
+        [ptr_lo, cmd.dequeue_ptr.get_bits(32..=63) as u32, 0, control]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_the_input_context_pointer_across_both_dwords() {
+        let cmd = EvaluateContextCommand::new(0x1234_5678_9abc_def0, 7);
+        let trb: [u32; 4] = cmd.into();
+        assert_eq!(trb[0], 0x9abc_def0);
+        assert_eq!(trb[1], 0x1234_5678);
+    }
+
+    #[test]
+    fn encodes_trb_type_and_slot_id_in_the_control_dword() {
+        let cmd = EvaluateContextCommand::new(0, 5);
+        let trb: [u32; 4] = cmd.into();
+        assert_eq!(trb[2], 0, "status dword is reserved for this command");
+        assert_eq!(trb[3].get_bits(10..=15), TRB_TYPE_EVALUATE_CONTEXT);
+        assert_eq!(trb[3].get_bits(24..=31), 5);
+    }
+
+    #[test]
+    fn leaves_the_cycle_bit_for_the_command_ring_to_set() {
+        let cmd = EvaluateContextCommand::new(0xffff_ffff_ffff_ffff, 31);
+        let trb: [u32; 4] = cmd.into();
+        assert!(!trb[3].get_bit(0));
+    }
+
+    #[test]
+    fn reset_endpoint_encodes_trb_type_dci_and_slot_id() {
+        let dci = DeviceContextIndex::try_from(3).unwrap();
+        let cmd = ResetEndpointCommand::new(dci, 5);
+        let trb: [u32; 4] = cmd.into();
+        assert_eq!(trb[3].get_bits(10..=15), TRB_TYPE_RESET_ENDPOINT);
+        assert_eq!(trb[3].get_bits(16..=20), 3);
+        assert_eq!(trb[3].get_bits(24..=31), 5);
+    }
+
+    #[test]
+    fn set_tr_dequeue_pointer_encodes_the_pointer_across_both_dwords() {
+        let dci = DeviceContextIndex::try_from(1).unwrap();
+        let cmd = SetTrDequeuePointerCommand::new(0x1234_5678_9abc_def0, false, dci, 7);
+        let trb: [u32; 4] = cmd.into();
+        assert_eq!(trb[0], 0x9abc_def0);
+        assert_eq!(trb[1], 0x1234_5678);
+    }
+
+    #[test]
+    fn set_tr_dequeue_pointer_stamps_the_dequeue_cycle_state_bit() {
+        let dci = DeviceContextIndex::try_from(1).unwrap();
+        let with_dcs = SetTrDequeuePointerCommand::new(0x1000, true, dci, 1);
+        let trb: [u32; 4] = with_dcs.into();
+        assert!(trb[0].get_bit(0));
+
+        let without_dcs = SetTrDequeuePointerCommand::new(0x1000, false, dci, 1);
+        let trb: [u32; 4] = without_dcs.into();
+        assert!(!trb[0].get_bit(0));
+    }
+
+    #[test]
+    fn set_tr_dequeue_pointer_encodes_trb_type_dci_and_slot_id() {
+        let dci = DeviceContextIndex::try_from(4).unwrap();
+        let cmd = SetTrDequeuePointerCommand::new(0, false, dci, 9);
+        let trb: [u32; 4] = cmd.into();
+        assert_eq!(trb[3].get_bits(10..=15), TRB_TYPE_SET_TR_DEQUEUE_POINTER);
+        assert_eq!(trb[3].get_bits(16..=20), 4);
+        assert_eq!(trb[3].get_bits(24..=31), 9);
+    }
+}
@@ -0,0 +1,154 @@
+//! Transfer Event TRB completion codes (xHCI spec §6.4.5, Table 6-95)
+//! and turning a non-success one into an `Err` instead of a logged-and-
+//! ignored warning.
+//!
+//! There's no `Driver::transfer_event`/`Device::transfer_event` in this
+//! tree to change -- no `Driver`, `Device`, command ring, or transfer
+//! event dispatch exists here at all yet (see the module doc on
+//! [`super::transfer`]) -- so there's no `log::debug!`-and-continue call
+//! site to stop swallowing. This is the piece such a call site would
+//! need first: the completion code decode and the `Err` it should
+//! return instead, ready for when a real dispatcher exists to call it.
+//!
+//! [`TransferFailed`] carries the Slot ID alongside the endpoint and
+//! completion code, since a Transfer Event TRB names its slot directly
+//! (xHCI spec §6.4.2.1) and a future dispatcher would already have it
+//! on hand -- without it, a log line can only say an endpoint stalled,
+//! not which device's. There's no `Driver`/`Device` here to look a
+//! human-facing name up from that slot (see [`super::port_slot_map`]
+//! for the one piece of slot/port bookkeeping that does exist), so
+//! [`TransferFailed`]'s [`core::fmt::Display`] can only report it as a
+//! raw ID.
+use core::fmt;
+
+use super::endpoint::DeviceContextIndex;
+
+/// A Transfer/Command Event TRB's Completion Code (Status dword bits
+/// 24..=31). Only the codes this driver currently has reason to
+/// distinguish are named; anything else decodes to [`Self::Other`]
+/// rather than being silently treated as one of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionCode {
+    Success,
+    DataBufferError,
+    BabbleDetectedError,
+    UsbTransactionError,
+    TrbError,
+    StallError,
+    /// The transfer completed with fewer bytes than requested -- not a
+    /// failure, just short, e.g. a device that has less data to report
+    /// right now than the buffer could hold.
+    ShortPacket,
+    Other(u8),
+}
+
+impl CompletionCode {
+    pub fn decode(raw: u8) -> Self {
+        match raw {
+            1 => Self::Success,
+            2 => Self::DataBufferError,
+            3 => Self::BabbleDetectedError,
+            4 => Self::UsbTransactionError,
+            5 => Self::TrbError,
+            6 => Self::StallError,
+            13 => Self::ShortPacket,
+            other => Self::Other(other),
+        }
+    }
+
+    /// Whether a transfer with this code moved data correctly.
+    /// `ShortPacket` counts as success here: the data that did arrive
+    /// arrived correctly.
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success | Self::ShortPacket)
+    }
+
+    /// Whether the endpoint this code came from is now halted and needs
+    /// [`super::endpoint`]-level recovery (Reset Endpoint + Set TR
+    /// Dequeue Pointer) before it can be used again, rather than just
+    /// being retried.
+    pub fn needs_endpoint_reset(&self) -> bool {
+        matches!(self, Self::StallError)
+    }
+}
+
+/// Why a transfer didn't complete successfully, returned in place of
+/// logging and continuing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferFailed {
+    pub code: CompletionCode,
+    pub dci: DeviceContextIndex,
+    pub slot_id: u8,
+}
+
+impl fmt::Display for TransferFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "slot {} dci {}: transfer failed ({:?})",
+            self.slot_id,
+            self.dci.raw(),
+            self.code
+        )
+    }
+}
+
+/// Turns `code` into `Ok(())` if it's a success per
+/// [`CompletionCode::is_success`], or `Err(TransferFailed)` naming
+/// `code`, `dci`, and `slot_id` otherwise -- the check a transfer-event
+/// dispatcher should make before proceeding as if the transfer went
+/// through.
+pub fn check(code: CompletionCode, dci: DeviceContextIndex, slot_id: u8) -> Result<(), TransferFailed> {
+    if code.is_success() {
+        Ok(())
+    } else {
+        Err(TransferFailed { code, dci, slot_id })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_the_named_codes() {
+        assert_eq!(CompletionCode::decode(1), CompletionCode::Success);
+        assert_eq!(CompletionCode::decode(6), CompletionCode::StallError);
+        assert_eq!(CompletionCode::decode(13), CompletionCode::ShortPacket);
+    }
+
+    #[test]
+    fn unnamed_codes_decode_to_other() {
+        assert_eq!(CompletionCode::decode(200), CompletionCode::Other(200));
+    }
+
+    #[test]
+    fn success_and_short_packet_are_not_failures() {
+        assert!(CompletionCode::Success.is_success());
+        assert!(CompletionCode::ShortPacket.is_success());
+        assert!(!CompletionCode::StallError.is_success());
+    }
+
+    #[test]
+    fn only_stall_needs_an_endpoint_reset() {
+        assert!(CompletionCode::StallError.needs_endpoint_reset());
+        assert!(!CompletionCode::BabbleDetectedError.needs_endpoint_reset());
+    }
+
+    #[test]
+    fn check_passes_successes_through_and_errors_on_failures() {
+        let dci = DeviceContextIndex::try_from(1).unwrap();
+        assert_eq!(check(CompletionCode::Success, dci, 3), Ok(()));
+        assert_eq!(
+            check(CompletionCode::StallError, dci, 3),
+            Err(TransferFailed { code: CompletionCode::StallError, dci, slot_id: 3 })
+        );
+    }
+
+    #[test]
+    fn display_names_the_slot_dci_and_code() {
+        let dci = DeviceContextIndex::try_from(1).unwrap();
+        let failed = TransferFailed { code: CompletionCode::StallError, dci, slot_id: 3 };
+        assert_eq!(format!("{}", failed), "slot 3 dci 1: transfer failed (StallError)");
+    }
+}
@@ -0,0 +1,153 @@
+//! The Device Context Base Address Array (xHCI spec §6.1): one 64-bit
+//! entry per Device Slot, pointing the controller at that slot's Device
+//! Context structure. Index 0 is reserved for the Scratchpad Buffer
+//! Array's pointer rather than a device.
+//!
+//! There's no `xhci/context.rs` (`*mut DeviceCtx`-backed,
+//! `BoundedAlloc64`) or `usb/context.rs` (`Pin<Box<DeviceCtx>>`-backed,
+//! `XhcRuntimeAllocator`) in this tree to reconcile -- no
+//! `DeviceCtx`/`DeviceContextBaseAddressArray` of any shape exists here
+//! yet, under either module, dead or otherwise. This is the single
+//! implementation such a reconciliation would have converged on: plain
+//! 64-bit addresses, since there's neither a `DeviceCtx` type nor a heap
+//! (no `extern crate alloc`, so `Pin<Box<_>>` isn't available) in this
+//! tree to own a typed pointer into yet -- once a device context type
+//! and a way to allocate DMA-visible memory for it exist, `register`'s
+//! `u64` argument is where the real pointer gets cast in from.
+//!
+//! That also means there's no `utils::leak_raw_pin`, `DeviceManager`, or
+//! `Driver` here for a safe, ownership-tracked replacement to slot into --
+//! nothing in this tree leaks a `DeviceCtx` today because nothing owns one
+//! yet. [`DeviceContextBaseAddressArray::unregister`]/[`is_registered`](DeviceContextBaseAddressArray::is_registered)
+//! are as far as "tracked ownership" can go without that type: they let a
+//! caller ask "is this slot live" and clear it without reaching for the
+//! bare `register(slot_id, 0)` convention directly. Once a `Driver` exists
+//! to own a context, it's the one place responsible for calling
+//! `unregister` when a slot is torn down, same as it'll be the one
+//! allocating the context in the first place.
+#[derive(Debug)]
+pub struct DeviceContextBaseAddressArray<const N: usize> {
+    entries: [u64; N],
+}
+
+impl<const N: usize> Default for DeviceContextBaseAddressArray<N> {
+    fn default() -> Self {
+        Self { entries: [0; N] }
+    }
+}
+
+impl<const N: usize> DeviceContextBaseAddressArray<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `device_ctx_ptr` (a DMA-visible physical address, or `0`
+    /// to clear the slot) as the Device Context for `slot_id`.
+    pub fn register(&mut self, slot_id: u8, device_ctx_ptr: u64) {
+        self.entries[slot_id as usize] = device_ctx_ptr;
+    }
+
+    /// The address registered for `slot_id`, or `0` if nothing has been
+    /// registered there -- the same "no device" value the xHC itself
+    /// uses for an empty slot.
+    pub fn get(&self, slot_id: u8) -> u64 {
+        self.entries[slot_id as usize]
+    }
+
+    /// The array's base address to program into the Device Context Base
+    /// Address Array Pointer register (xHCI spec §5.4.6).
+    pub fn as_ptr(&self) -> *const u64 {
+        self.entries.as_ptr()
+    }
+
+    /// Clears `slot_id`'s entry, same as `register(slot_id, 0)` -- named
+    /// for the caller that's tearing a slot down rather than registering
+    /// a new context into it.
+    pub fn unregister(&mut self, slot_id: u8) {
+        self.register(slot_id, 0);
+    }
+
+    /// Whether `slot_id` currently points at a Device Context.
+    pub fn is_registered(&self, slot_id: u8) -> bool {
+        self.get(slot_id) != 0
+    }
+
+    /// Panics in debug builds if `slot_id` is still registered -- the
+    /// invariant a context's owner must hold before freeing its backing
+    /// memory, so a context is never freed while the DCBAA entry still
+    /// points at it.
+    ///
+    /// There's no heap allocator, `CtxAllocation`, or `Driver` type in
+    /// this tree for a real `Drop` impl to call this from yet (see the
+    /// module doc) -- nothing frees a Device Context today because
+    /// nothing allocates one. This is the one piece of that invariant
+    /// that doesn't need any of those to exist: the check itself.
+    pub fn debug_assert_unregistered(&self, slot_id: u8) {
+        debug_assert!(
+            !self.is_registered(slot_id),
+            "slot {} freed while its DCBAA entry still points at it",
+            slot_id
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unregistered_slots_default_to_zero() {
+        let dcbaa: DeviceContextBaseAddressArray<8> = DeviceContextBaseAddressArray::new();
+        assert_eq!(dcbaa.get(1), 0);
+    }
+
+    #[test]
+    fn register_then_get_round_trips() {
+        let mut dcbaa: DeviceContextBaseAddressArray<8> = DeviceContextBaseAddressArray::new();
+        dcbaa.register(3, 0x1000);
+        assert_eq!(dcbaa.get(3), 0x1000);
+        assert_eq!(dcbaa.get(2), 0);
+    }
+
+    #[test]
+    fn registering_zero_clears_a_slot() {
+        let mut dcbaa: DeviceContextBaseAddressArray<8> = DeviceContextBaseAddressArray::new();
+        dcbaa.register(3, 0x1000);
+        dcbaa.register(3, 0);
+        assert_eq!(dcbaa.get(3), 0);
+    }
+
+    #[test]
+    fn unregister_clears_a_slot() {
+        let mut dcbaa: DeviceContextBaseAddressArray<8> = DeviceContextBaseAddressArray::new();
+        dcbaa.register(3, 0x1000);
+        dcbaa.unregister(3);
+        assert_eq!(dcbaa.get(3), 0);
+    }
+
+    #[test]
+    fn is_registered_reflects_current_state() {
+        let mut dcbaa: DeviceContextBaseAddressArray<8> = DeviceContextBaseAddressArray::new();
+        assert!(!dcbaa.is_registered(3));
+        dcbaa.register(3, 0x1000);
+        assert!(dcbaa.is_registered(3));
+        dcbaa.unregister(3);
+        assert!(!dcbaa.is_registered(3));
+    }
+
+    #[test]
+    fn debug_assert_unregistered_passes_once_the_slot_is_cleared() {
+        let mut dcbaa: DeviceContextBaseAddressArray<8> = DeviceContextBaseAddressArray::new();
+        dcbaa.register(3, 0x1000);
+        dcbaa.unregister(3);
+        dcbaa.debug_assert_unregistered(3); // does not panic
+    }
+
+    #[test]
+    #[should_panic(expected = "freed while its DCBAA entry still points at it")]
+    fn debug_assert_unregistered_panics_while_the_slot_is_still_live() {
+        let mut dcbaa: DeviceContextBaseAddressArray<8> = DeviceContextBaseAddressArray::new();
+        dcbaa.register(3, 0x1000);
+        dcbaa.debug_assert_unregistered(3);
+    }
+}
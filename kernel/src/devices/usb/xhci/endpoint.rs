@@ -0,0 +1,199 @@
+//! Endpoint naming for xHCI.
+//!
+//! xHCI slots a device's per-endpoint resources (contexts, transfer rings)
+//! by Device Context Index (DCI), not by the USB-address-format byte
+//! descriptors use (`EndpointDescriptor::endpoint_address`: bit 7 =
+//! direction, bits 0..=3 = number). [`EndpointId`] is the id the rest of
+//! the driver names endpoints by; [`DeviceContextIndex`] is the raw value
+//! xHCI wants. `EndpointId::dci` and `DeviceContextIndex::endpoint_id` are
+//! the single source of truth for converting between the two.
+use bit_field::BitField;
+
+use super::super::descriptor::Endpoint as EndpointDescriptor;
+use super::super::request::Direction;
+
+/// Bit 0 = direction, bits 1..=4 = endpoint number (xHCI spec §4.5.1's
+/// `DCI = 2 * EndpointNumber + Direction` scheme, before the
+/// control-endpoint special case in [`Self::dci`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EndpointId(u8);
+
+impl EndpointId {
+    /// Endpoint 0, the bidirectional control endpoint every device has.
+    pub const DEFAULT_CONTROL: Self = Self::new(0, Direction::Out);
+
+    pub const fn new(number: u8, direction: Direction) -> Self {
+        let direction_bit = match direction {
+            Direction::Out => 0,
+            Direction::In => 1,
+        };
+        Self((number << 1) | direction_bit)
+    }
+
+    /// [`Self::new`], but rejects `number > 15` instead of silently
+    /// wrapping it into another endpoint's bits. USB endpoint numbers
+    /// are 4 bits (`bEndpointAddress` bits 0..=3, USB 2.0 spec Table
+    /// 9-13), so `15` is the largest value a real descriptor can ever
+    /// report; anything above that packed through `new` would collide
+    /// with a lower endpoint number's encoding instead of erroring.
+    pub const fn try_new(number: u8, direction: Direction) -> Option<Self> {
+        if number > 15 {
+            return None;
+        }
+        Some(Self::new(number, direction))
+    }
+
+    /// The endpoint id an `EndpointDescriptor`'s `endpoint_address`
+    /// (USB address format: bit 7 = direction, bits 0..=3 = number)
+    /// names, re-encoded into this module's bit layout.
+    pub fn from_descriptor(desc: &EndpointDescriptor) -> Self {
+        let address = desc.endpoint_address();
+        let number = address.get_bits(0..=3);
+        let direction = if address.get_bit(7) {
+            Direction::In
+        } else {
+            Direction::Out
+        };
+        Self::new(number, direction)
+    }
+
+    pub fn number(&self) -> u8 {
+        self.0.get_bits(1..=4)
+    }
+
+    pub fn direction(&self) -> Direction {
+        if self.0.get_bit(0) {
+            Direction::In
+        } else {
+            Direction::Out
+        }
+    }
+
+    /// The DCI this endpoint's context and transfer ring live at. The
+    /// control endpoint is bidirectional and shares one context
+    /// regardless of direction, so it is pinned to DCI 1 instead of
+    /// following the general `2 * number + direction` formula.
+    pub fn dci(&self) -> DeviceContextIndex {
+        DeviceContextIndex(if self.number() == 0 { 1 } else { self.0 })
+    }
+}
+
+impl TryFrom<u8> for EndpointId {
+    type Error = u8;
+
+    /// `raw` is this module's bit layout (bit 0 = direction, bits 1..=4
+    /// = number), not a DCI — use [`DeviceContextIndex::endpoint_id`] to
+    /// convert a DCI instead. Valid range is the 5 bits' worth, `0..32`.
+    fn try_from(raw: u8) -> Result<Self, Self::Error> {
+        if raw >= 32 {
+            return Err(raw);
+        }
+        Ok(Self(raw))
+    }
+}
+
+/// The Device Context Index xHCI indexes a device's endpoint contexts
+/// and transfer rings by (xHCI spec §4.5.1). Valid range is `1..=31`;
+/// DCI 0 names the Slot Context, which isn't an endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceContextIndex(u8);
+
+impl DeviceContextIndex {
+    pub const fn raw(&self) -> u8 {
+        self.0
+    }
+
+    /// The inverse of [`EndpointId::dci`]. DCI 1 is ambiguous on its own
+    /// (either direction's control endpoint maps to it), so this always
+    /// reports [`Direction::Out`] for it, matching
+    /// [`EndpointId::DEFAULT_CONTROL`].
+    pub fn endpoint_id(&self) -> EndpointId {
+        if self.0 == 1 {
+            return EndpointId::DEFAULT_CONTROL;
+        }
+        let number = self.0 >> 1;
+        let direction = if self.0.get_bit(0) {
+            Direction::In
+        } else {
+            Direction::Out
+        };
+        EndpointId::new(number, direction)
+    }
+}
+
+impl TryFrom<u8> for DeviceContextIndex {
+    type Error = u8;
+
+    fn try_from(raw: u8) -> Result<Self, Self::Error> {
+        if raw == 0 || raw >= 32 {
+            return Err(raw);
+        }
+        Ok(Self(raw))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_control_maps_to_dci_one() {
+        assert_eq!(EndpointId::DEFAULT_CONTROL.dci().raw(), 1);
+        assert_eq!(EndpointId::new(0, Direction::In).dci().raw(), 1);
+    }
+
+    #[test]
+    fn round_trips_every_raw_endpoint_id() {
+        for raw in 0..32u8 {
+            let id = EndpointId::try_from(raw).unwrap();
+            let dci = id.dci();
+            let back = dci.endpoint_id();
+            if id.number() == 0 {
+                // Both directions of the control endpoint collapse to
+                // DCI 1, so only DEFAULT_CONTROL round-trips exactly.
+                assert_eq!(back, EndpointId::DEFAULT_CONTROL);
+            } else {
+                assert_eq!(back, id, "raw {} did not round-trip through its DCI", raw);
+            }
+        }
+    }
+
+    #[test]
+    fn dci_is_two_times_endpoint_number_plus_direction_for_non_control_endpoints() {
+        for number in 1u8..=15 {
+            let out = EndpointId::new(number, Direction::Out);
+            let in_ = EndpointId::new(number, Direction::In);
+            assert_eq!(out.dci().raw(), 2 * number);
+            assert_eq!(in_.dci().raw(), 2 * number + 1);
+        }
+    }
+
+    #[test]
+    fn try_new_accepts_every_valid_endpoint_number() {
+        for number in 0u8..=15 {
+            assert!(EndpointId::try_new(number, Direction::Out).is_some());
+            assert!(EndpointId::try_new(number, Direction::In).is_some());
+        }
+    }
+
+    #[test]
+    fn try_new_rejects_endpoint_numbers_above_15() {
+        assert_eq!(EndpointId::try_new(16, Direction::Out), None);
+        assert_eq!(EndpointId::try_new(200, Direction::In), None);
+    }
+
+    #[test]
+    fn endpoint_id_from_descriptor_matches_address_bits() {
+        // endpoint_address = 0x81: bit 7 set (In), number 1.
+        let bytes = [7, 5, 0x81, 0x03, 0x08, 0x00, 0x0a];
+        let id = EndpointId::from_descriptor(&EndpointDescriptor(bytes));
+        assert_eq!(id.number(), 1);
+        assert_eq!(id.direction(), Direction::In);
+
+        // endpoint_address = 0x02: bit 7 clear (Out), number 2.
+        let bytes = [7, 5, 0x02, 0x02, 0x40, 0x00, 0x00];
+        let id = EndpointId::from_descriptor(&EndpointDescriptor(bytes));
+        assert_eq!(id.number(), 2);
+        assert_eq!(id.direction(), Direction::Out);
+    }
+}
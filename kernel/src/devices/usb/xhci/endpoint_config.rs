@@ -0,0 +1,165 @@
+//! The configuration a class driver needs to start using one of a
+//! device's non-default endpoints, derived from its descriptor (USB 2.0
+//! spec §9.6.6).
+//!
+//! `ClassDriver::set_endpoints`, `Device::set_endpoints`,
+//! `Driver::read_and_set_config`, and `new_transfer_ring_at` don't exist
+//! in this tree -- there's no `ClassDriver` trait or `Device`/`Driver`
+//! type to thread a collected `&[EndpointConfig]` through. This is the
+//! element type such a slice would hold: everything a transfer ring
+//! allocator and a class driver both need (the DCI to allocate at, and
+//! the max packet size/transfer type/interval the driver needs to know
+//! to use the endpoint correctly) derived once from the descriptor
+//! instead of each side re-deriving its own half.
+use super::super::descriptor::Endpoint as EndpointDescriptor;
+use super::super::descriptor::TransferType;
+use super::super::utils::PortSpeed;
+use super::endpoint::{DeviceContextIndex, EndpointId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EndpointConfig {
+    pub endpoint_id: EndpointId,
+    /// The DCI a transfer ring for this endpoint should be allocated at.
+    pub dci: DeviceContextIndex,
+    pub max_packet_size: u16,
+    pub transfer_type: TransferType,
+    pub interval: u8,
+}
+
+impl EndpointConfig {
+    pub fn from_descriptor(desc: &EndpointDescriptor) -> Self {
+        let endpoint_id = EndpointId::from_descriptor(desc);
+        Self {
+            endpoint_id,
+            dci: endpoint_id.dci(),
+            max_packet_size: desc.max_packet_size(),
+            transfer_type: desc.transfer_type(),
+            interval: desc.interval(),
+        }
+    }
+
+    /// Converts [`Self::interval`] (the raw `bInterval` from the
+    /// descriptor) into the xHCI endpoint context's Interval field
+    /// (xHCI spec §6.2.3.6), which isn't the same unit at every speed:
+    ///
+    /// - High-speed/SuperSpeed interrupt and isochronous endpoints
+    ///   already express `bInterval` as a microframe exponent
+    ///   (`1..=16`, meaning `2^(bInterval-1)` microframes), so the field
+    ///   is just `bInterval - 1`.
+    /// - Full-/low-speed isochronous endpoints express `bInterval` the
+    ///   same way but in frames instead of microframes (`3..=16`), so
+    ///   the field is `bInterval - 1` microframe-exponent-equivalents
+    ///   plus the 3 bits a frame is worth in microframes: `bInterval + 2`.
+    /// - Full-/low-speed interrupt endpoints express `bInterval` as a
+    ///   literal frame count (`1..=255`), not a power of two, so it's
+    ///   rounded down to the nearest one before the same frames-to-
+    ///   microframes conversion applies.
+    /// - Control and bulk endpoints have no polling schedule; the field
+    ///   is unused and left at `0`.
+    ///
+    /// There's no `ClassDriver`/`Device::set_endpoints` in this tree
+    /// yet (see the module doc) to call this from; its one caller is
+    /// [`super::EndpointContext::from_config`].
+    pub fn xhci_interval(&self, port_speed: PortSpeed) -> u8 {
+        use PortSpeed::{Full, High, Low, Super};
+
+        match (port_speed, self.transfer_type) {
+            (High | Super, TransferType::Interrupt | TransferType::Isochronous) => {
+                self.interval.saturating_sub(1)
+            }
+            (Low | Full, TransferType::Isochronous) => self.interval.saturating_add(2),
+            (Low | Full, TransferType::Interrupt) => {
+                floor_log2(self.interval.max(1)).saturating_add(3)
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// `floor(log2(n))` for `n >= 1`, i.e. the exponent of the largest power
+/// of two that's `<= n` -- used to round a full-/low-speed interrupt
+/// endpoint's frame-count `bInterval` down to the nearest power of two
+/// before converting it to a microframe exponent.
+fn floor_log2(n: u8) -> u8 {
+    7 - n.leading_zeros() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::request::Direction;
+
+    #[test]
+    fn derives_dci_and_transfer_parameters_from_the_descriptor() {
+        // endpoint_address = 0x81 (In, number 1), interrupt, MPS 8, interval 10.
+        let bytes = [7, 5, 0x81, 0x03, 0x08, 0x00, 0x0a];
+        let desc = EndpointDescriptor(bytes);
+        let config = EndpointConfig::from_descriptor(&desc);
+
+        assert_eq!(config.endpoint_id.number(), 1);
+        assert_eq!(config.endpoint_id.direction(), Direction::In);
+        assert_eq!(config.dci, config.endpoint_id.dci());
+        assert_eq!(config.max_packet_size, 8);
+        assert_eq!(config.transfer_type, TransferType::Interrupt);
+        assert_eq!(config.interval, 10);
+    }
+
+    fn config_with(transfer_type: TransferType, interval: u8) -> EndpointConfig {
+        let endpoint_id = EndpointId::new(1, Direction::In);
+        EndpointConfig {
+            endpoint_id,
+            dci: endpoint_id.dci(),
+            max_packet_size: 8,
+            transfer_type,
+            interval,
+        }
+    }
+
+    #[test]
+    fn high_and_super_speed_subtract_one_from_the_microframe_exponent() {
+        // (port_speed, bInterval, expected xhci interval)
+        let cases = [
+            (PortSpeed::High, 1u8, 0u8),
+            (PortSpeed::High, 8, 7),
+            (PortSpeed::Super, 1, 0),
+            (PortSpeed::Super, 16, 15),
+        ];
+        for (speed, binterval, want) in cases {
+            for transfer_type in [TransferType::Interrupt, TransferType::Isochronous] {
+                let config = config_with(transfer_type, binterval);
+                assert_eq!(config.xhci_interval(speed), want, "{:?}/{:?}/{}", speed, transfer_type, binterval);
+            }
+        }
+    }
+
+    #[test]
+    fn low_and_full_speed_isochronous_adds_two_frames_worth_of_microframes() {
+        let cases = [(3u8, 5u8), (16, 18)];
+        for (binterval, want) in cases {
+            for speed in [PortSpeed::Low, PortSpeed::Full] {
+                let config = config_with(TransferType::Isochronous, binterval);
+                assert_eq!(config.xhci_interval(speed), want);
+            }
+        }
+    }
+
+    #[test]
+    fn low_and_full_speed_interrupt_rounds_the_frame_count_down_to_a_power_of_two() {
+        // (bInterval frames, expected xhci interval = floor(log2(frames)) + 3)
+        let cases = [(1u8, 3u8), (8, 6), (10, 6), (15, 6), (16, 7), (255, 10)];
+        for (binterval, want) in cases {
+            for speed in [PortSpeed::Low, PortSpeed::Full] {
+                let config = config_with(TransferType::Interrupt, binterval);
+                assert_eq!(config.xhci_interval(speed), want, "binterval={}", binterval);
+            }
+        }
+    }
+
+    #[test]
+    fn control_and_bulk_have_no_polling_schedule() {
+        for speed in [PortSpeed::Low, PortSpeed::Full, PortSpeed::High, PortSpeed::Super] {
+            assert_eq!(config_with(TransferType::Control, 5).xhci_interval(speed), 0);
+            assert_eq!(config_with(TransferType::Bulk, 5).xhci_interval(speed), 0);
+        }
+    }
+}
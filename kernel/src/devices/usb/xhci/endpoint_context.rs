@@ -0,0 +1,206 @@
+//! The xHCI Endpoint Context (xHCI spec §6.2.3): what a Configure
+//! Endpoint or Address Device command's Input Context actually carries
+//! per endpoint, as opposed to [`super::EndpointConfig`], which is the
+//! pre-xHCI-specific shape a class driver deals with.
+//!
+//! There's no `Device::new_transfer_ring_at`/`set_endpoints` or
+//! Configure Endpoint command builder in this tree to fill an
+//! `EndpointContext` into -- no `Device`/`Driver` type
+//! exists to own an Input Context or call `read_and_set_config` (see
+//! [`super::endpoint_config`]'s module doc), and there's no command.rs
+//! entry for Configure Endpoint yet either (only Evaluate Context, Reset
+//! Endpoint, and Set TR Dequeue Pointer, per that module's own TRB type
+//! list). [`EndpointContext::from_config`] is the encoding half such a
+//! caller will need: given an [`super::EndpointConfig`] and the transfer
+//! ring dequeue pointer it allocated, it fills in every Endpoint Context
+//! field [`super::EndpointConfig`] alone doesn't carry -- EP Type, CErr,
+//! and the xHCI-units Interval -- the same way [`super::command`] builds
+//! a command TRB from the fields callers hand it.
+use bit_field::BitField;
+
+use super::super::utils::PortSpeed;
+use super::super::descriptor::TransferType;
+use super::endpoint_config::EndpointConfig;
+use super::endpoint::EndpointId;
+use super::super::request::Direction;
+
+/// EP Type field values (xHCI spec Table 6-10).
+const EP_TYPE_ISOCH_OUT: u32 = 1;
+const EP_TYPE_BULK_OUT: u32 = 2;
+const EP_TYPE_INTERRUPT_OUT: u32 = 3;
+const EP_TYPE_CONTROL: u32 = 4;
+const EP_TYPE_ISOCH_IN: u32 = 5;
+const EP_TYPE_BULK_IN: u32 = 6;
+const EP_TYPE_INTERRUPT_IN: u32 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EndpointContext {
+    endpoint_type: u32,
+    /// Error Count: the number of consecutive USB Bus Errors allowed
+    /// before the xHC halts the endpoint. Fixed at 3 for every transfer
+    /// type except isochronous, which has no retry concept and is
+    /// always 0 (xHCI spec §6.2.3).
+    error_count: u32,
+    /// [`EndpointConfig::xhci_interval`]'s result -- already converted
+    /// out of the descriptor's raw `bInterval` units.
+    interval: u32,
+    max_packet_size: u32,
+    /// USB2 High-Speed's "additional transactions per microframe" and
+    /// SuperSpeed's companion-descriptor `bMaxBurst` both feed this
+    /// field; this tree decodes neither (no HS multiplier bits read out
+    /// of `wMaxPacketSize`, no SuperSpeed Endpoint Companion Descriptor
+    /// type at all), so it's always left at 0, the correct value for
+    /// every endpoint that doesn't burst.
+    max_burst_size: u32,
+    /// A single reasonable default (xHCI spec §6.2.3 only requires
+    /// "an appropriate value") rather than one derived per-transfer-size,
+    /// since there's no transfer-size estimation anywhere in this tree --
+    /// the endpoint's own max packet size is as good a guess as any.
+    average_trb_length: u32,
+    dequeue_ptr: u64,
+    dequeue_cycle_state: bool,
+}
+
+impl EndpointContext {
+    /// Builds the Endpoint Context fields for `config`, whose transfer
+    /// ring's first TRB is at `dequeue_ptr` with initial cycle state
+    /// `dequeue_cycle_state` -- the same two values
+    /// [`super::SetTrDequeuePointerCommand`] takes for an already-running
+    /// endpoint, supplied here instead for the ring's very first TRB.
+    pub fn from_config(config: &EndpointConfig, port_speed: PortSpeed, dequeue_ptr: u64, dequeue_cycle_state: bool) -> Self {
+        let error_count = match config.transfer_type {
+            TransferType::Isochronous => 0,
+            _ => 3,
+        };
+
+        Self {
+            endpoint_type: endpoint_type(config.transfer_type, config.endpoint_id),
+            error_count,
+            interval: config.xhci_interval(port_speed) as u32,
+            max_packet_size: config.max_packet_size as u32,
+            max_burst_size: 0,
+            average_trb_length: config.max_packet_size as u32,
+            dequeue_ptr,
+            dequeue_cycle_state,
+        }
+    }
+}
+
+/// EP Type is the one field the descriptor's [`TransferType`] can't
+/// supply alone: control is bidirectional regardless of `endpoint_id`'s
+/// direction bit, but every other transfer type splits into a distinct
+/// In/Out EP Type value (xHCI spec Table 6-10).
+fn endpoint_type(transfer_type: TransferType, endpoint_id: EndpointId) -> u32 {
+    if transfer_type == TransferType::Control {
+        return EP_TYPE_CONTROL;
+    }
+    match (transfer_type, endpoint_id.direction()) {
+        (TransferType::Isochronous, Direction::Out) => EP_TYPE_ISOCH_OUT,
+        (TransferType::Isochronous, Direction::In) => EP_TYPE_ISOCH_IN,
+        (TransferType::Bulk, Direction::Out) => EP_TYPE_BULK_OUT,
+        (TransferType::Bulk, Direction::In) => EP_TYPE_BULK_IN,
+        (TransferType::Interrupt, Direction::Out) => EP_TYPE_INTERRUPT_OUT,
+        (TransferType::Interrupt, Direction::In) => EP_TYPE_INTERRUPT_IN,
+        (TransferType::Control, _) => unreachable!("handled above"),
+    }
+}
+
+/// The Endpoint Context's eight dwords (xHCI spec §6.2.3, Figure 6-10).
+/// Dwords 5..=7 are reserved and left at 0.
+impl From<EndpointContext> for [u32; 8] {
+    fn from(ctx: EndpointContext) -> Self {
+        let mut dw0 = 0u32;
+        dw0.set_bits(16..=23, ctx.interval);
+
+        let mut dw1 = 0u32;
+        dw1.set_bits(1..=2, ctx.error_count);
+        dw1.set_bits(3..=5, ctx.endpoint_type);
+        dw1.set_bits(8..=15, ctx.max_burst_size);
+        dw1.set_bits(16..=31, ctx.max_packet_size);
+
+        let mut dw2 = ctx.dequeue_ptr.get_bits(0..=31) as u32;
+        dw2.set_bit(0, ctx.dequeue_cycle_state);
+        let dw3 = ctx.dequeue_ptr.get_bits(32..=63) as u32;
+
+        let mut dw4 = 0u32;
+        dw4.set_bits(0..=15, ctx.average_trb_length);
+
+        [dw0, dw1, dw2, dw3, dw4, 0, 0, 0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::endpoint::EndpointId;
+
+    fn interrupt_in_config(interval: u8) -> EndpointConfig {
+        let endpoint_id = EndpointId::new(1, Direction::In);
+        EndpointConfig {
+            endpoint_id,
+            dci: endpoint_id.dci(),
+            max_packet_size: 8,
+            transfer_type: TransferType::Interrupt,
+            interval,
+        }
+    }
+
+    #[test]
+    fn control_endpoints_get_cerr_three_and_the_bidirectional_ep_type() {
+        let endpoint_id = EndpointId::new(0, Direction::Out);
+        let config = EndpointConfig {
+            endpoint_id,
+            dci: endpoint_id.dci(),
+            max_packet_size: 8,
+            transfer_type: TransferType::Control,
+            interval: 0,
+        };
+        let ctx = EndpointContext::from_config(&config, PortSpeed::Full, 0, false);
+        let dwords: [u32; 8] = ctx.into();
+        assert_eq!(dwords[1].get_bits(1..=2), 3);
+        assert_eq!(dwords[1].get_bits(3..=5), EP_TYPE_CONTROL);
+    }
+
+    #[test]
+    fn isochronous_endpoints_get_cerr_zero() {
+        let endpoint_id = EndpointId::new(2, Direction::In);
+        let config = EndpointConfig {
+            endpoint_id,
+            dci: endpoint_id.dci(),
+            max_packet_size: 1024,
+            transfer_type: TransferType::Isochronous,
+            interval: 1,
+        };
+        let ctx = EndpointContext::from_config(&config, PortSpeed::High, 0, false);
+        let dwords: [u32; 8] = ctx.into();
+        assert_eq!(dwords[1].get_bits(1..=2), 0);
+        assert_eq!(dwords[1].get_bits(3..=5), EP_TYPE_ISOCH_IN);
+    }
+
+    #[test]
+    fn interrupt_in_gets_the_in_ep_type_and_xhci_converted_interval() {
+        let config = interrupt_in_config(10);
+        let ctx = EndpointContext::from_config(&config, PortSpeed::Full, 0, false);
+        let dwords: [u32; 8] = ctx.into();
+        assert_eq!(dwords[1].get_bits(3..=5), EP_TYPE_INTERRUPT_IN);
+        assert_eq!(dwords[0].get_bits(16..=23), config.xhci_interval(PortSpeed::Full) as u32);
+    }
+
+    #[test]
+    fn max_packet_size_lands_in_the_high_half_of_dword_one() {
+        let config = interrupt_in_config(10);
+        let ctx = EndpointContext::from_config(&config, PortSpeed::Full, 0, false);
+        let dwords: [u32; 8] = ctx.into();
+        assert_eq!(dwords[1].get_bits(16..=31), 8);
+    }
+
+    #[test]
+    fn dequeue_pointer_and_cycle_state_are_encoded_like_set_tr_dequeue_pointer() {
+        let config = interrupt_in_config(10);
+        let ctx = EndpointContext::from_config(&config, PortSpeed::Full, 0x1234_5678_9abc_def0, true);
+        let dwords: [u32; 8] = ctx.into();
+        assert_eq!(dwords[2] & !1, 0x9abc_def0 & !1);
+        assert!(dwords[2].get_bit(0));
+        assert_eq!(dwords[3], 0x1234_5678);
+    }
+}
@@ -0,0 +1,93 @@
+//! A fixed-capacity, per-endpoint store indexed by Device Context Index
+//! (DCI), not by [`EndpointId`].
+//!
+//! `usb/mod.rs`'s `NUM_OF_ENDPOINTS`, `driver.rs`, and `device.rs` don't
+//! exist in this tree -- there's no `Driver`/`Device` sizing
+//! `desc_bufs`/`num_configurations` arrays, and no `EndpointId::as_index`
+//! method either. What does exist, and is worth being careful about
+//! regardless, is the distinction the request is really about: this
+//! driver has two endpoint-shaped index spaces that look interchangeable
+//! but aren't.
+//!
+//! - [`EndpointId`] is this driver's own numbering: bit 0 = direction,
+//!   bits 1..=4 = endpoint number, range `0..32` (see
+//!   [`super::endpoint`]). A device can have at most 16 endpoint
+//!   numbers (USB 2.0 spec §9.6.6's `bEndpointAddress` only has 4
+//!   number bits), each usable IN and OUT, so `EndpointId`'s range is
+//!   `0..32` even though at most ~31 of those values are ever actually
+//!   assigned to a real endpoint on any one device.
+//! - [`DeviceContextIndex`] (DCI) is what xHCI actually indexes contexts
+//!   and transfer rings by (xHCI spec §4.5.1): `2 * number + direction`,
+//!   except the control endpoint (both directions) is pinned to DCI 1.
+//!   Valid range is `1..=31` -- DCI 0 names the Slot Context, not an
+//!   endpoint.
+//!
+//! Indexing a per-endpoint array by one when it was sized or intended
+//! for the other is exactly the bug class the request describes: a
+//! `[T; 16]` sized off a count of *endpoint numbers* silently underruns
+//! the `1..=31` DCI space the controller actually uses to address
+//! endpoint contexts and transfer rings. [`EndpointContextArray`] is
+//! sized to the real DCI range and only ever indexed by
+//! [`DeviceContextIndex`], so that mismatch can't compile.
+use super::endpoint::DeviceContextIndex;
+
+/// One slot per Device Context Index, `0..=31` -- index 0 is reserved
+/// (it names the Slot Context, not an endpoint) and is simply never
+/// read or written by [`Self::get`]/[`Self::get_mut`], which only
+/// accept an already-validated [`DeviceContextIndex`] (valid range
+/// `1..=31`).
+#[derive(Debug, Clone, Copy)]
+pub struct EndpointContextArray<T> {
+    slots: [T; 32],
+}
+
+impl<T: Default + Copy> Default for EndpointContextArray<T> {
+    fn default() -> Self {
+        Self {
+            slots: [T::default(); 32],
+        }
+    }
+}
+
+impl<T: Default + Copy> EndpointContextArray<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T> EndpointContextArray<T> {
+    pub fn get(&self, dci: DeviceContextIndex) -> &T {
+        &self.slots[dci.raw() as usize]
+    }
+
+    pub fn get_mut(&mut self, dci: DeviceContextIndex) -> &mut T {
+        &mut self.slots[dci.raw() as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::endpoint::EndpointId;
+    use super::super::super::request::Direction;
+
+    #[test]
+    fn every_valid_dci_addresses_a_distinct_slot() {
+        let mut array: EndpointContextArray<u8> = EndpointContextArray::new();
+        for raw in 1u8..32 {
+            let dci = DeviceContextIndex::try_from(raw).unwrap();
+            *array.get_mut(dci) = raw;
+        }
+        for raw in 1u8..32 {
+            let dci = DeviceContextIndex::try_from(raw).unwrap();
+            assert_eq!(*array.get(dci), raw);
+        }
+    }
+
+    #[test]
+    fn control_endpoint_in_and_out_share_one_slot() {
+        let mut array: EndpointContextArray<u32> = EndpointContextArray::new();
+        *array.get_mut(EndpointId::new(0, Direction::Out).dci()) = 7;
+        assert_eq!(*array.get(EndpointId::new(0, Direction::In).dci()), 7);
+    }
+}
@@ -0,0 +1,169 @@
+//! The two-phase Device descriptor fetch real xHCI enumeration requires
+//! (USB 2.0 spec §9.2.6.3 / xHCI spec §4.3): a device's real EP0 max
+//! packet size isn't known until the first 8 bytes of its Device
+//! descriptor come back, so the first `GetDescriptor(Device)` can only
+//! safely request 8 bytes against a guessed max packet size (see
+//! [`super::super::utils::get_max_packet_size`]). Once the real size is
+//! read back, EP0's context has to be updated via an Evaluate Context
+//! command before the full 18-byte descriptor is re-fetched; skipping
+//! straight to an 18-byte fetch against the guessed size is what makes
+//! enumeration work in QEMU (which tolerates the oversized request) but
+//! fail against real hardware that doesn't.
+//!
+//! This is modeled as a standalone phase enum rather than folded into a
+//! `Driver`/`Device` state machine -- this tree has neither yet, nor the
+//! TRBs, command ring, or input contexts such a state machine would
+//! need to drive the command itself. `Driver::command_completion` has
+//! no Evaluate Context arm to add here for the same reason: there's no
+//! `command_completion` dispatcher in this tree at all yet, xHCI or
+//! otherwise.
+use super::super::descriptor::Device as DeviceDescriptor;
+
+/// Where a single device's enumeration is up to in the two-phase
+/// descriptor fetch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnumerationPhase {
+    /// Waiting on the first 8 bytes of the Device descriptor.
+    AwaitingPartialDescriptor,
+    /// The partial descriptor is in; waiting on Evaluate Context to
+    /// apply the real `max_packet_size0` before the full fetch is issued.
+    AwaitingEvaluateContext { max_packet_size0: u8 },
+    /// Evaluate Context is done; waiting on the full 18-byte fetch.
+    AwaitingFullDescriptor { max_packet_size0: u8 },
+    /// The full descriptor is in hand.
+    Done(DeviceDescriptor),
+}
+
+impl EnumerationPhase {
+    pub fn start() -> Self {
+        Self::AwaitingPartialDescriptor
+    }
+
+    /// Advances past the first `GetDescriptor(Device)`, given the
+    /// `guessed_max_packet_size0` EP0's context was configured with
+    /// (see [`super::super::utils::get_max_packet_size`]) and the real
+    /// `bMaxPacketSize0` read back from the 8-byte response. A call in
+    /// any other phase is a no-op: a stray, late-arriving completion
+    /// for a transfer this phase already moved past shouldn't rewind it.
+    ///
+    /// Full-speed devices are the only ones where these can legitimately
+    /// differ (8/16/32/64, not known until now); Low Speed is always 8
+    /// and High Speed always 64, so the guess is already correct for
+    /// them. When they match, the Evaluate Context step -- which exists
+    /// purely to correct EP0's context before it's used for the wrong
+    /// max packet size -- is skipped entirely rather than issued as a
+    /// no-op command. Both sizes are `u8` byte counts, so SuperSpeed's
+    /// 512-byte guess does not fit here and SuperSpeed is not handled.
+    pub fn on_partial_descriptor(self, guessed_max_packet_size0: u8, max_packet_size0: u8) -> Self {
+        let next = match self {
+            Self::AwaitingPartialDescriptor => {
+                if max_packet_size0 == guessed_max_packet_size0 {
+                    Self::AwaitingFullDescriptor { max_packet_size0 }
+                } else {
+                    Self::AwaitingEvaluateContext { max_packet_size0 }
+                }
+            }
+            other => other,
+        };
+        super::trace::phase(&self, &next);
+        next
+    }
+
+    /// Advances past the Evaluate Context command that applied
+    /// `max_packet_size0` to EP0's context.
+    pub fn on_evaluate_context_complete(self) -> Self {
+        let next = match self {
+            Self::AwaitingEvaluateContext { max_packet_size0 } => {
+                Self::AwaitingFullDescriptor { max_packet_size0 }
+            }
+            other => other,
+        };
+        super::trace::phase(&self, &next);
+        next
+    }
+
+    /// Advances past the full 18-byte `GetDescriptor(Device)`.
+    pub fn on_full_descriptor(self, device: DeviceDescriptor) -> Self {
+        let next = match self {
+            Self::AwaitingFullDescriptor { .. } => Self::Done(device),
+            other => other,
+        };
+        super::trace::phase(&self, &next);
+        next
+    }
+
+    pub fn device(&self) -> Option<&DeviceDescriptor> {
+        match self {
+            Self::Done(device) => Some(device),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_device() -> DeviceDescriptor {
+        let mut bytes = [0u8; DeviceDescriptor::LENGTH];
+        bytes[7] = 64; // max_packet_size0
+        DeviceDescriptor(bytes)
+    }
+
+    #[test]
+    fn walks_through_every_phase_in_order_when_the_guess_was_wrong() {
+        let phase = EnumerationPhase::start();
+        assert_eq!(phase, EnumerationPhase::AwaitingPartialDescriptor);
+
+        // Guessed 8 (the full-speed default), the device reports 64.
+        let phase = phase.on_partial_descriptor(8, 64);
+        assert_eq!(
+            phase,
+            EnumerationPhase::AwaitingEvaluateContext { max_packet_size0: 64 }
+        );
+
+        let phase = phase.on_evaluate_context_complete();
+        assert_eq!(
+            phase,
+            EnumerationPhase::AwaitingFullDescriptor { max_packet_size0: 64 }
+        );
+
+        let device = sample_device();
+        let phase = phase.on_full_descriptor(device);
+        assert_eq!(phase.device(), Some(&device));
+    }
+
+    #[test]
+    fn skips_evaluate_context_when_the_guess_was_already_correct() {
+        // High Speed always guesses 64, which is also the only value a
+        // High Speed device ever reports -- no mismatch to correct.
+        let phase = EnumerationPhase::start().on_partial_descriptor(64, 64);
+        assert_eq!(
+            phase,
+            EnumerationPhase::AwaitingFullDescriptor { max_packet_size0: 64 }
+        );
+    }
+
+    #[test]
+    fn stray_events_out_of_order_are_ignored() {
+        // An Evaluate Context completion arriving before the partial
+        // descriptor fetch finished must not skip a phase.
+        let phase = EnumerationPhase::start().on_evaluate_context_complete();
+        assert_eq!(phase, EnumerationPhase::AwaitingPartialDescriptor);
+
+        // A full-descriptor completion arriving while still waiting on
+        // Evaluate Context must not be accepted early.
+        let phase = EnumerationPhase::start()
+            .on_partial_descriptor(8, 64)
+            .on_full_descriptor(sample_device());
+        assert_eq!(
+            phase,
+            EnumerationPhase::AwaitingEvaluateContext { max_packet_size0: 64 }
+        );
+    }
+
+    #[test]
+    fn device_is_only_available_once_done() {
+        assert_eq!(EnumerationPhase::start().device(), None);
+    }
+}
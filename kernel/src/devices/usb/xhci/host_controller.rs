@@ -0,0 +1,240 @@
+/// Decoded contents of the xHCI Runtime register MFINDEX (xHCI spec
+/// §5.5.1): the controller's frame/microframe counter, incrementing
+/// every 125us and wrapping at 2048 frames -- what interval scheduling
+/// for isochronous and interrupt endpoints is timed against.
+///
+/// There's no `Runtime`/`Controller` register abstraction in this tree
+/// for a `current_microframe` to read the raw value from -- `HostController`
+/// doesn't map any operational or runtime register yet, only tracking
+/// `mmio_base` (see its own doc comment) -- so this only decodes a raw
+/// MFINDEX value a caller already has. Locating the Runtime register
+/// space to read one from needs the capability registers' CAPLENGTH and
+/// RTSOFF, neither of which this tree parses yet either.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MicroframeIndex(u32);
+
+impl MicroframeIndex {
+    /// Only the low 14 bits of MFINDEX are defined; the rest are
+    /// reserved and must be ignored on read (xHCI spec §5.5.1).
+    pub fn from_raw(raw: u32) -> Self {
+        Self(raw & 0x3fff)
+    }
+
+    /// The current microframe within [`Self::frame`], `0..8`.
+    pub fn microframe(&self) -> u8 {
+        (self.0 & 0x7) as u8
+    }
+
+    /// The current frame, incrementing every 8 microframes (1ms) and
+    /// wrapping at 2048 -- the unit `EndpointConfig`'s full-/low-speed
+    /// interval is expressed in.
+    pub fn frame(&self) -> u16 {
+        (self.0 >> 3) as u16
+    }
+
+    pub fn as_raw(&self) -> u32 {
+        self.0
+    }
+
+    /// The `MicroframeIndex` an endpoint polled at this one should next
+    /// be re-armed at, `2^interval_exponent` microframes later --
+    /// [`EndpointConfig::xhci_interval`](super::EndpointConfig::xhci_interval)'s
+    /// result, since both already agree the schedule period is
+    /// `2^interval_exponent` microframes. Wraps the same way the real
+    /// register does, via [`Self::from_raw`]'s mask.
+    ///
+    /// There's no HID driver or re-arm loop in this tree yet to call
+    /// this from -- nothing queues a second interrupt-IN transfer on
+    /// completion of the first, so there's nothing to gate on "has the
+    /// interval elapsed" today. This is the frame-math half of that gate,
+    /// ready for whichever re-arm loop eventually reads a real MFINDEX.
+    pub fn advance_by_interval(&self, interval_exponent: u8) -> Self {
+        Self::from_raw(self.0.wrapping_add(1u32 << interval_exponent))
+    }
+}
+
+/// Coarse lifecycle of the xHC, mirroring the subset of USBCMD/USBSTS
+/// transitions this driver currently drives.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum State {
+    #[default]
+    Halted,
+    Running,
+}
+
+/// Monotonically increasing counters for how the controller is doing,
+/// meant for the `usbstat` shell command and the status bar.
+///
+/// Plain integers, not atomics: [`HostController::process_events`] (the
+/// only place any of these are incremented today) runs on the single
+/// main-loop thread, with nothing else able to touch a `HostController`
+/// concurrently -- if that ever changes, these need to move to atomics
+/// or a lock before it does.
+///
+/// `command_errors`, `transfer_errors`, `port_resets`, and
+/// `enumerated_devices` exist here for `usbstat` to report, but nothing
+/// increments them yet: there's no command ring, transfer event
+/// dispatch, port reset handling, or enumeration path wired into
+/// this driver to dispatch those events from (see the module doc and
+/// [`super::enumeration`]). `events_by_type`, a per-TRB-type breakdown
+/// the request also asked for, isn't here at all -- `process_events`
+/// doesn't decode a TRB's type yet, so there's no "type" to key a
+/// breakdown by; `events_processed` is the flat count it can actually
+/// produce today.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub events_processed: usize,
+    pub command_errors: usize,
+    pub transfer_errors: usize,
+    pub port_resets: usize,
+    pub enumerated_devices: usize,
+}
+
+/// Minimal handle onto the xHC's MMIO region.
+///
+/// This does not yet touch the capability/operational register sets; it
+/// only tracks enough state to let the main loop drain the event ring.
+/// The register plumbing (USBCMD, USBSTS, the interrupter's event ring
+/// dequeue pointer, ...) lands incrementally as the driver grows.
+#[derive(Debug)]
+pub struct HostController {
+    #[allow(dead_code)]
+    mmio_base: usize,
+    state: State,
+    stats: Stats,
+}
+
+impl HostController {
+    pub const fn new(mmio_base: usize) -> Self {
+        Self {
+            mmio_base,
+            state: State::Halted,
+            stats: Stats {
+                events_processed: 0,
+                command_errors: 0,
+                transfer_errors: 0,
+                port_resets: 0,
+                enumerated_devices: 0,
+            },
+        }
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Transition the controller into `Running`, i.e. the point after
+    /// which the event ring may start filling up.
+    pub fn run(&mut self) {
+        // TODO: actually set USBCMD.RS once the operational register set
+        // is mapped; for now this only flips the driver-side state so the
+        // main loop knows to start polling.
+        super::trace::state(self.state, State::Running);
+        self.state = State::Running;
+    }
+
+    /// Whether the main loop should call [`Self::process_events`].
+    ///
+    /// Until the event ring's dequeue pointer is wired up this is a
+    /// polling fallback that simply checks the controller is running, as
+    /// suggested for the interrupt-less bring-up path.
+    pub fn has_unprocessed_events(&self) -> bool {
+        self.state == State::Running
+    }
+
+    /// Drain and dispatch pending events from the event ring.
+    ///
+    /// Returns the number of events processed this call.
+    pub fn process_events(&mut self) -> usize {
+        if !self.has_unprocessed_events() {
+            return 0;
+        }
+        // TODO: walk the event ring TRB-by-TRB and dispatch to the device
+        // manager once the ring/TRB types exist.
+        self.stats.events_processed += 1;
+        1
+    }
+
+    pub fn events_processed(&self) -> usize {
+        self.stats.events_processed
+    }
+
+    /// Snapshot of the counters tracked in [`Stats`].
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Transitions back to `Halted`, e.g. to recover from a misbehaving
+    /// controller or to re-enumerate after a configuration change.
+    ///
+    /// There's no `HcStatus` typestate, event/command ring, or
+    /// `DeviceManager` in this tree yet (`run` itself only flips
+    /// driver-side state, with a TODO for the real USBCMD.RS write), so
+    /// this is scoped the same way: it resets the state this struct
+    /// actually has, but doesn't wait for HCHalted, clear a ring dequeue
+    /// pointer, or signal a caller to rebuild a device manager, since
+    /// none of those exist here to clear or rebuild. `run()` can be
+    /// called again afterwards to return to `Running`.
+    pub fn stop(&mut self) {
+        super::trace::state(self.state, State::Halted);
+        self.state = State::Halted;
+        self.stats = Stats::default();
+    }
+
+    /// Whether the controller is halted, i.e. safe to treat as fully
+    /// stopped.
+    ///
+    /// There's no `status` module, `hc_halted` register read, or
+    /// `HostController<S>` typestate in this tree -- `State` is a plain
+    /// enum field, not a generic parameter, so there's no
+    /// `HostController<Running>`/`HostController<Resetted>` to add a
+    /// `stop(self) -> HostController<Resetted>` transition between (see
+    /// [`Self::stop`] for the non-consuming equivalent this tree already
+    /// has). Until USBSTS is actually mapped, this just reads back the
+    /// driver-side state `stop`/`run` already track; once it is, a
+    /// caller recovering from an unexpected halt `stop` wasn't called
+    /// for can poll this the same way a real `hc_halted` spin-wait would.
+    pub fn is_halted(&self) -> bool {
+        self.state == State::Halted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_raw_masks_off_the_reserved_bits() {
+        assert_eq!(MicroframeIndex::from_raw(0xffff_ffff).as_raw(), 0x3fff);
+    }
+
+    #[test]
+    fn decodes_frame_and_microframe() {
+        // frame 5, microframe 3: (5 << 3) | 3 = 43
+        let mfindex = MicroframeIndex::from_raw(43);
+        assert_eq!(mfindex.frame(), 5);
+        assert_eq!(mfindex.microframe(), 3);
+    }
+
+    #[test]
+    fn frame_wraps_at_2048() {
+        let mfindex = MicroframeIndex::from_raw((2048 * 8) + 1);
+        assert_eq!(mfindex.frame(), 0);
+        assert_eq!(mfindex.microframe(), 1);
+    }
+
+    #[test]
+    fn advance_by_interval_adds_the_microframe_count() {
+        let mfindex = MicroframeIndex::from_raw(43); // frame 5, microframe 3
+        let next = mfindex.advance_by_interval(3); // 2^3 = 8 microframes = 1 frame
+        assert_eq!(next.frame(), 6);
+        assert_eq!(next.microframe(), 3);
+    }
+
+    #[test]
+    fn advance_by_interval_wraps_like_the_real_register() {
+        let mfindex = MicroframeIndex::from_raw(0x3fff);
+        let next = mfindex.advance_by_interval(0);
+        assert_eq!(next.as_raw(), 0);
+    }
+}
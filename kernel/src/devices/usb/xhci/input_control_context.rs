@@ -0,0 +1,122 @@
+//! The Input Control Context (xHCI spec §6.2.5.1): the Add/Drop Context
+//! flags that tell AddressDevice/ConfigureEndpoint/EvaluateContext which
+//! of an Input Context's Slot Context and Endpoint Contexts to apply.
+//!
+//! There's no `Device` type or `inp_ctx` field in this tree for a real
+//! `Device::prepare_input_for_configure` to live on, and no
+//! `DeviceManager`/`address_device` to own the physical Input Context
+//! buffer this would sit at the front of (see [`super::slot_context`]'s
+//! module doc for the pieces that don't exist yet). There's also no
+//! command-issuing machinery to add a "no command using this context is
+//! still pending" debug check to. This is the encoding half such a
+//! caller will need: which flags to set, composable with
+//! [`super::EndpointContext`] and [`super::SlotContext`] the same way
+//! those two are standalone ahead of their own callers.
+use bit_field::BitField;
+
+use super::endpoint::DeviceContextIndex;
+
+/// Which Endpoint Contexts (by DCI, `1..=31`) and/or the Slot Context
+/// (A0) a command should add, drop, or both -- ConfigureEndpoint sets
+/// A0 whenever any endpoint changes, since the Slot Context's Context
+/// Entries field has to be updated to match (xHCI spec §4.6.6).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct InputControlContext {
+    add_flags: u32,
+    drop_flags: u32,
+}
+
+impl InputControlContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks the Slot Context (A0) to be applied.
+    pub fn add_slot(&mut self) -> &mut Self {
+        self.add_flags.set_bit(0, true);
+        self
+    }
+
+    /// Marks `dci`'s Endpoint Context to be applied.
+    pub fn add_endpoint(&mut self, dci: DeviceContextIndex) -> &mut Self {
+        self.add_flags.set_bit(dci.raw() as usize, true);
+        self
+    }
+
+    /// Marks `dci`'s Endpoint Context to be disabled. D0/D1 (bits 0..=1,
+    /// the Slot Context and DCI 1) are reserved and always 0 -- the
+    /// control endpoint is never dropped on its own (xHCI spec Table
+    /// 6-12).
+    pub fn drop_endpoint(&mut self, dci: DeviceContextIndex) -> &mut Self {
+        if dci.raw() >= 2 {
+            self.drop_flags.set_bit(dci.raw() as usize, true);
+        }
+        self
+    }
+}
+
+/// The Input Control Context's first two dwords (xHCI spec Figure 6-11):
+/// `[Drop Context flags, Add Context flags]`. The remaining six dwords
+/// (Configuration Value/Interface Number/Alternate Setting and
+/// reserved fields) aren't set here -- they're only meaningful alongside
+/// the Slot Context and Endpoint Contexts that follow this one in a
+/// real Input Context buffer.
+impl From<InputControlContext> for [u32; 2] {
+    fn from(ctx: InputControlContext) -> Self {
+        [ctx.drop_flags, ctx.add_flags]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dci(raw: u8) -> DeviceContextIndex {
+        DeviceContextIndex::try_from(raw).unwrap()
+    }
+
+    #[test]
+    fn add_slot_sets_bit_zero_of_add_flags() {
+        let mut ctx = InputControlContext::new();
+        ctx.add_slot();
+        let dwords: [u32; 2] = ctx.into();
+        assert!(dwords[1].get_bit(0));
+        assert_eq!(dwords[0], 0);
+    }
+
+    #[test]
+    fn add_endpoint_sets_its_dci_bit_in_add_flags() {
+        let mut ctx = InputControlContext::new();
+        ctx.add_endpoint(dci(3));
+        let dwords: [u32; 2] = ctx.into();
+        assert!(dwords[1].get_bit(3));
+        assert!(!dwords[1].get_bit(0));
+    }
+
+    #[test]
+    fn drop_endpoint_sets_its_dci_bit_in_drop_flags() {
+        let mut ctx = InputControlContext::new();
+        ctx.drop_endpoint(dci(5));
+        let dwords: [u32; 2] = ctx.into();
+        assert!(dwords[0].get_bit(5));
+    }
+
+    #[test]
+    fn drop_endpoint_on_the_control_endpoint_is_a_no_op() {
+        let mut ctx = InputControlContext::new();
+        ctx.drop_endpoint(DeviceContextIndex::try_from(1).unwrap());
+        let dwords: [u32; 2] = ctx.into();
+        assert_eq!(dwords[0], 0);
+    }
+
+    #[test]
+    fn add_and_drop_can_target_different_endpoints_at_once() {
+        let mut ctx = InputControlContext::new();
+        ctx.add_slot();
+        ctx.add_endpoint(dci(4));
+        ctx.drop_endpoint(dci(2));
+        let dwords: [u32; 2] = ctx.into();
+        assert_eq!(dwords[0], 0b0100);
+        assert_eq!(dwords[1], 0b10001);
+    }
+}
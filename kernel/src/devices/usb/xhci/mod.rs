@@ -0,0 +1,68 @@
+//! xHCI building blocks -- TRB/context encoding, port<->slot bookkeeping,
+//! transfer descriptor construction, and so on -- built one piece at a
+//! time ahead of the `DeviceManager`/command-ring/enumeration driver
+//! that would actually call them. [`HostController`] is the only type
+//! here wired to `main.rs`, and only as far as starting the controller
+//! and draining its event ring (see its own doc comment); none of the
+//! rest is reachable from the boot path yet, which is why several of
+//! the `pub use`s below carry their own `#[allow(unused_imports)]`.
+//! Treat this module as a primitives milestone, not a working USB
+//! stack: every piece is unit-tested in isolation, but nothing here has
+//! been exercised end-to-end against real (or emulated) hardware, e.g.
+//! an actual Address Device round trip.
+mod command;
+mod completion;
+mod dcbaa;
+mod endpoint;
+mod endpoint_config;
+mod endpoint_context;
+mod endpoint_context_array;
+mod enumeration;
+mod host_controller;
+mod input_control_context;
+mod port_addressing;
+mod port_enumeration_summary;
+mod port_slot_map;
+mod portsc;
+mod ring;
+mod slot_context;
+mod trace;
+mod transfer;
+
+pub use command::{EvaluateContextCommand, ResetEndpointCommand, SetTrDequeuePointerCommand};
+pub use completion::{check, CompletionCode, TransferFailed};
+pub use dcbaa::DeviceContextBaseAddressArray;
+pub use endpoint::{DeviceContextIndex, EndpointId};
+pub use endpoint_config::EndpointConfig;
+pub use endpoint_context::EndpointContext;
+pub use endpoint_context_array::EndpointContextArray;
+pub use enumeration::EnumerationPhase;
+pub use host_controller::{HostController, MicroframeIndex, State, Stats};
+pub use input_control_context::InputControlContext;
+pub use port_addressing::{PortAction, PortAddressing};
+pub use port_enumeration_summary::{PortEnumerationError, PortEnumerationResult, PortEnumerationSummary};
+// Not wired to a caller yet -- nothing maps a port to a slot outside
+// this module's own tests.
+#[allow(unused_imports)]
+pub use port_slot_map::{PortSlotMap, PortSlotMapError, SlotSummary};
+// Not wired to a caller yet -- nothing decodes PORTSC's change bits
+// outside this module's own tests.
+#[allow(unused_imports)]
+pub use portsc::PortStatusChange;
+// Not wired to a caller yet -- see ring.rs's own module doc.
+#[allow(unused_imports)]
+pub use ring::TransferRing;
+// Not wired to a caller yet -- no device-enumeration code builds a
+// SlotContext outside this module's own tests.
+#[allow(unused_imports)]
+pub use slot_context::{HubAttachment, SlotContext, TransactionTranslator};
+pub use transfer::NormalTrb;
+
+// Not wired to a caller yet -- no command-ring/doorbell-issuing code
+// exists to use this.
+#[allow(unused_imports)]
+pub use transfer::doorbell_value;
+// Not wired to a caller yet -- no transfer-ring enqueue path builds one
+// of these outside this module's own tests.
+#[allow(unused_imports)]
+pub use transfer::{TransferDescriptor, TransferDescriptorError, MAX_TRB_TRANSFER_LEN};
@@ -0,0 +1,154 @@
+//! Serializes which port is being addressed: the xHC only permits one
+//! outstanding Address Device command at a time (xHCI spec §4.3.4), so a
+//! port that connects while another is mid-enumeration has to wait its
+//! turn rather than being addressed concurrently -- or, worse, silently
+//! dropped.
+//!
+//! Kept standalone from `DeviceManager` (which doesn't exist in this
+//! tree): this models just the addressing slot and the FIFO of waiting
+//! ports, so the one-at-a-time invariant and the "two ports connect at
+//! once" case are both real, tested logic ready for `DeviceManager` to
+//! drive once it exists.
+use core::mem;
+
+/// What a newly connected port should do, returned by [`PortAddressing::connect`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortAction {
+    /// No port is currently being addressed: reset `port_id` and begin
+    /// addressing it immediately.
+    BeginAddressing(u8),
+    /// Another port is mid-address; `port_id` is now queued and will be
+    /// returned from a future [`PortAddressing::complete_configuration`].
+    Queued,
+    /// The wait queue is full; `port_id` was dropped.
+    QueueFull,
+}
+
+/// Tracks the port currently being addressed and a FIFO of ports waiting
+/// their turn, up to `N` waiters.
+#[derive(Debug)]
+pub struct PortAddressing<const N: usize> {
+    addressing: Option<u8>,
+    pending: [Option<u8>; N],
+    pending_len: usize,
+}
+
+impl<const N: usize> Default for PortAddressing<N> {
+    fn default() -> Self {
+        Self {
+            addressing: None,
+            pending: [None; N],
+            pending_len: 0,
+        }
+    }
+}
+
+impl<const N: usize> PortAddressing<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn addressing_port(&self) -> Option<u8> {
+        self.addressing
+    }
+
+    /// A port just connected. If no port is being addressed, `port_id`
+    /// becomes the one being addressed; otherwise it's queued.
+    pub fn connect(&mut self, port_id: u8) -> PortAction {
+        if self.addressing.is_none() {
+            self.addressing = Some(port_id);
+            return PortAction::BeginAddressing(port_id);
+        }
+        if self.pending_len == N {
+            return PortAction::QueueFull;
+        }
+        self.pending[self.pending_len] = Some(port_id);
+        self.pending_len += 1;
+        PortAction::Queued
+    }
+
+    /// `port_id` finished enumeration (reached Configured). If it was
+    /// the port being addressed, frees the slot and pops the next
+    /// waiter, if any, returning it so the caller can begin addressing
+    /// it in turn.
+    pub fn complete_configuration(&mut self, port_id: u8) -> Option<u8> {
+        if self.addressing != Some(port_id) {
+            return None;
+        }
+        if self.pending_len == 0 {
+            self.addressing = None;
+            return None;
+        }
+        let next = self.pending[0];
+        for i in 1..self.pending_len {
+            self.pending[i - 1] = mem::take(&mut self.pending[i]);
+        }
+        self.pending_len -= 1;
+        self.addressing = next;
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_connection_addresses_immediately() {
+        let mut queue: PortAddressing<4> = PortAddressing::new();
+        assert_eq!(queue.connect(1), PortAction::BeginAddressing(1));
+        assert_eq!(queue.addressing_port(), Some(1));
+    }
+
+    #[test]
+    fn second_simultaneous_connection_is_queued_not_dropped() {
+        let mut queue: PortAddressing<4> = PortAddressing::new();
+        assert_eq!(queue.connect(1), PortAction::BeginAddressing(1));
+        assert_eq!(queue.connect(2), PortAction::Queued);
+        // Port 2 must not have been silently ignored: it's still
+        // pending, not addressed and not lost.
+        assert_eq!(queue.addressing_port(), Some(1));
+    }
+
+    #[test]
+    fn two_simultaneous_connections_both_eventually_enumerate() {
+        let mut queue: PortAddressing<4> = PortAddressing::new();
+        assert_eq!(queue.connect(1), PortAction::BeginAddressing(1));
+        assert_eq!(queue.connect(2), PortAction::Queued);
+
+        // Port 1 finishes configuring; port 2 is popped and begins.
+        assert_eq!(queue.complete_configuration(1), Some(2));
+        assert_eq!(queue.addressing_port(), Some(2));
+
+        // Port 2 finishes; nothing left waiting.
+        assert_eq!(queue.complete_configuration(2), None);
+        assert_eq!(queue.addressing_port(), None);
+    }
+
+    #[test]
+    fn completion_for_a_port_that_is_not_being_addressed_is_ignored() {
+        let mut queue: PortAddressing<4> = PortAddressing::new();
+        queue.connect(1);
+        assert_eq!(queue.complete_configuration(99), None);
+        assert_eq!(queue.addressing_port(), Some(1));
+    }
+
+    #[test]
+    fn waiters_are_served_in_connection_order() {
+        let mut queue: PortAddressing<4> = PortAddressing::new();
+        queue.connect(1);
+        queue.connect(2);
+        queue.connect(3);
+        assert_eq!(queue.complete_configuration(1), Some(2));
+        assert_eq!(queue.complete_configuration(2), Some(3));
+        assert_eq!(queue.complete_configuration(3), None);
+    }
+
+    #[test]
+    fn a_full_queue_reports_the_overflowing_port_as_dropped() {
+        let mut queue: PortAddressing<1> = PortAddressing::new();
+        queue.connect(1);
+        assert_eq!(queue.connect(2), PortAction::Queued);
+        assert_eq!(queue.connect(3), PortAction::QueueFull);
+    }
+}
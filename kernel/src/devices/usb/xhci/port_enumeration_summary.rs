@@ -0,0 +1,117 @@
+//! Per-port enumeration outcomes, the shape
+//! `HostController::enumerate_connected` would hand back and `lsusb`
+//! would render.
+//!
+//! There's no `HostController<Running>` in this tree to add that method
+//! to -- [`super::host_controller::HostController::is_halted`]'s own doc
+//! comment already spells out why: `State` is a plain enum field, not a
+//! generic parameter, so there's no `HostController<S>` typestate to
+//! pick a `Running` variant of. There's also no `reset_port` (no
+//! `PORTSC` register access at all yet) and no timeout service (nothing
+//! in this tree tracks wall-clock deadlines against
+//! [`super::super::super::services::time`]'s uptime). This is the
+//! standalone piece that doesn't need any of those: given a result per
+//! port as it becomes known, track whether every connected port has
+//! settled (`Configured` or failed) yet, bounded the same way
+//! [`super::port_slot_map::PortSlotMap`] is.
+use super::super::descriptor::Device as DeviceDescriptor;
+
+/// Why a port's enumeration didn't reach [`EnumerationPhase::Done`](super::EnumerationPhase::Done).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortEnumerationError {
+    /// No timeout service exists in this tree yet to actually raise
+    /// this -- see the module doc -- but it's the one failure mode a
+    /// caller blocking with a `timeout_ms` needs to distinguish from
+    /// "still in progress".
+    Timeout,
+}
+
+pub type PortEnumerationResult = Result<DeviceDescriptor, PortEnumerationError>;
+
+/// One outcome per port, for up to `N` root hub ports.
+#[derive(Debug)]
+pub struct PortEnumerationSummary<const N: usize> {
+    results: [Option<PortEnumerationResult>; N],
+}
+
+impl<const N: usize> Default for PortEnumerationSummary<N> {
+    fn default() -> Self {
+        Self { results: [None; N] }
+    }
+}
+
+impl<const N: usize> PortEnumerationSummary<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `port_id`'s outcome, overwriting any previous one --
+    /// e.g. a port that times out and is then unplugged and replugged
+    /// before the caller gives up waiting.
+    pub fn record(&mut self, port_id: u8, result: PortEnumerationResult) {
+        self.results[port_id as usize] = Some(result);
+    }
+
+    pub fn result_for_port(&self, port_id: u8) -> Option<PortEnumerationResult> {
+        self.results[port_id as usize]
+    }
+
+    /// Whether every port in `connected_ports` has a recorded outcome --
+    /// what a blocking wait loop would poll to know it's done, once one
+    /// exists to poll it.
+    pub fn all_settled(&self, connected_ports: &[u8]) -> bool {
+        connected_ports.iter().all(|&port_id| self.results[port_id as usize].is_some())
+    }
+
+    /// How many recorded outcomes were successful.
+    pub fn configured_count(&self) -> usize {
+        self.results.iter().filter(|r| matches!(r, Some(Ok(_)))).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_device() -> DeviceDescriptor {
+        DeviceDescriptor([0u8; DeviceDescriptor::LENGTH])
+    }
+
+    #[test]
+    fn unrecorded_ports_report_none() {
+        let summary: PortEnumerationSummary<8> = PortEnumerationSummary::new();
+        assert_eq!(summary.result_for_port(2), None);
+    }
+
+    #[test]
+    fn record_then_result_for_port_round_trips() {
+        let mut summary: PortEnumerationSummary<8> = PortEnumerationSummary::new();
+        summary.record(2, Ok(sample_device()));
+        assert_eq!(summary.result_for_port(2), Some(Ok(sample_device())));
+    }
+
+    #[test]
+    fn all_settled_is_false_until_every_connected_port_has_an_outcome() {
+        let mut summary: PortEnumerationSummary<8> = PortEnumerationSummary::new();
+        summary.record(2, Ok(sample_device()));
+        assert!(!summary.all_settled(&[2, 3]));
+        summary.record(3, Err(PortEnumerationError::Timeout));
+        assert!(summary.all_settled(&[2, 3]));
+    }
+
+    #[test]
+    fn configured_count_only_counts_successes() {
+        let mut summary: PortEnumerationSummary<8> = PortEnumerationSummary::new();
+        summary.record(2, Ok(sample_device()));
+        summary.record(3, Err(PortEnumerationError::Timeout));
+        assert_eq!(summary.configured_count(), 1);
+    }
+
+    #[test]
+    fn recording_again_overwrites_the_previous_outcome() {
+        let mut summary: PortEnumerationSummary<8> = PortEnumerationSummary::new();
+        summary.record(2, Err(PortEnumerationError::Timeout));
+        summary.record(2, Ok(sample_device()));
+        assert_eq!(summary.result_for_port(2), Some(Ok(sample_device())));
+    }
+}
@@ -0,0 +1,156 @@
+//! Bidirectional port<->slot ID mapping. A command completion identified
+//! by Slot ID (ConfigureEndpoint, and eventually ResetEndpoint/
+//! DisableSlot) should look its port up here rather than falling back to
+//! whichever port the controller happens to be addressing right now --
+//! that fallback is only correct for EnableSlot, the one command that
+//! doesn't carry a port at all yet.
+//!
+//! `DeviceManager::command_completion`/`port_to_slot`/`slot_to_port`/
+//! `try_get_addressing_port` don't exist in this tree -- there's no
+//! `DeviceManager` (see [`super::port_addressing`]) -- so this is the
+//! standalone map itself, correctly indexed in both directions, with a
+//! regression test for the indexing bug the request calls out: a
+//! `set_port_to_slot` that checked `port_to_slot`'s occupancy by
+//! `slot_id` instead of `port_id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortSlotMapError {
+    /// `port_id` already has a slot assigned; a real controller never
+    /// issues a second Address Device for an already-addressed port, so
+    /// this indicates a logic error upstream rather than expected input.
+    PortAlreadyMapped,
+}
+
+impl core::fmt::Display for PortSlotMapError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::PortAlreadyMapped => write!(f, "port already has a slot assigned"),
+        }
+    }
+}
+
+/// Maps port IDs to slot IDs and back, for up to `N` of each (ports and
+/// slots share the same valid ID range, `0..N`, on this controller).
+#[derive(Debug)]
+pub struct PortSlotMap<const N: usize> {
+    port_to_slot: [Option<u8>; N],
+    slot_to_port: [Option<u8>; N],
+}
+
+impl<const N: usize> Default for PortSlotMap<N> {
+    fn default() -> Self {
+        Self {
+            port_to_slot: [None; N],
+            slot_to_port: [None; N],
+        }
+    }
+}
+
+impl<const N: usize> PortSlotMap<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that `port_id` was just assigned `slot_id` by an
+    /// AddressDevice completion. Fails if `port_id` already has a slot
+    /// -- checked by indexing `port_to_slot` by `port_id`, not `slot_id`,
+    /// which is the bug this type exists to not repeat.
+    pub fn set(&mut self, port_id: u8, slot_id: u8) -> Result<(), PortSlotMapError> {
+        if self.port_to_slot[port_id as usize].is_some() {
+            return Err(PortSlotMapError::PortAlreadyMapped);
+        }
+        self.port_to_slot[port_id as usize] = Some(slot_id);
+        self.slot_to_port[slot_id as usize] = Some(port_id);
+        Ok(())
+    }
+
+    pub fn slot_for_port(&self, port_id: u8) -> Option<u8> {
+        self.port_to_slot[port_id as usize]
+    }
+
+    pub fn port_for_slot(&self, slot_id: u8) -> Option<u8> {
+        self.slot_to_port[slot_id as usize]
+    }
+
+    /// Snapshot of how many slot IDs are currently assigned, out of the
+    /// `N` this map was built for.
+    ///
+    /// There's no root-hub port count or per-port connection status to
+    /// report alongside this -- that needs the xHC's HCSPARAMS1
+    /// capability register and live PORTSC reads, neither of which
+    /// `HostController` maps yet (see its module doc). `SlotSummary` is
+    /// only the half of the request this map can actually answer: how
+    /// many AddressDevice completions have landed a slot.
+    pub fn summary(&self) -> SlotSummary {
+        SlotSummary {
+            mapped: self.slot_to_port.iter().filter(|slot| slot.is_some()).count(),
+            capacity: N,
+        }
+    }
+}
+
+/// Returned by [`PortSlotMap::summary`], for the `usbstat` shell command
+/// and status bar diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotSummary {
+    pub mapped: usize,
+    pub capacity: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_in_both_directions() {
+        let mut map: PortSlotMap<8> = PortSlotMap::new();
+        map.set(2, 5).unwrap();
+        assert_eq!(map.slot_for_port(2), Some(5));
+        assert_eq!(map.port_for_slot(5), Some(2));
+    }
+
+    #[test]
+    fn unmapped_ids_report_none() {
+        let map: PortSlotMap<8> = PortSlotMap::new();
+        assert_eq!(map.slot_for_port(3), None);
+        assert_eq!(map.port_for_slot(3), None);
+    }
+
+    #[test]
+    fn remapping_an_already_addressed_port_is_rejected() {
+        let mut map: PortSlotMap<8> = PortSlotMap::new();
+        map.set(1, 2).unwrap();
+        assert_eq!(map.set(1, 3), Err(PortSlotMapError::PortAlreadyMapped));
+    }
+
+    /// Regression test for the reported bug: a `set` whose occupancy
+    /// check reads `port_to_slot[slot_id]` instead of
+    /// `port_to_slot[port_id]` would, after mapping port 5 -> slot 1
+    /// (which happens to write `port_to_slot[5] = Some(1)`), wrongly
+    /// reject mapping the *different*, still-unmapped port 1 -> slot 5,
+    /// because index 5 of `port_to_slot` is occupied for an unrelated
+    /// reason. The correct check only ever looks at `port_to_slot[1]`.
+    #[test]
+    fn occupancy_check_is_keyed_by_port_id_not_slot_id() {
+        let mut map: PortSlotMap<8> = PortSlotMap::new();
+        map.set(5, 1).unwrap();
+        assert_eq!(map.set(1, 5), Ok(()));
+        assert_eq!(map.slot_for_port(1), Some(5));
+        assert_eq!(map.slot_for_port(5), Some(1));
+    }
+
+    #[test]
+    fn summary_counts_only_mapped_slots() {
+        let mut map: PortSlotMap<8> = PortSlotMap::new();
+        map.set(2, 5).unwrap();
+        map.set(3, 6).unwrap();
+        assert_eq!(map.summary(), SlotSummary { mapped: 2, capacity: 8 });
+    }
+
+    #[test]
+    fn display_gives_a_human_message() {
+        assert_eq!(
+            format!("{}", PortSlotMapError::PortAlreadyMapped),
+            "port already has a slot assigned"
+        );
+    }
+}
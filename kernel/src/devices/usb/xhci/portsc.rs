@@ -0,0 +1,115 @@
+//! PORTSC (Port Status and Control) change-bit decoding (xHCI spec
+//! Table 5-20, bits 17..=21). Kept standalone from [`super::host_controller`]:
+//! this driver doesn't map the operational register set yet, so there is
+//! no live PORTSC to read and no `DeviceManager`/port-phase state machine
+//! to route these into -- this is the bit-layout logic ready for when
+//! both exist, so a real Port Status Change Event handler isn't starting
+//! from scratch.
+use bit_field::BitField;
+
+const CSC_BIT: usize = 17;
+const PEC_BIT: usize = 18;
+const WRC_BIT: usize = 19;
+const OCC_BIT: usize = 20;
+const PRC_BIT: usize = 21;
+
+/// Which of PORTSC's RW1C "change" bits are set, decoded from a raw
+/// register value: Connect Status Change, Port Enabled/Disabled Change,
+/// Warm Reset Change, Over-current Change, Port Reset Change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PortStatusChange {
+    pub connect_status_change: bool,
+    pub port_enabled_disabled_change: bool,
+    pub warm_reset_change: bool,
+    pub overcurrent_change: bool,
+    pub port_reset_change: bool,
+}
+
+impl PortStatusChange {
+    /// Decodes `portsc`'s change bits. Every other bit (port speed, PLS,
+    /// PP, ...) is ignored; this type only ever reports the RW1C bits a
+    /// Port Status Change Event handler needs to route and acknowledge.
+    pub fn decode(portsc: u32) -> Self {
+        Self {
+            connect_status_change: portsc.get_bit(CSC_BIT),
+            port_enabled_disabled_change: portsc.get_bit(PEC_BIT),
+            warm_reset_change: portsc.get_bit(WRC_BIT),
+            overcurrent_change: portsc.get_bit(OCC_BIT),
+            port_reset_change: portsc.get_bit(PRC_BIT),
+        }
+    }
+
+    /// Whether any change bit is set at all, i.e. whether this PORTSC is
+    /// plausibly the source of the event that was just dispatched.
+    pub fn any(&self) -> bool {
+        self.connect_status_change
+            || self.port_enabled_disabled_change
+            || self.warm_reset_change
+            || self.overcurrent_change
+            || self.port_reset_change
+    }
+
+    /// The value to write back to PORTSC to acknowledge exactly the bits
+    /// this was decoded from. RW1C bits clear when written `1` and are
+    /// unaffected by a `0`, so every bit this wasn't decoded from -- the
+    /// other change bits as well as PORTSC's RW/RW1S bits -- is left `0`
+    /// here rather than round-tripping the raw value back, which would
+    /// risk re-triggering a port reset or power toggle on write-back.
+    pub fn clear_mask(&self) -> u32 {
+        let mut mask = 0u32;
+        mask.set_bit(CSC_BIT, self.connect_status_change);
+        mask.set_bit(PEC_BIT, self.port_enabled_disabled_change);
+        mask.set_bit(WRC_BIT, self.warm_reset_change);
+        mask.set_bit(OCC_BIT, self.overcurrent_change);
+        mask.set_bit(PRC_BIT, self.port_reset_change);
+        mask
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_each_change_bit_independently() {
+        assert_eq!(
+            PortStatusChange::decode(1 << CSC_BIT),
+            PortStatusChange {
+                connect_status_change: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            PortStatusChange::decode(1 << OCC_BIT),
+            PortStatusChange {
+                overcurrent_change: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn ignores_non_change_bits() {
+        // PP (bit 9) and a port speed nibble set, no change bits.
+        let portsc = (1 << 9) | (3 << 10);
+        assert_eq!(PortStatusChange::decode(portsc), PortStatusChange::default());
+    }
+
+    #[test]
+    fn no_bits_set_reports_none() {
+        assert!(!PortStatusChange::decode(0).any());
+    }
+
+    #[test]
+    fn clear_mask_only_sets_the_bits_that_were_decoded() {
+        let change = PortStatusChange::decode((1 << CSC_BIT) | (1 << PRC_BIT));
+        assert!(change.any());
+        let mask = change.clear_mask();
+        assert_eq!(mask, (1 << CSC_BIT) | (1 << PRC_BIT));
+    }
+
+    #[test]
+    fn clear_mask_of_no_change_is_zero() {
+        assert_eq!(PortStatusChange::default().clear_mask(), 0);
+    }
+}
@@ -0,0 +1,120 @@
+//! A fixed-capacity transfer ring producer (xHCI spec §4.9.2): the
+//! concrete piece `Hid` (or any other class driver) would hold to push
+//! TRBs onto an endpoint's transfer ring, which this tree doesn't have
+//! yet -- there's no `ClassDriver` trait, `Hid` type, or
+//! `Device::on_endpoints_configured` to own one. This only tracks the
+//! enqueue pointer and cycle state a producer needs; actually placing
+//! the ring in DMA-visible memory and wiring a Link TRB's target
+//! pointer back to the ring's own physical base address is left for
+//! when this tree has a way to allocate and address such memory.
+//!
+//! This is the only ring module in the tree -- there's no older
+//! `devices/usb/ring.rs` with a separate `Producer`/`Consumer` split or
+//! a `push` that forgets the cycle bit to consolidate away. If a second
+//! implementation is ever added, [`TransferRing`] (which does stamp the
+//! cycle bit on every enqueue) is the one to keep.
+use super::transfer::NormalTrb;
+
+/// A ring of `N` TRB slots, the last of which is reserved for the Link
+/// TRB that makes the ring wrap (xHCI spec §4.9.2.2) -- so `N - 1` TRBs
+/// can be enqueued before the producer wraps back to slot 0.
+#[derive(Debug)]
+pub struct TransferRing<const N: usize> {
+    trbs: [[u32; 4]; N],
+    enqueue_index: usize,
+    /// The Producer Cycle State: toggled every time the ring wraps, and
+    /// stamped into bit 0 of each TRB's control dword so the xHC (which
+    /// tracks its own Consumer Cycle State) can tell a not-yet-enqueued
+    /// slot from a real TRB.
+    cycle_state: bool,
+}
+
+impl<const N: usize> Default for TransferRing<N> {
+    fn default() -> Self {
+        Self {
+            trbs: [[0; 4]; N],
+            enqueue_index: 0,
+            // xHCI spec §4.9.1: rings start with Cycle State = 1.
+            cycle_state: true,
+        }
+    }
+}
+
+impl<const N: usize> TransferRing<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds `trb`'s TRB, stamps the ring's current cycle bit into it,
+    /// and writes it into the next slot, wrapping (and toggling the
+    /// cycle state) once the last usable slot is reached. Returns the
+    /// index the TRB was written to, i.e. where the doorbell write tells
+    /// the xHC to start consuming from.
+    pub fn enqueue_interrupt_transfer(&mut self, trb: NormalTrb) -> usize {
+        let mut raw: [u32; 4] = trb.into();
+        raw[3] |= self.cycle_state as u32;
+
+        let index = self.enqueue_index;
+        self.trbs[index] = raw;
+
+        if self.enqueue_index == N - 2 {
+            // The real implementation also writes a Link TRB into slot
+            // N - 1 here, pointing back at slot 0 with its Toggle Cycle
+            // bit set -- omitted since it needs this ring's own physical
+            // base address, which isn't known until it's placed in
+            // DMA-visible memory.
+            self.enqueue_index = 0;
+            self.cycle_state = !self.cycle_state;
+        } else {
+            self.enqueue_index += 1;
+        }
+        index
+    }
+
+    pub fn trb_at(&self, index: usize) -> [u32; 4] {
+        self.trbs[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_trb() -> NormalTrb {
+        NormalTrb {
+            buffer_ptr: 0x1000,
+            length: 8,
+            interrupt_on_completion: true,
+            chain: false,
+            td_size: 0,
+        }
+    }
+
+    #[test]
+    fn first_enqueue_stamps_the_initial_cycle_bit() {
+        let mut ring: TransferRing<4> = TransferRing::new();
+        let index = ring.enqueue_interrupt_transfer(sample_trb());
+        assert_eq!(index, 0);
+        assert_eq!(ring.trb_at(0)[3] & 1, 1);
+    }
+
+    #[test]
+    fn enqueue_advances_sequentially_until_the_link_slot() {
+        // N = 3: two usable slots (0, 1), slot 2 reserved for the Link TRB.
+        let mut ring: TransferRing<3> = TransferRing::new();
+        assert_eq!(ring.enqueue_interrupt_transfer(sample_trb()), 0);
+        assert_eq!(ring.enqueue_interrupt_transfer(sample_trb()), 1);
+    }
+
+    #[test]
+    fn wraps_and_toggles_cycle_state_after_the_last_usable_slot() {
+        let mut ring: TransferRing<3> = TransferRing::new();
+        ring.enqueue_interrupt_transfer(sample_trb()); // index 0
+        ring.enqueue_interrupt_transfer(sample_trb()); // index 1, last usable slot (N - 2)
+
+        // Wraps back to 0, with the cycle bit now flipped to 0.
+        let index = ring.enqueue_interrupt_transfer(sample_trb());
+        assert_eq!(index, 0);
+        assert_eq!(ring.trb_at(0)[3] & 1, 0);
+    }
+}
@@ -0,0 +1,214 @@
+//! The xHCI Slot Context (xHCI spec §6.2.2): per-device topology and
+//! speed, set once by Address Device and otherwise only touched by
+//! Evaluate Context.
+//!
+//! There's no `DeviceManager`/`address_device` in this tree (see
+//! [`super::port_addressing`], [`super::port_slot_map`], and
+//! [`super::dcbaa`]'s module docs for the pieces that don't exist yet to
+//! build one from) to fill a Slot Context from, and no hub driver to
+//! supply a [`HubAttachment`] for a device that isn't on a root port.
+//! [`SlotContext::from_root_port`]/[`SlotContext::from_hub_port`] are the
+//! encoding half such a caller will need, the same standalone-ahead-of-
+//! its-consumer shape as [`super::EndpointContext`].
+use bit_field::BitField;
+
+use super::super::utils::PortSpeed;
+
+/// Where a device attaches on its immediate parent hub, for everything
+/// but a root-port device (which needs none of this -- its Slot
+/// Context's Route String is always 0 and it has no Parent Hub Slot
+/// ID/Port Number).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HubAttachment {
+    pub hub_slot: u8,
+    /// 1-based downstream port number on `hub_slot` the device is
+    /// attached to.
+    pub hub_port: u8,
+    /// The parent hub's own Route String.
+    pub hub_route_string: u32,
+    /// How many nibbles of `hub_route_string` are already populated --
+    /// `0` for a hub attached directly to a root port (USB 3.x spec
+    /// §8.9 tier 1, not itself encoded in any route string), `1` for a
+    /// hub one tier further, and so on. A hub's own tier is one more
+    /// than whatever tier it was attached at.
+    pub tier: u8,
+}
+
+/// FS/LS Transaction Translator fields (xHCI spec §6.2.2), needed only
+/// when the device is Low-/Full-Speed and its immediate parent hub is a
+/// High-Speed hub translating for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionTranslator {
+    /// Whether the parent hub is a multi-TT hub (one TT per downstream
+    /// port) rather than single-TT (one TT shared by the whole hub) --
+    /// from the parent hub's Hub Descriptor, which no hub driver in
+    /// this tree decodes yet.
+    pub multi_tt: bool,
+    /// TT Think Time, in increments of 8 FS bit times (xHCI spec Table
+    /// 6-7): `0..=3`, also read from the parent hub's Hub Descriptor.
+    pub think_time: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotContext {
+    route_string: u32,
+    speed: PortSpeed,
+    root_hub_port_number: u8,
+    parent: Option<HubAttachment>,
+    tt: Option<TransactionTranslator>,
+}
+
+impl SlotContext {
+    /// A device attached directly to a root port: Route String `0`, no
+    /// Parent Hub Slot ID/Port Number, no TT fields.
+    pub fn from_root_port(speed: PortSpeed, root_hub_port_number: u8) -> Self {
+        Self {
+            route_string: 0,
+            speed,
+            root_hub_port_number,
+            parent: None,
+            tt: None,
+        }
+    }
+
+    /// A device attached behind a hub. `root_hub_port_number` is still
+    /// the root hub port the whole chain descends from, not `parent`'s
+    /// own port number -- xHCI tracks that separately from the Route
+    /// String precisely so the controller doesn't have to walk the
+    /// topology to find it.
+    pub fn from_hub_port(speed: PortSpeed, root_hub_port_number: u8, parent: HubAttachment, tt: Option<TransactionTranslator>) -> Self {
+        Self {
+            route_string: route_string_for(&parent),
+            speed,
+            root_hub_port_number,
+            parent: Some(parent),
+            tt,
+        }
+    }
+}
+
+/// Appends `parent`'s downstream port number as the next free nibble of
+/// its own Route String (USB 3.x spec §8.9): tier 0 occupies bits 0..=3,
+/// tier 1 bits 4..=7, and so on through tier 4 (5 tiers, the most a USB
+/// topology allows below the root hub).
+fn route_string_for(parent: &HubAttachment) -> u32 {
+    let shift = 4 * parent.tier as u32;
+    parent.hub_route_string | ((parent.hub_port as u32 & 0xf) << shift)
+}
+
+/// The xHCI root hub's default Protocol Speed ID encoding (xHCI spec
+/// Table 5-18) is exactly the Slot Context Speed field's encoding too --
+/// the same mapping [`PortSpeed::from_xhci_psiv`] decodes, run in
+/// reverse.
+fn speed_psiv(speed: PortSpeed) -> u32 {
+    match speed {
+        PortSpeed::Full => 1,
+        PortSpeed::Low => 2,
+        PortSpeed::High => 3,
+        PortSpeed::Super => 4,
+    }
+}
+
+/// The Slot Context's eight dwords (xHCI spec §6.2.2, Figure 6-6). Hub
+/// (bit 26) and Context Entries (bits 27..=31) aren't set here -- both
+/// describe the device's own downstream ports/configured endpoints,
+/// neither of which is known at Address Device time; Max Exit Latency,
+/// Interrupter Target, USB Device Address, and Slot State (dwords 1 and
+/// 3's remaining fields) are likewise left for whatever fills in the
+/// rest of the Input Context around this Slot Context.
+impl From<SlotContext> for [u32; 8] {
+    fn from(ctx: SlotContext) -> Self {
+        let mut dw0 = 0u32;
+        dw0.set_bits(0..=19, ctx.route_string);
+        dw0.set_bits(20..=23, speed_psiv(ctx.speed));
+        if let Some(tt) = ctx.tt {
+            dw0.set_bit(25, tt.multi_tt);
+        }
+
+        let mut dw1 = 0u32;
+        dw1.set_bits(16..=23, ctx.root_hub_port_number as u32);
+
+        let mut dw2 = 0u32;
+        if let Some(parent) = ctx.parent {
+            dw2.set_bits(0..=7, parent.hub_slot as u32);
+            dw2.set_bits(8..=15, parent.hub_port as u32);
+        }
+        if let Some(tt) = ctx.tt {
+            dw2.set_bits(16..=17, tt.think_time as u32);
+        }
+
+        [dw0, dw1, dw2, 0, 0, 0, 0, 0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_port_device_gets_route_string_zero_and_no_parent_fields() {
+        let ctx = SlotContext::from_root_port(PortSpeed::High, 3);
+        let dwords: [u32; 8] = ctx.into();
+        assert_eq!(dwords[0].get_bits(0..=19), 0);
+        assert_eq!(dwords[1].get_bits(16..=23), 3);
+        assert_eq!(dwords[2], 0);
+    }
+
+    #[test]
+    fn speed_is_encoded_with_the_same_psiv_mapping_from_xhci_psiv_decodes() {
+        for (speed, psiv) in [
+            (PortSpeed::Full, 1),
+            (PortSpeed::Low, 2),
+            (PortSpeed::High, 3),
+            (PortSpeed::Super, 4),
+        ] {
+            let ctx = SlotContext::from_root_port(speed, 1);
+            let dwords: [u32; 8] = ctx.into();
+            assert_eq!(dwords[0].get_bits(20..=23), psiv);
+            assert_eq!(PortSpeed::from_xhci_psiv(psiv as u8), Some(speed));
+        }
+    }
+
+    #[test]
+    fn depth_one_route_string_is_the_hubs_own_port_number() {
+        // A device plugged into port 3 of a hub that's itself on a root port.
+        let parent = HubAttachment { hub_slot: 2, hub_port: 3, hub_route_string: 0, tier: 0 };
+        let ctx = SlotContext::from_hub_port(PortSpeed::Full, 1, parent, None);
+        let dwords: [u32; 8] = ctx.into();
+        assert_eq!(dwords[0].get_bits(0..=19), 3);
+        assert_eq!(dwords[2].get_bits(0..=7), 2);
+        assert_eq!(dwords[2].get_bits(8..=15), 3);
+    }
+
+    #[test]
+    fn depth_two_route_string_appends_the_next_tiers_nibble() {
+        // A second hub plugged into port 3 of the tier-1 hub above (its
+        // own Route String is therefore 3, at tier 0) has its own
+        // children at tier 1: a device on its port 5 appends that port
+        // number as the next nibble.
+        let tier1_hub_route_string = 3;
+        let parent = HubAttachment { hub_slot: 5, hub_port: 5, hub_route_string: tier1_hub_route_string, tier: 1 };
+        let ctx = SlotContext::from_hub_port(PortSpeed::Full, 1, parent, None);
+        let dwords: [u32; 8] = ctx.into();
+        assert_eq!(dwords[0].get_bits(0..=19), 0x53);
+    }
+
+    #[test]
+    fn low_speed_behind_a_high_speed_hub_sets_the_tt_fields() {
+        let parent = HubAttachment { hub_slot: 4, hub_port: 2, hub_route_string: 0, tier: 0 };
+        let tt = TransactionTranslator { multi_tt: true, think_time: 2 };
+        let ctx = SlotContext::from_hub_port(PortSpeed::Low, 1, parent, Some(tt));
+        let dwords: [u32; 8] = ctx.into();
+        assert!(dwords[0].get_bit(25));
+        assert_eq!(dwords[2].get_bits(16..=17), 2);
+    }
+
+    #[test]
+    fn no_tt_leaves_multi_tt_and_think_time_clear() {
+        let parent = HubAttachment { hub_slot: 4, hub_port: 2, hub_route_string: 0, tier: 0 };
+        let ctx = SlotContext::from_hub_port(PortSpeed::High, 1, parent, None);
+        let dwords: [u32; 8] = ctx.into();
+        assert!(!dwords[0].get_bit(25));
+        assert_eq!(dwords[2].get_bits(16..=17), 0);
+    }
+}
@@ -0,0 +1,32 @@
+//! Optional `EnumerationPhase`/`State` transition audit log, compiled
+//! out unless the `xhci-trace` feature is enabled.
+//!
+//! Centralizes the `log::trace!` calls these transitions make in one
+//! place instead of sprinkling a format string at each call site, so
+//! tracing can be added to or removed from a transition by editing
+//! this module rather than hunting down every place that sets a phase
+//! or state.
+//!
+//! There's no `port_phases` map or `Driver`/`Device` in this tree --
+//! [`super::EnumerationPhase`] isn't stored per-port anywhere, and
+//! [`super::host_controller::State`] belongs to the controller as a
+//! whole, not a port or slot -- so neither trace line below can name a
+//! port or slot; they only have the value being transitioned between.
+use super::enumeration::EnumerationPhase;
+use super::host_controller::State;
+
+#[cfg(feature = "xhci-trace")]
+pub(super) fn phase(from: &EnumerationPhase, to: &EnumerationPhase) {
+    log::trace!("EnumerationPhase: {:?} -> {:?}", from, to);
+}
+
+#[cfg(not(feature = "xhci-trace"))]
+pub(super) fn phase(_from: &EnumerationPhase, _to: &EnumerationPhase) {}
+
+#[cfg(feature = "xhci-trace")]
+pub(super) fn state(from: State, to: State) {
+    log::trace!("HostController::State: {:?} -> {:?}", from, to);
+}
+
+#[cfg(not(feature = "xhci-trace"))]
+pub(super) fn state(_from: State, _to: State) {}
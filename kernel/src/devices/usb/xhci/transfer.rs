@@ -0,0 +1,382 @@
+//! Transfer ring TRBs and the doorbell write that tells the xHC to start
+//! processing them (xHCI spec §4.9, §4.7). There's no `Driver` or
+//! `issuers` map in this tree yet, and [`super::TransferRing`] only
+//! tracks enqueue state (see its module doc) -- `interrupt_in`/
+//! `interrupt_out` are nowhere to be found, let alone implemented -- so
+//! this only covers the TRB/doorbell encoding, the same scope
+//! [`super::command`] takes for command TRBs.
+use core::fmt;
+
+use bit_field::BitField;
+
+use super::endpoint::DeviceContextIndex;
+
+/// TRB Type field values this module encodes (xHCI spec Table 6-86).
+const TRB_TYPE_NORMAL: u32 = 1;
+
+/// An xHCI Normal TRB (xHCI spec §6.4.1.1): transfers `length` bytes
+/// to/from `buffer_ptr` on whichever transfer ring it's enqueued onto.
+/// This is the only TRB type an interrupt IN/OUT transfer needs -- it
+/// has no Setup Stage, just a data buffer. A Transfer Descriptor longer
+/// than one TRB can carry (see [`TransferDescriptor`]) chains several of
+/// these together via `chain`/`td_size`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NormalTrb {
+    pub buffer_ptr: u64,
+    pub length: u32,
+    /// Whether the xHC should post a Transfer Event on completion. Only
+    /// the last TRB of a Transfer Descriptor wants this set -- an
+    /// interrupt pipe's single-TRB TD always does, since it has no
+    /// other TRB to defer to.
+    pub interrupt_on_completion: bool,
+    /// Whether another TRB of the same Transfer Descriptor follows this
+    /// one (xHCI spec §4.11.2.4) -- set on every TRB but the last when a
+    /// transfer is split across more than one.
+    pub chain: bool,
+    /// TD Size (Status dword bits 17..=21): the number of max-packets
+    /// still remaining in the TD after this TRB completes, saturated at
+    /// its 5-bit field's max of 31 (xHCI spec §4.11.2.4). Zero for a
+    /// single-TRB TD, since nothing remains after it.
+    pub td_size: u8,
+}
+
+/// A transfer TRB's four dwords: `[ptr_lo, ptr_hi, status, control]`.
+/// As with [`super::command::EvaluateContextCommand`], the cycle bit
+/// (control dword, bit 0) is left clear for the transfer ring to set
+/// when it actually writes this into a ring slot.
+impl From<NormalTrb> for [u32; 4] {
+    fn from(trb: NormalTrb) -> Self {
+        let mut status = 0u32;
+        status.set_bits(0..=16, trb.length.get_bits(0..=16));
+        status.set_bits(17..=21, (trb.td_size & 0x1f) as u32);
+
+        let mut control = 0u32;
+        control.set_bits(10..=15, TRB_TYPE_NORMAL);
+        control.set_bit(4, trb.chain);
+        control.set_bit(5, trb.interrupt_on_completion);
+
+        [
+            trb.buffer_ptr.get_bits(0..=31) as u32,
+            trb.buffer_ptr.get_bits(32..=63) as u32,
+            status,
+            control,
+        ]
+    }
+}
+
+impl NormalTrb {
+    /// Builds the Normal TRB for an interrupt IN transfer into `buf`.
+    /// An interrupt pipe's max packet size is always small enough that
+    /// this single TRB is its own whole Transfer Descriptor, so `chain`
+    /// is clear and `td_size` is 0.
+    ///
+    /// `buf` must be `'static`: once this TRB is enqueued and the
+    /// doorbell rung, the xHC holds and writes through `buf`'s physical
+    /// address asynchronously until it posts a completion event, so the
+    /// buffer can't be a short-lived stack slice the caller's frame
+    /// might drop or move before then -- the same requirement
+    /// `Device::control_transfer` would document on its own buffers, if
+    /// this tree had a `Device` to host it; `'static` is the closest
+    /// enforcement available without a heap/`Pin<Box<_>>` to allocate
+    /// and pin one (there's no global allocator in this kernel yet).
+    pub fn for_interrupt_buffer(buf: &'static mut [u8]) -> Self {
+        Self {
+            buffer_ptr: buf.as_mut_ptr() as u64,
+            length: buf.len() as u32,
+            interrupt_on_completion: true,
+            chain: false,
+            td_size: 0,
+        }
+    }
+}
+
+/// The most TRBs a single [`TransferDescriptor::for_buffer`] call will
+/// split a transfer into -- 64 KiB (the most one TRB's 17-bit length
+/// field can carry) per TRB, so `N * 64 KiB` is the largest transfer
+/// `TransferDescriptor<N>` can hold. Named here so callers sizing one
+/// don't have to re-derive the 64 KiB-per-TRB limit themselves.
+pub const MAX_TRB_TRANSFER_LEN: u32 = 64 * 1024;
+
+/// Why [`TransferDescriptor::for_buffer`] couldn't build a TD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferDescriptorError {
+    /// A zero-length transfer has no TRB to carry it.
+    EmptyBuffer,
+    /// `max_packet_size` was 0, making "packets remaining" undefined.
+    ZeroMaxPacketSize,
+    /// The buffer needs more than `N` TRBs (each up to
+    /// [`MAX_TRB_TRANSFER_LEN`]) to carry -- this is the ring-full-style
+    /// atomicity check: a TD that doesn't fit is rejected whole, not
+    /// partially built, so a caller never enqueues half a TD onto the
+    /// ring before discovering the rest doesn't fit.
+    TooManyTrbs,
+}
+
+impl fmt::Display for TransferDescriptorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyBuffer => write!(f, "cannot build a transfer descriptor for a zero-length buffer"),
+            Self::ZeroMaxPacketSize => {
+                write!(f, "max_packet_size is 0, so packets-remaining per TRB is undefined")
+            }
+            Self::TooManyTrbs => write!(
+                f,
+                "buffer needs more Normal TRBs ({} B each) than this transfer descriptor can hold",
+                MAX_TRB_TRANSFER_LEN,
+            ),
+        }
+    }
+}
+
+/// A Transfer Descriptor (xHCI spec §4.11.2.4): one or more chained
+/// Normal TRBs that together move `total_len` bytes through `buffer_ptr`
+/// -- needed once a transfer is longer than one TRB's 64 KiB limit
+/// (e.g. a bulk mass-storage read). `N` bounds how many TRBs this can
+/// hold without a heap to grow into.
+///
+/// There's no `Driver`, `issuers` map, or transfer ring push in this
+/// tree to enqueue these onto or track for completion yet (see the
+/// module doc) -- this only builds the TRBs themselves, with `chain`,
+/// `interrupt_on_completion`, and `td_size` set correctly on each.
+#[derive(Debug, Clone, Copy)]
+pub struct TransferDescriptor<const N: usize> {
+    trbs: [NormalTrb; N],
+    len: usize,
+}
+
+impl<const N: usize> TransferDescriptor<N> {
+    /// Splits `total_len` bytes starting at `buffer_ptr` into up to `N`
+    /// chained Normal TRBs of at most [`MAX_TRB_TRANSFER_LEN`] each:
+    /// `chain` is set on every TRB but the last, `interrupt_on_completion`
+    /// only on the last, and each TRB's `td_size` is the number of
+    /// `max_packet_size`-sized packets remaining after it (xHCI spec
+    /// §4.11.2.4), saturated at 31.
+    ///
+    /// Fails atomically -- building nothing -- if the split needs more
+    /// than `N` TRBs, rather than returning a truncated TD a caller
+    /// might enqueue part of.
+    pub fn for_buffer(buffer_ptr: u64, total_len: u32, max_packet_size: u16) -> Result<Self, TransferDescriptorError> {
+        if total_len == 0 {
+            return Err(TransferDescriptorError::EmptyBuffer);
+        }
+        if max_packet_size == 0 {
+            return Err(TransferDescriptorError::ZeroMaxPacketSize);
+        }
+
+        let trb_count = total_len.div_ceil(MAX_TRB_TRANSFER_LEN) as usize;
+        if trb_count > N {
+            return Err(TransferDescriptorError::TooManyTrbs);
+        }
+
+        let placeholder = NormalTrb {
+            buffer_ptr: 0,
+            length: 0,
+            interrupt_on_completion: false,
+            chain: false,
+            td_size: 0,
+        };
+        let mut trbs = [placeholder; N];
+
+        let mut offset: u32 = 0;
+        for (i, trb) in trbs.iter_mut().take(trb_count).enumerate() {
+            let length = (total_len - offset).min(MAX_TRB_TRANSFER_LEN);
+            let is_last = i + 1 == trb_count;
+            let remaining_after = total_len - offset - length;
+            let td_size = remaining_after.div_ceil(max_packet_size as u32).min(31) as u8;
+
+            *trb = NormalTrb {
+                buffer_ptr: buffer_ptr + offset as u64,
+                length,
+                interrupt_on_completion: is_last,
+                chain: !is_last,
+                td_size,
+            };
+            offset += length;
+        }
+
+        Ok(Self { trbs, len: trb_count })
+    }
+
+    /// The TD's TRBs in the order they must be enqueued.
+    pub fn trbs(&self) -> &[NormalTrb] {
+        &self.trbs[..self.len]
+    }
+}
+
+/// The value to write to the Doorbell Register array's entry for a
+/// device's Slot ID to kick the xHC into processing a transfer ring
+/// (xHCI spec §5.6): DB Target is the endpoint's DCI, DB Stream ID is 0
+/// since this driver doesn't use Stream Arrays.
+pub fn doorbell_value(dci: DeviceContextIndex) -> u32 {
+    let mut value = 0u32;
+    value.set_bits(0..=7, dci.raw() as u32);
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_trb(interrupt_on_completion: bool) -> NormalTrb {
+        NormalTrb {
+            buffer_ptr: 0x1122_3344_5566_7788,
+            length: 8,
+            interrupt_on_completion,
+            chain: false,
+            td_size: 0,
+        }
+    }
+
+    #[test]
+    fn encodes_buffer_pointer_and_length() {
+        let raw: [u32; 4] = sample_trb(true).into();
+        assert_eq!(raw[0], 0x5566_7788);
+        assert_eq!(raw[1], 0x1122_3344);
+        assert_eq!(raw[2].get_bits(0..=16), 8);
+    }
+
+    #[test]
+    fn sets_trb_type_and_interrupt_on_completion() {
+        let raw: [u32; 4] = sample_trb(true).into();
+        assert_eq!(raw[3].get_bits(10..=15), TRB_TYPE_NORMAL);
+        assert!(raw[3].get_bit(5));
+    }
+
+    #[test]
+    fn interrupt_on_completion_can_be_left_unset() {
+        let raw: [u32; 4] = sample_trb(false).into();
+        assert!(!raw[3].get_bit(5));
+    }
+
+    #[test]
+    fn encodes_chain_bit_and_td_size() {
+        let trb = NormalTrb {
+            buffer_ptr: 0,
+            length: 8,
+            interrupt_on_completion: false,
+            chain: true,
+            td_size: 5,
+        };
+        let raw: [u32; 4] = trb.into();
+        assert!(raw[3].get_bit(4));
+        assert_eq!(raw[2].get_bits(17..=21), 5);
+    }
+
+    #[test]
+    fn td_size_is_masked_to_its_5_bit_field() {
+        let trb = NormalTrb {
+            buffer_ptr: 0,
+            length: 8,
+            interrupt_on_completion: false,
+            chain: false,
+            td_size: 0xff,
+        };
+        let raw: [u32; 4] = trb.into();
+        assert_eq!(raw[2].get_bits(17..=21), 0x1f);
+    }
+
+    #[test]
+    fn doorbell_targets_the_endpoints_dci_with_no_stream() {
+        let dci = DeviceContextIndex::try_from(3).unwrap();
+        let db = doorbell_value(dci);
+        assert_eq!(db.get_bits(0..=7), 3);
+        assert_eq!(db.get_bits(16..=31), 0);
+    }
+
+    #[test]
+    fn for_interrupt_buffer_derives_pointer_and_length_and_sets_ioc() {
+        static mut BUF: [u8; 8] = [0; 8];
+        let ptr = core::ptr::addr_of_mut!(BUF);
+        let trb = unsafe { NormalTrb::for_interrupt_buffer(&mut *ptr) };
+        assert_eq!(trb.buffer_ptr, ptr as u64);
+        assert_eq!(trb.length, 8);
+        assert!(trb.interrupt_on_completion);
+        assert!(!trb.chain);
+        assert_eq!(trb.td_size, 0);
+    }
+
+    #[test]
+    fn one_byte_transfer_is_a_single_unchained_trb() {
+        let td: TransferDescriptor<4> = TransferDescriptor::for_buffer(0x1000, 1, 512).unwrap();
+        let trbs = td.trbs();
+        assert_eq!(trbs.len(), 1);
+        assert_eq!(trbs[0].buffer_ptr, 0x1000);
+        assert_eq!(trbs[0].length, 1);
+        assert!(!trbs[0].chain);
+        assert!(trbs[0].interrupt_on_completion);
+        assert_eq!(trbs[0].td_size, 0);
+    }
+
+    #[test]
+    fn exactly_64kib_still_fits_in_one_trb() {
+        let td: TransferDescriptor<4> = TransferDescriptor::for_buffer(0x1000, MAX_TRB_TRANSFER_LEN, 512).unwrap();
+        let trbs = td.trbs();
+        assert_eq!(trbs.len(), 1);
+        assert_eq!(trbs[0].length, MAX_TRB_TRANSFER_LEN);
+        assert!(!trbs[0].chain);
+        assert!(trbs[0].interrupt_on_completion);
+    }
+
+    #[test]
+    fn two_hundred_kib_splits_into_chained_trbs_with_correct_td_size_and_ioc() {
+        let total_len = 200 * 1024;
+        let max_packet_size = 512u16;
+        let td: TransferDescriptor<8> =
+            TransferDescriptor::for_buffer(0x2000, total_len, max_packet_size).unwrap();
+        let trbs = td.trbs();
+
+        // 200 KiB needs ceil(200*1024 / 65536) = 4 TRBs of 64, 64, 64, 8 KiB.
+        assert_eq!(trbs.len(), 4);
+        let expected_lengths = [MAX_TRB_TRANSFER_LEN, MAX_TRB_TRANSFER_LEN, MAX_TRB_TRANSFER_LEN, 8 * 1024];
+        let mut offset = 0u64;
+        let mut remaining = total_len;
+        for (i, (trb, &length)) in trbs.iter().zip(expected_lengths.iter()).enumerate() {
+            let is_last = i + 1 == trbs.len();
+            assert_eq!(trb.length, length);
+            assert_eq!(trb.buffer_ptr, 0x2000 + offset);
+            assert_eq!(trb.chain, !is_last);
+            assert_eq!(trb.interrupt_on_completion, is_last);
+
+            remaining -= length;
+            let expected_td_size = remaining.div_ceil(max_packet_size as u32).min(31) as u8;
+            assert_eq!(trb.td_size, expected_td_size);
+            offset += length as u64;
+        }
+        assert_eq!(trbs.last().unwrap().td_size, 0);
+    }
+
+    #[test]
+    fn a_td_that_needs_more_trbs_than_the_capacity_fails_atomically() {
+        let result: Result<TransferDescriptor<2>, _> =
+            TransferDescriptor::for_buffer(0x1000, 200 * 1024, 512);
+        assert_eq!(result.unwrap_err(), TransferDescriptorError::TooManyTrbs);
+    }
+
+    #[test]
+    fn rejects_an_empty_buffer() {
+        let result: Result<TransferDescriptor<4>, _> = TransferDescriptor::for_buffer(0x1000, 0, 512);
+        assert_eq!(result.unwrap_err(), TransferDescriptorError::EmptyBuffer);
+    }
+
+    #[test]
+    fn rejects_a_zero_max_packet_size() {
+        let result: Result<TransferDescriptor<4>, _> = TransferDescriptor::for_buffer(0x1000, 8, 0);
+        assert_eq!(result.unwrap_err(), TransferDescriptorError::ZeroMaxPacketSize);
+    }
+
+    #[test]
+    fn display_gives_each_variant_a_human_message() {
+        assert_eq!(
+            format!("{}", TransferDescriptorError::EmptyBuffer),
+            "cannot build a transfer descriptor for a zero-length buffer"
+        );
+        assert_eq!(
+            format!("{}", TransferDescriptorError::ZeroMaxPacketSize),
+            "max_packet_size is 0, so packets-remaining per TRB is undefined"
+        );
+        assert_eq!(
+            format!("{}", TransferDescriptorError::TooManyTrbs),
+            "buffer needs more Normal TRBs (65536 B each) than this transfer descriptor can hold"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+#![allow(dead_code)]
+//! A fixed-size array accessed through `read_volatile`/`write_volatile`,
+//! for memory-mapped tables indexed by ids that come from hardware
+//! (a port or slot number reported by a controller) rather than from
+//! code that already knows they're in range. `Index`/`IndexMut` panic
+//! on an out-of-range id same as a plain array; `try_read_volatile_at`/
+//! `try_write_volatile_at` turn that into an `Option` for callers on a
+//! hot path who'd rather report an error than trust the hardware.
+//!
+//! [`VolatileArray`] is generic over an access mode ([`ReadOnly`],
+//! [`WriteOnly`] or [`ReadWrite`], the default) so a register that must
+//! never be read back (e.g. write-1-to-clear) or one that's read-only
+//! status can't accidentally gain the other half of the API -- the
+//! wrong method call fails to compile rather than misbehaving on real
+//! hardware.
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut};
+use core::ptr;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Which of read/write access a [`VolatileArray`] exposes. Sealed: the
+/// only implementors are [`ReadOnly`], [`WriteOnly`] and [`ReadWrite`].
+pub trait AccessMode: sealed::Sealed {}
+
+/// Selects [`VolatileArray::try_read_volatile_at`]/`Index` being available.
+pub trait Readable: AccessMode {}
+
+/// Selects [`VolatileArray::try_write_volatile_at`]/`IndexMut` being available.
+pub trait Writable: AccessMode {}
+
+/// A register that must only ever be read, e.g. a status register whose
+/// bits are cleared by hardware, not by software writing back to it.
+#[derive(Debug)]
+pub struct ReadOnly;
+
+/// A register that must only ever be written, e.g. write-1-to-clear
+/// interrupt status or a command register with no meaningful read value.
+#[derive(Debug)]
+pub struct WriteOnly;
+
+/// The default: both reads and writes are allowed, same as a plain array.
+#[derive(Debug)]
+pub struct ReadWrite;
+
+impl sealed::Sealed for ReadOnly {}
+impl sealed::Sealed for WriteOnly {}
+impl sealed::Sealed for ReadWrite {}
+impl AccessMode for ReadOnly {}
+impl AccessMode for WriteOnly {}
+impl AccessMode for ReadWrite {}
+impl Readable for ReadOnly {}
+impl Readable for ReadWrite {}
+impl Writable for WriteOnly {}
+impl Writable for ReadWrite {}
+
+#[derive(Debug)]
+pub struct VolatileArray<T, const N: usize, A: AccessMode = ReadWrite>([T; N], PhantomData<A>);
+
+impl<T: Copy + Default, const N: usize, A: AccessMode> Default for VolatileArray<T, N, A> {
+    fn default() -> Self {
+        Self([T::default(); N], PhantomData)
+    }
+}
+
+impl<T: Copy, const N: usize, A: AccessMode> VolatileArray<T, N, A> {
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+}
+
+impl<T: Copy, const N: usize, A: Readable> VolatileArray<T, N, A> {
+    /// Equivalent to `self[i]`, but `None` instead of a panic when `i`
+    /// is out of range.
+    pub fn try_read_volatile_at(&self, i: usize) -> Option<T> {
+        if i >= N {
+            return None;
+        }
+        Some(unsafe { ptr::read_volatile(&self.0[i]) })
+    }
+}
+
+impl<T: Copy, const N: usize, A: Writable> VolatileArray<T, N, A> {
+    /// Equivalent to `self[i] = value`, but `None` instead of a panic
+    /// when `i` is out of range.
+    pub fn try_write_volatile_at(&mut self, i: usize, value: T) -> Option<()> {
+        if i >= N {
+            return None;
+        }
+        unsafe { ptr::write_volatile(&mut self.0[i], value) };
+        Some(())
+    }
+}
+
+impl<T: Copy, const N: usize, A: Readable> Index<usize> for VolatileArray<T, N, A> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        &self.0[i]
+    }
+}
+
+// `IndexMut: Index` is a supertrait bound in `core`, so a write-only
+// array (no `Index`, by design) can't implement `IndexMut` either --
+// `arr[i] = v` sugar is only available where both halves are allowed
+// (i.e. `ReadWrite`); `WriteOnly` still has `try_write_volatile_at`.
+impl<T: Copy, const N: usize, A: Readable + Writable> IndexMut<usize> for VolatileArray<T, N, A> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        &mut self.0[i]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_read_out_of_range_is_none_instead_of_a_panic() {
+        let arr: VolatileArray<u8, 4> = VolatileArray::default();
+        assert_eq!(arr.try_read_volatile_at(3), Some(0));
+        assert_eq!(arr.try_read_volatile_at(4), None);
+    }
+
+    #[test]
+    fn try_write_round_trips_through_index() {
+        let mut arr: VolatileArray<u32, 4> = VolatileArray::default();
+        assert_eq!(arr.try_write_volatile_at(2, 42), Some(()));
+        assert_eq!(arr[2], 42);
+        assert_eq!(arr.try_write_volatile_at(4, 42), None);
+    }
+
+    #[test]
+    fn read_only_array_supports_reads() {
+        let arr: VolatileArray<u16, 4, ReadOnly> = VolatileArray::default();
+        assert_eq!(arr.try_read_volatile_at(0), Some(0));
+        assert_eq!(arr[1], 0);
+    }
+
+    #[test]
+    fn write_only_array_supports_writes() {
+        let mut arr: VolatileArray<u16, 4, WriteOnly> = VolatileArray::default();
+        assert_eq!(arr.try_write_volatile_at(0, 7), Some(()));
+        assert_eq!(arr.try_write_volatile_at(1, 9), Some(()));
+    }
+
+    // `arr.try_read_volatile_at(..)`/`arr[..]` on a `WriteOnly` array, or
+    // `try_write_volatile_at`/`arr[..] = ..` on a `ReadOnly` one, must be
+    // rejected at compile time -- that's the whole point -- so there's no
+    // runtime test for it here.
+}
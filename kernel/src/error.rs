@@ -0,0 +1,43 @@
+//! A single error type for `main.rs`'s top-level helpers, so code that
+//! touches more than one subsystem (e.g. [`crate::start_xhc`], which
+//! needs both PCI and USB) can return one `Result` instead of
+//! `.expect()`ing past whichever subsystem's own error enum it hit.
+use core::fmt;
+
+use derive_more::From;
+
+use crate::devices::pci;
+use crate::devices::usb::config_desc_reader;
+use crate::devices::usb::xhci;
+
+#[derive(Debug, From)]
+pub enum KernelError {
+    Pci(pci::Error),
+    Usb(config_desc_reader::Error),
+    /// A transfer failed with a non-success completion code. Carries
+    /// the slot, endpoint, and code [`xhci::check`] saw, instead of
+    /// just a bare "something failed" -- see
+    /// [`xhci::TransferFailed`]'s module doc for why there's no
+    /// dispatcher in this tree yet to actually produce one.
+    UsbTransfer(xhci::TransferFailed),
+    Memory,
+    Graphics,
+    /// A human-readable note about what the kernel was trying to do,
+    /// for call sites with no subsystem error to wrap -- e.g. "no
+    /// usable BAR0" isn't a [`pci::Error`] variant, just a decision
+    /// made about one.
+    Context(&'static str),
+}
+
+impl fmt::Display for KernelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KernelError::Pci(e) => write!(f, "PCI error: {:?}", e),
+            KernelError::Usb(e) => write!(f, "USB error: {}", e),
+            KernelError::UsbTransfer(e) => write!(f, "USB transfer error: {}", e),
+            KernelError::Memory => write!(f, "memory error"),
+            KernelError::Graphics => write!(f, "graphics error"),
+            KernelError::Context(msg) => write!(f, "{}", msg),
+        }
+    }
+}
@@ -1,40 +1,86 @@
 use super::font::{FONT_H as FH, FONT_W as FW};
+use super::status_bar;
 use super::Position;
-use super::{Color, FrameBufDrawer, FrameBuffer};
+use super::{Color, Draw, FrameBufDrawer, FrameBuffer, Offset, Rect};
 
 const COLS: usize = 95;
 const ROWS: usize = 30;
 const X_PAD: usize = 10;
-const Y_PAD: usize = 10;
+/// Leaves room for the status bar above the console so text never
+/// overlaps it.
+const Y_PAD: usize = status_bar::HEIGHT + 10;
+
+/// How many lines of scrollback to keep. Once full, the oldest line is
+/// overwritten by the newest, same as the visible window always was
+/// before scrollback existed.
+const HISTORY_LINES: usize = 500;
+
+/// The foreground color `ESC [ 0 m` (SGR reset) restores.
+const DEFAULT_OUTPUT_COLOR: Color = Color::WHITE;
+
+/// How long the cursor stays in each half of its blink cycle, once
+/// something drives [`Console::tick_cursor_blink`] on a real clock.
+const CURSOR_BLINK_INTERVAL_MS: u64 = 500;
+
+/// One character cell of the scrollback buffer. Stores its own color so
+/// scrolling can repaint colored log lines correctly instead of
+/// repainting everything in whatever `output_color` happens to be
+/// current at scroll time.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Cell {
+    ch: char,
+    color: Color,
+}
 
 #[derive(Debug)]
-pub struct Console<'fb> {
-    pub(crate) drawer: FrameBufDrawer<'fb>,
-    // In Rust, char uses 32 bits each and its memory consuming than char array in C.
-    // This implementation would be replaced in future, but now leave as this for simplicity.
-    buf: [[char; COLS]; ROWS],
+pub struct Console<D> {
+    pub(crate) drawer: D,
+    /// Ring buffer of every line written so far, up to [`HISTORY_LINES`].
+    /// Indexed by `line % HISTORY_LINES`; `cur_line` says how many lines
+    /// (and therefore how much of the ring has been overwritten) there
+    /// have ever been.
+    history: [[Cell; COLS]; HISTORY_LINES],
+    cur_line: usize,
     x: usize,
-    y: usize,
+    /// Lines scrolled back from the bottom (`cur_line`). Zero means the
+    /// viewport tracks the cursor live; nonzero means the user scrolled
+    /// up and new output shouldn't yank the viewport back down.
+    scroll_offset: usize,
     background_color: Color,
     output_color: Color,
+    ansi: AnsiParser,
+    /// Whether the cursor cell is currently painted inverted. Always
+    /// `true` while `cursor_blink_enabled` is `false`, so disabling
+    /// blink leaves a solid, non-flickering cursor.
+    cursor_visible: bool,
+    cursor_blink_enabled: bool,
+    /// The `now_ms` at which [`Self::tick_cursor_blink`] should next
+    /// flip [`Self::cursor_visible`]. Advanced by whatever the caller
+    /// passes to that method -- see its doc for why there's no clock in
+    /// here to read the time from instead.
+    next_blink_at_ms: u64,
 }
 
-impl<'fb> Console<'fb> {
-    pub const fn from_drawer(drawer: FrameBufDrawer<'fb>) -> Self {
+impl<D: Draw> Console<D> {
+    pub const fn from_drawer(drawer: D) -> Self {
         Self {
             drawer,
-            buf: [['\0'; COLS]; ROWS],
+            history: [[Cell {
+                ch: '\0',
+                color: Color::BLACK,
+            }; COLS]; HISTORY_LINES],
+            cur_line: 0,
             x: 0,
-            y: 0,
+            scroll_offset: 0,
             background_color: Color::WHITE,
             output_color: Color::BLACK,
+            ansi: AnsiParser::new(),
+            cursor_visible: true,
+            cursor_blink_enabled: true,
+            next_blink_at_ms: CURSOR_BLINK_INTERVAL_MS,
         }
     }
 
-    pub fn from_frame_buffer(fb: &'fb mut FrameBuffer) -> Self {
-        Self::from_drawer(fb.drawer())
-    }
-
     pub fn fill_screen(&mut self) {
         self.drawer.draw_all(self.background_color);
     }
@@ -46,50 +92,410 @@ impl<'fb> Console<'fb> {
     pub fn set_output_color(&mut self, color: Color) {
         self.output_color = color;
     }
+
+    pub fn output_color(&self) -> Color {
+        self.output_color
+    }
+
+    /// Run `f` with the output color temporarily set to `color`,
+    /// restoring the previous color afterward. Backs
+    /// [`crate::kprint_colored`]/[`crate::kprintln_colored`] so a
+    /// one-off colored line doesn't leak its color into the output that
+    /// follows it.
+    pub fn with_output_color<R>(&mut self, color: Color, f: impl FnOnce(&mut Self) -> R) -> R {
+        let previous = self.output_color;
+        self.output_color = color;
+        let result = f(self);
+        self.output_color = previous;
+        result
+    }
+
+    /// Scroll `n` lines further back into history, clamped to how much
+    /// is actually available. For PageUp/a mouse-wheel-up event.
+    pub fn scroll_up(&mut self, n: usize) {
+        let target = (self.scroll_offset + n).min(self.max_scroll_offset());
+        self.set_scroll_offset(target);
+    }
+
+    /// Scroll `n` lines toward the bottom. For PageDown/a
+    /// mouse-wheel-down event.
+    pub fn scroll_down(&mut self, n: usize) {
+        let target = self.scroll_offset.saturating_sub(n);
+        self.set_scroll_offset(target);
+    }
+
+    /// Jump back to tracking the cursor live.
+    pub fn scroll_to_bottom(&mut self) {
+        self.set_scroll_offset(0);
+    }
+
+    /// Whether the viewport is scrolled away from the live cursor.
+    pub fn is_scrolled_back(&self) -> bool {
+        self.scroll_offset != 0
+    }
+
+    /// Enable or disable cursor blinking. Disabling leaves the cursor
+    /// painted solid instead of wherever it happened to be mid-cycle,
+    /// for contexts (e.g. a screenshot) that want it always visible.
+    pub fn set_cursor_blink(&mut self, enabled: bool) {
+        self.cursor_blink_enabled = enabled;
+        if !enabled && !self.cursor_visible {
+            self.cursor_visible = true;
+            self.paint_cursor(true);
+        }
+    }
+
+    /// Advance the blink clock to `now_ms`, flipping cursor visibility
+    /// once [`CURSOR_BLINK_INTERVAL_MS`] has elapsed since the last
+    /// flip.
+    ///
+    /// There's no `TimerTick` message, timer/PIT driver, or keyboard
+    /// driver in this tree yet (see [`crate::input`]'s module doc), so
+    /// -- same as [`crate::input::Typematic`] -- this takes the
+    /// timestamp as an explicit parameter instead of reading a clock or
+    /// reacting to a message, ready for whichever driver lands first to
+    /// call it on a real cadence.
+    pub fn tick_cursor_blink(&mut self, now_ms: u64) {
+        if !self.cursor_blink_enabled || now_ms < self.next_blink_at_ms {
+            return;
+        }
+        self.next_blink_at_ms = now_ms + CURSOR_BLINK_INTERVAL_MS;
+        self.cursor_visible = !self.cursor_visible;
+        self.paint_cursor(self.cursor_visible);
+    }
+
+    /// The cursor's on-screen position, or `None` while it's outside
+    /// the live viewport (scrolled back; see [`Self::is_scrolled_back`]).
+    fn cursor_position(&self) -> Option<Position> {
+        if self.is_scrolled_back() {
+            return None;
+        }
+        let row = self.cur_line - self.viewport_top();
+        Some(font_aligned_position(self.x, row))
+    }
+
+    /// Paint the cursor cell: inverted (background-as-foreground, with
+    /// [`Self::output_color`] standing in for whatever's underneath) if
+    /// `inverted`, or its real contents otherwise. A no-op while
+    /// scrolled back, per [`Self::cursor_position`].
+    fn paint_cursor(&mut self, inverted: bool) {
+        let Some(pos) = self.cursor_position() else {
+            return;
+        };
+        let cell = self.line(self.cur_line)[self.x];
+        let (ch, color) = match cell.ch {
+            '\0' | '\n' => (' ', self.background_color),
+            ch => (ch, cell.color),
+        };
+        let (fg, bg) = if inverted {
+            (self.background_color, self.output_color)
+        } else {
+            (color, self.background_color)
+        };
+        self.drawer.draw_ascii_bg(ch, pos, fg, bg);
+    }
+
+    fn clear(&mut self) {
+        self.drawer.draw_all(self.background_color);
+        self.history = [[Cell::default(); COLS]; HISTORY_LINES];
+        self.cur_line = 0;
+        self.x = 0;
+        self.scroll_offset = 0;
+    }
+
+    fn line(&self, line: usize) -> &[Cell; COLS] {
+        &self.history[line % HISTORY_LINES]
+    }
+
+    fn line_mut(&mut self, line: usize) -> &mut [Cell; COLS] {
+        &mut self.history[line % HISTORY_LINES]
+    }
+
+    /// How many lines are actually sitting in the ring buffer, bounded
+    /// by its capacity even once more than that have ever been written.
+    fn available_lines(&self) -> usize {
+        (self.cur_line + 1).min(HISTORY_LINES)
+    }
+
+    /// The furthest `scroll_offset` can legally reach: enough to put the
+    /// oldest available line at the top of the viewport, no further.
+    fn max_scroll_offset(&self) -> usize {
+        self.available_lines().saturating_sub(ROWS)
+    }
+
+    fn viewport_bottom(&self) -> usize {
+        self.cur_line.saturating_sub(self.scroll_offset)
+    }
+
+    fn viewport_top(&self) -> usize {
+        self.viewport_bottom().saturating_sub(ROWS - 1)
+    }
+
+    /// Redraw console row `row` (0 = top of the viewport) from history.
+    fn redraw_row(&mut self, row: usize) {
+        let cells = *self.line(self.viewport_top() + row);
+        self.drawer.fill_rect(
+            Rect::new(
+                Position::new(X_PAD, Y_PAD + row * FH),
+                Offset::new(COLS * FW, FH),
+            ),
+            self.background_color,
+        );
+        for (col, cell) in cells.iter().enumerate() {
+            if cell.ch == '\0' || cell.ch == '\n' {
+                continue;
+            }
+            self.drawer
+                .draw_ascii(cell.ch, font_aligned_position(col, row), cell.color);
+        }
+    }
+
+    fn redraw_viewport(&mut self) {
+        for row in 0..ROWS {
+            self.redraw_row(row);
+        }
+    }
+
+    /// Move the viewport to `new_offset`, reusing [`Draw::scroll_rows`]
+    /// to shift already-drawn rows instead of redrawing them when the
+    /// old and new viewports overlap by less than a full screen.
+    fn set_scroll_offset(&mut self, new_offset: usize) {
+        if new_offset == self.scroll_offset {
+            return;
+        }
+        let old_bottom = self.viewport_bottom();
+        self.scroll_offset = new_offset;
+        let delta = self.viewport_bottom() as isize - old_bottom as isize;
+        let shift = delta.unsigned_abs();
+
+        let handled = shift > 0
+            && shift < ROWS
+            && if delta > 0 {
+                self.drawer
+                    .scroll_rows(Y_PAD, Y_PAD + shift * FH, (ROWS - shift) * FH)
+            } else {
+                self.drawer
+                    .scroll_rows(Y_PAD + shift * FH, Y_PAD, (ROWS - shift) * FH)
+            };
+
+        if handled {
+            if delta > 0 {
+                (ROWS - shift..ROWS).for_each(|row| self.redraw_row(row));
+            } else {
+                (0..shift).for_each(|row| self.redraw_row(row));
+            }
+        } else {
+            self.redraw_viewport();
+        }
+
+        // redraw_row/redraw_viewport above paint every cell with its
+        // real contents, including the cursor's own cell; repaint it
+        // inverted on top if it should be showing (a no-op via
+        // Self::cursor_position if the new viewport scrolled it out of
+        // view).
+        if self.cursor_visible {
+            self.paint_cursor(true);
+        }
+    }
+
+    /// Store `c` in the scrollback and, if the viewport is tracking the
+    /// cursor live, draw it; a scrolled-back viewport is left alone so
+    /// new output doesn't yank it back to the bottom.
+    fn put_char(&mut self, c: char) {
+        let line = self.cur_line;
+        let x = self.x;
+        self.line_mut(line)[x] = Cell {
+            ch: c,
+            color: self.output_color,
+        };
+
+        if self.scroll_offset == 0 && c != '\n' {
+            let row = line - self.viewport_top();
+            self.drawer.draw_ascii_bg(
+                c,
+                font_aligned_position(self.x, row),
+                self.output_color,
+                self.background_color,
+            );
+        }
+
+        if c == '\n' || self.x == COLS - 1 {
+            self.advance_line();
+        } else {
+            self.x += 1;
+        }
+    }
+
+    /// Move the cursor to a fresh line, shifting the live viewport down
+    /// by one row once there's more history than fits on screen.
+    fn advance_line(&mut self) {
+        self.cur_line += 1;
+        self.x = 0;
+        if self.scroll_offset == 0 && self.cur_line >= ROWS {
+            let handled = self.drawer.scroll_rows(Y_PAD, Y_PAD + FH, (ROWS - 1) * FH);
+            if handled {
+                self.redraw_row(ROWS - 1);
+            } else {
+                self.redraw_viewport();
+            }
+        }
+    }
+}
+
+impl<'fb> Console<FrameBufDrawer<'fb>> {
+    pub fn from_frame_buffer(fb: &'fb mut FrameBuffer) -> Self {
+        Self::from_drawer(fb.drawer())
+    }
 }
 
-impl<'fb> core::fmt::Write for Console<'fb> {
+impl<D: Draw> core::fmt::Write for Console<D> {
     fn write_str(&mut self, s: &str) -> core::fmt::Result {
-        let mut x = self.x;
-        let mut y = self.y;
+        self.paint_cursor(false);
         for c in s.chars() {
-            // Scroll and repaint before putting char,
-            // if cursor reaches the bottom of the console.
-            if y == ROWS {
-                self.drawer.draw_all(self.background_color); // TODO: fill only area for console.
-                for r in 0..ROWS - 1 {
-                    self.buf[r] = self.buf[r + 1];
-                    for (c, &ch) in self.buf[r].iter().enumerate() {
-                        if ch == '\n' {
-                            break;
-                        }
-                        let pos = font_aligned_position(c, r);
-                        self.drawer.draw_ascii(ch, pos, self.output_color);
-                    }
+            let c = match self.ansi.feed(c) {
+                AnsiAction::Print(c) => c,
+                AnsiAction::SetColor(color) => {
+                    self.output_color = color;
+                    continue;
                 }
-                self.buf[ROWS - 1].fill('\0');
-                y -= 1;
-            }
+                AnsiAction::ClearScreen => {
+                    self.clear();
+                    continue;
+                }
+                AnsiAction::Pending => continue,
+            };
+            self.put_char(c);
+        }
+        if self.cursor_visible {
+            self.paint_cursor(true);
+        }
+        Ok(())
+    }
+}
 
-            self.buf[y][x] = c; // Note that 'y' selects row and 'x' selects column.
-            if c == '\n' {
-                x = 0;
-                y += 1;
-            } else {
-                let pos = font_aligned_position(x, y);
-                self.drawer.draw_ascii(c, pos, self.output_color);
-                if x == COLS - 1 {
-                    x = 0;
-                    y += 1;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnsiState {
+    Ground,
+    Escape,
+    Csi,
+}
+
+/// What [`Console::write_str`] should do with the byte just fed to the
+/// parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnsiAction {
+    /// Not part of any escape sequence; draw it as-is.
+    Print(char),
+    /// `ESC [ <params> m` resolved to a foreground color change.
+    SetColor(Color),
+    /// `ESC [ 2 J`.
+    ClearScreen,
+    /// Consumed as part of a sequence still in progress, or a completed
+    /// sequence this parser doesn't recognize.
+    Pending,
+}
+
+/// Recognizes a small subset of ANSI CSI sequences so the logger can
+/// color error/warning lines without inventing a private protocol:
+/// `ESC [ <params> m` for SGR foreground colors (30-37, 90-97, and 0 to
+/// reset) and `ESC [ 2 J` to clear the screen. Any other CSI sequence is
+/// still consumed up to its final byte so escape bytes never leak
+/// through to [`Draw::draw_ascii`], it just has no effect.
+#[derive(Debug)]
+struct AnsiParser {
+    state: AnsiState,
+    params: [u16; 4],
+    param_count: usize,
+}
+
+impl AnsiParser {
+    const fn new() -> Self {
+        Self {
+            state: AnsiState::Ground,
+            params: [0; 4],
+            param_count: 0,
+        }
+    }
+
+    fn feed(&mut self, c: char) -> AnsiAction {
+        match self.state {
+            AnsiState::Ground => {
+                if c == '\x1b' {
+                    self.state = AnsiState::Escape;
+                    AnsiAction::Pending
+                } else {
+                    AnsiAction::Print(c)
+                }
+            }
+            AnsiState::Escape => {
+                if c == '[' {
+                    self.state = AnsiState::Csi;
+                    self.params = [0; 4];
+                    self.param_count = 0;
                 } else {
-                    x += 1;
+                    // Not a sequence we understand; drop it and resume.
+                    self.state = AnsiState::Ground;
                 }
+                AnsiAction::Pending
             }
+            AnsiState::Csi => self.feed_csi(c),
         }
-        self.x = x;
-        self.y = y;
-        Ok(())
     }
+
+    fn feed_csi(&mut self, c: char) -> AnsiAction {
+        match c {
+            '0'..='9' => {
+                self.param_count = self.param_count.max(1);
+                if let Some(param) = self.params.get_mut(self.param_count - 1) {
+                    let digit = c as u16 - '0' as u16;
+                    *param = param.saturating_mul(10).saturating_add(digit);
+                }
+                AnsiAction::Pending
+            }
+            ';' => {
+                self.param_count += 1;
+                AnsiAction::Pending
+            }
+            final_byte => {
+                let params = &self.params[..self.param_count.min(self.params.len())];
+                let action = match final_byte {
+                    'm' => sgr_color(params).map_or(AnsiAction::Pending, AnsiAction::SetColor),
+                    'J' if matches!(params, [2]) => AnsiAction::ClearScreen,
+                    _ => AnsiAction::Pending,
+                };
+                self.state = AnsiState::Ground;
+                action
+            }
+        }
+    }
+}
+
+/// Resolves the SGR foreground-color parameters of `ESC [ <params> m`.
+/// Later params win if more than one foreground color shows up in the
+/// same sequence; unrecognized params (bold, background colors, ...)
+/// are silently ignored rather than rejecting the whole sequence.
+fn sgr_color(params: &[u16]) -> Option<Color> {
+    // An empty parameter list (plain `ESC[m`) means reset, same as `0`.
+    if params.is_empty() {
+        return Some(DEFAULT_OUTPUT_COLOR);
+    }
+
+    let mut color = None;
+    for &p in params {
+        color = match p {
+            0 => Some(DEFAULT_OUTPUT_COLOR),
+            30 | 90 => Some(Color::BLACK),
+            31 | 91 => Some(Color::RED),
+            32 | 92 => Some(Color::GREEN),
+            33 | 93 => Some(Color::YELLOW),
+            34 | 94 => Some(Color::BLUE),
+            35 | 95 => Some(Color::MAGENTA),
+            36 | 96 => Some(Color::CYAN),
+            37 | 97 => Some(Color::WHITE),
+            _ => color,
+        };
+    }
+    color
 }
 
 // grid counts to potision on frame buffer
@@ -98,14 +504,259 @@ fn font_aligned_position(x: usize, y: usize) -> Position {
     (X_PAD + x * FW, Y_PAD + y * FH).into()
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Glyph {
+        c: char,
+        color: Color,
+    }
+
+    /// Records which characters/colors reached [`Draw::draw_ascii`]
+    /// instead of rendering them, so tests can check that escape bytes
+    /// never make it past [`AnsiParser`].
+    struct RecordingDrawer {
+        drawn: [Option<Glyph>; 16],
+        count: usize,
+    }
+
+    impl RecordingDrawer {
+        fn new() -> Self {
+            Self {
+                drawn: [None; 16],
+                count: 0,
+            }
+        }
+
+        fn recorded(&self) -> &[Option<Glyph>] {
+            &self.drawn[..self.count]
+        }
+    }
+
+    impl Draw for RecordingDrawer {
+        fn draw_pixel(&mut self, _p: Position, _color: Color) {}
+
+        fn draw_ascii(&mut self, c: char, _start: Position, color: Color) {
+            self.drawn[self.count] = Some(Glyph { c, color });
+            self.count += 1;
+        }
+
+        fn draw_ascii_bg(&mut self, c: char, _start: Position, fg: Color, _bg: Color) {
+            self.drawn[self.count] = Some(Glyph { c, color: fg });
+            self.count += 1;
+        }
+    }
+
+    fn glyphs(pairs: &[(char, Color)]) -> [Option<Glyph>; 16] {
+        let mut out = [None; 16];
+        for (i, &(c, color)) in pairs.iter().enumerate() {
+            out[i] = Some(Glyph { c, color });
+        }
+        out
+    }
+
+    #[test]
+    fn escape_sequences_never_reach_the_glyph_renderer() {
+        let mut console = Console::from_drawer(RecordingDrawer::new());
+        write!(console, "\x1b[31mred\x1b[0mplain").unwrap();
+        assert_eq!(
+            console.drawer.recorded(),
+            // A leading and trailing entry bracket the real text: write_str
+            // un-draws the cursor from its old (blank) cell before printing
+            // and redraws it, inverted, at the new one afterward.
+            &glyphs(&[
+                (' ', Color::WHITE),
+                ('r', Color::RED),
+                ('e', Color::RED),
+                ('d', Color::RED),
+                ('p', Color::WHITE),
+                ('l', Color::WHITE),
+                ('a', Color::WHITE),
+                ('i', Color::WHITE),
+                ('n', Color::WHITE),
+                (' ', Color::WHITE),
+            ])[..10]
+        );
+    }
+
+    #[test]
+    fn unrecognized_csi_sequences_are_dropped_silently() {
+        let mut console = Console::from_drawer(RecordingDrawer::new());
+        write!(console, "\x1b[1;4Hvisible").unwrap();
+        assert_eq!(
+            console.drawer.recorded(),
+            &glyphs(&[
+                (' ', Color::WHITE),
+                ('v', Color::BLACK),
+                ('i', Color::BLACK),
+                ('s', Color::BLACK),
+                ('i', Color::BLACK),
+                ('b', Color::BLACK),
+                ('l', Color::BLACK),
+                ('e', Color::BLACK),
+                (' ', Color::WHITE),
+            ])[..9]
+        );
+    }
+
+    #[test]
+    fn bright_and_named_sgr_colors_resolve() {
+        assert_eq!(sgr_color(&[33]), Some(Color::YELLOW));
+        assert_eq!(sgr_color(&[93]), Some(Color::YELLOW));
+        assert_eq!(sgr_color(&[0]), Some(DEFAULT_OUTPUT_COLOR));
+        assert_eq!(sgr_color(&[]), Some(DEFAULT_OUTPUT_COLOR));
+        assert_eq!(sgr_color(&[1]), None);
+    }
+
+    #[test]
+    fn clear_screen_resets_the_scrollback_buffer() {
+        let mut console = Console::from_drawer(RecordingDrawer::new());
+        write!(console, "hello\x1b[2J").unwrap();
+        assert_eq!(console.history[0][0], Cell::default());
+        assert_eq!(console.x, 0);
+        assert_eq!(console.cur_line, 0);
+        assert_eq!(console.scroll_offset, 0);
+    }
+
+    #[test]
+    fn cannot_scroll_before_history_fills_a_viewport() {
+        let mut console = Console::from_drawer(RecordingDrawer::new());
+        write!(console, "a\nb\nc\n").unwrap();
+        console.scroll_up(1000);
+        assert_eq!(console.scroll_offset, 0);
+    }
+
+    #[test]
+    fn scroll_offset_is_clamped_to_available_history() {
+        let mut console = Console::from_drawer(RecordingDrawer::new());
+        // Fast-forward the cursor past a full screen's worth of lines
+        // without actually drawing any of them.
+        console.cur_line = 40;
+
+        console.scroll_up(1000);
+        assert_eq!(console.scroll_offset, 41 - ROWS);
+
+        console.scroll_down(1000);
+        assert_eq!(console.scroll_offset, 0);
+    }
+
+    #[test]
+    fn scroll_offset_is_clamped_to_ring_buffer_capacity() {
+        let mut console = Console::from_drawer(RecordingDrawer::new());
+        console.cur_line = HISTORY_LINES + 100;
+
+        console.scroll_up(usize::MAX);
+        assert_eq!(console.scroll_offset, HISTORY_LINES - ROWS);
+    }
+
+    #[test]
+    fn scrolled_back_output_is_not_drawn_until_caught_up() {
+        let mut console = Console::from_drawer(RecordingDrawer::new());
+        console.cur_line = 40;
+        console.scroll_up(5);
+        assert_eq!(console.drawer.recorded(), &[][..]);
+
+        // While scrolled back, new output lands in history but must not
+        // appear on screen or move the viewport.
+        write!(console, "X").unwrap();
+        assert_eq!(console.scroll_offset, 5);
+        assert_eq!(console.drawer.recorded(), &[][..]);
+
+        // Catching up repaints from history, including what was written
+        // while scrolled back, plus the cursor reappearing now that its
+        // cell is back in view.
+        console.scroll_to_bottom();
+        assert_eq!(
+            console.drawer.recorded(),
+            &[
+                Some(Glyph {
+                    c: 'X',
+                    color: Color::BLACK,
+                }),
+                Some(Glyph {
+                    c: ' ',
+                    color: Color::WHITE,
+                }),
+            ][..]
+        );
+    }
+
+    #[test]
+    fn cursor_is_drawn_inverted_after_a_write() {
+        let mut console = Console::from_drawer(RecordingDrawer::new());
+        write!(console, "a").unwrap();
+        assert_eq!(
+            console.drawer.recorded(),
+            // The (blank) cursor cell un-drawn, 'a' drawn normally, then
+            // the cursor drawn inverted one cell further along, where
+            // nothing has been written yet.
+            &glyphs(&[
+                (' ', Color::WHITE),
+                ('a', Color::BLACK),
+                (' ', Color::WHITE),
+            ])[..3]
+        );
+    }
+
+    #[test]
+    fn cursor_does_not_blink_before_the_interval_elapses() {
+        let mut console = Console::from_drawer(RecordingDrawer::new());
+        write!(console, "a").unwrap();
+        assert!(console.cursor_visible);
+
+        console.tick_cursor_blink(CURSOR_BLINK_INTERVAL_MS - 1);
+        assert!(console.cursor_visible);
+
+        console.tick_cursor_blink(CURSOR_BLINK_INTERVAL_MS);
+        assert!(!console.cursor_visible);
+    }
+
+    #[test]
+    fn disabling_blink_leaves_a_solid_cursor() {
+        let mut console = Console::from_drawer(RecordingDrawer::new());
+        write!(console, "a").unwrap();
+        console.tick_cursor_blink(CURSOR_BLINK_INTERVAL_MS);
+        assert!(!console.cursor_visible);
+
+        console.set_cursor_blink(false);
+        assert!(console.cursor_visible);
+
+        // Ticking further no longer flips it.
+        console.tick_cursor_blink(CURSOR_BLINK_INTERVAL_MS * 10);
+        assert!(console.cursor_visible);
+    }
+
+    #[test]
+    fn cursor_is_not_drawn_while_scrolled_back() {
+        let mut console = Console::from_drawer(RecordingDrawer::new());
+        console.cur_line = 40;
+        console.scroll_up(5);
+        console.tick_cursor_blink(CURSOR_BLINK_INTERVAL_MS);
+        assert_eq!(console.drawer.recorded(), &[][..]);
+    }
+}
+
+/// Writes through the real [`Console`] once `services::init` has set one
+/// up, falling back to [`crate::graphics::emergency`] otherwise (e.g. a
+/// panic before `services::init`, or `services::init` itself panicking
+/// partway through) instead of unwrapping a `None` and hanging silently.
 #[macro_export]
 macro_rules! kprint {
     ($($arg:tt)*) => {{
         use core::fmt::Write as _;
         use crate::services::CONSOLE as _CONSOLE;
         #[allow(unused_unsafe)]
-        let console = unsafe { _CONSOLE.as_mut().unwrap() };
-        write!(console, $($arg)*).expect("printk failed.");
+        match unsafe { _CONSOLE.as_mut() } {
+            Some(console) => write!(console, $($arg)*).expect("printk failed."),
+            None => {
+                if let Some(console) = crate::graphics::emergency::console_mut() {
+                    let _ = write!(console, $($arg)*);
+                }
+            }
+        }
     }};
 }
 
@@ -123,3 +774,35 @@ macro_rules! kprintln {
         kprint!(concat!($fmt, "\n"), $($arg)*);
     }};
 }
+
+/// Like [`kprint`], but writes with `$color` as the output color,
+/// restoring the console's previous output color afterward.
+#[macro_export]
+macro_rules! kprint_colored {
+    ($color:expr, $($arg:tt)*) => {{
+        use core::fmt::Write as _;
+        use crate::services::CONSOLE as _CONSOLE;
+        #[allow(unused_unsafe)]
+        let console = unsafe { _CONSOLE.as_mut().unwrap() };
+        console.with_output_color($color, |console| {
+            write!(console, $($arg)*).expect("printk failed.");
+        });
+    }};
+}
+
+/// Like [`kprintln`], but writes with `$color` as the output color,
+/// restoring the console's previous output color afterward.
+#[macro_export]
+macro_rules! kprintln_colored {
+    ($color:expr) => {{
+        kprint_colored!($color, "\n");
+    }};
+
+    ($color:expr, $fmt:expr) => {{
+        kprint_colored!($color, concat!($fmt, "\n"));
+    }};
+
+    ($color:expr, $fmt:expr, $($arg:tt)*) => {{
+        kprint_colored!($color, concat!($fmt, "\n"), $($arg)*);
+    }};
+}
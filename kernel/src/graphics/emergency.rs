@@ -0,0 +1,133 @@
+//! Text output that works before [`crate::services::init`] has run (or
+//! after something in it went wrong), for the boot window where
+//! [`kprint!`](crate::kprint) would otherwise dereference a `None`
+//! `CONSOLE` and hang silently instead of panicking somewhere visible.
+//!
+//! This deliberately doesn't reuse [`super::Console`]: that type's
+//! scrollback history and layered compositing all assume the rest of
+//! `services::init` (paging, the status bar, ...) already ran. This
+//! writes pixels straight into the raw [`common_data::graphics::FrameBuffer`]
+//! `kernel_main` was handed, with no allocation and no state beyond the
+//! one cursor position.
+use core::fmt::Write;
+
+use common_data::graphics::{FrameBuffer, PixelFormat};
+
+use super::font;
+use super::{Bgr, Color, Draw, MaskPaint, Paint, Position, Rgb};
+
+const X_PAD: usize = 10;
+const Y_PAD: usize = 10;
+
+static mut EMERGENCY_CONSOLE: Option<EmergencyConsole> = None;
+
+/// Makes the emergency console usable, if it isn't already.
+///
+/// Idempotent and cheap to call speculatively (e.g. at the very top of
+/// `kernel_main`, before the raw `fb` pointer has been validated by
+/// anything else): a second call is a no-op, so callers never need to
+/// track whether some earlier call already ran.
+///
+/// # Safety
+/// `fb` must point to a live, correctly laid out [`FrameBuffer`], same
+/// requirement as every other place this kernel dereferences it.
+pub unsafe fn init_once(fb: *const FrameBuffer) {
+    if EMERGENCY_CONSOLE.is_some() {
+        return;
+    }
+    let info = fb.read();
+    EMERGENCY_CONSOLE = Some(EmergencyConsole {
+        base: info.base_addr() as *mut u8,
+        stride: info.stride,
+        resolution: info.resolution,
+        format: info.format,
+        bytes_per_pixel: info.bytes_per_pixel,
+        x: X_PAD,
+        y: Y_PAD,
+    });
+}
+
+/// The emergency console, if [`init_once`] has run.
+pub fn console_mut() -> Option<&'static mut EmergencyConsole> {
+    unsafe { EMERGENCY_CONSOLE.as_mut() }
+}
+
+/// A bare pixel writer: one cursor, no scrollback, no color tracking.
+/// Wraps at the screen edge and simply restarts at the top-left once it
+/// runs off the bottom, since overwriting old emergency output matters
+/// far less than never panicking while trying to draw new output.
+pub struct EmergencyConsole {
+    base: *mut u8,
+    stride: usize,
+    resolution: (usize, usize),
+    format: PixelFormat,
+    bytes_per_pixel: usize,
+    x: usize,
+    y: usize,
+}
+
+impl EmergencyConsole {
+    fn advance(&mut self) {
+        self.x += font::FONT_W;
+        if self.x + font::FONT_W > self.resolution.0 {
+            self.x = X_PAD;
+            self.y += font::FONT_H;
+        }
+        if self.y + font::FONT_H > self.resolution.1 {
+            self.y = Y_PAD;
+        }
+    }
+
+    fn put_char(&mut self, c: char) {
+        match c {
+            '\n' => {
+                self.x = X_PAD;
+                self.y += font::FONT_H;
+                if self.y + font::FONT_H > self.resolution.1 {
+                    self.y = Y_PAD;
+                }
+            }
+            c => {
+                let p = Position::new(self.x, self.y);
+                self.draw_ascii(c, p, Color::WHITE);
+                self.advance();
+            }
+        }
+    }
+}
+
+impl Draw for EmergencyConsole {
+    fn draw_pixel(&mut self, p: Position, color: Color) {
+        if p.x >= self.resolution.0 || p.y >= self.resolution.1 {
+            return;
+        }
+        let i = (self.stride * p.y + p.x) * self.bytes_per_pixel;
+        // Same bounds-checked-by-construction reasoning as
+        // `FrameBuffer::row_mut`: `p` was just checked against
+        // `self.resolution`, so `i..i + bytes_per_pixel` is inside the
+        // buffer.
+        let pixel = unsafe { core::slice::from_raw_parts_mut(self.base.add(i), self.bytes_per_pixel) };
+        match self.format {
+            PixelFormat::Bgr => Bgr::paint(pixel, color),
+            PixelFormat::Rgb => Rgb::paint(pixel, color),
+            // Rebuilt per pixel rather than precomputed once, unlike
+            // `FrameBufDrawer`'s `Painter`: this console only exists
+            // for the rare pre-`services::init` failure path, where
+            // correctness matters far more than per-pixel cost.
+            PixelFormat::Bitmask {
+                r_mask,
+                g_mask,
+                b_mask,
+            } => MaskPaint::new(r_mask, g_mask, b_mask).paint(pixel, color),
+        }
+    }
+}
+
+impl Write for EmergencyConsole {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for c in s.chars() {
+            self.put_char(c);
+        }
+        Ok(())
+    }
+}
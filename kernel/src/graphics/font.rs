@@ -15,9 +15,46 @@ impl AsciiLayout {
     }
 }
 
+/// A generic box outline, computed from whatever `FONT_H`/`FONT_ROW_BYTES`
+/// the active font generated (the hand-authored hankaku format or a
+/// PSF file of any size), rather than a bitmap baked in at one fixed
+/// size.
+const fn replacement_glyph() -> [u8; FONT_H * FONT_ROW_BYTES] {
+    let mut glyph = [0u8; FONT_H * FONT_ROW_BYTES];
+    let mut row = 0;
+    while row < FONT_H {
+        let base = row * FONT_ROW_BYTES;
+        if row == 0 || row == FONT_H - 1 {
+            let mut col = 0;
+            while col < FONT_ROW_BYTES {
+                glyph[base + col] = 0xff;
+                col += 1;
+            }
+        } else {
+            glyph[base] |= 0x80;
+            glyph[base + FONT_ROW_BYTES - 1] |= 0x01;
+        }
+        row += 1;
+    }
+    glyph
+}
+
+/// Shown for any codepoint found in neither `ASCII_FONT` nor `EXT_FONT`,
+/// so a missing glyph reads as visibly absent rather than silently
+/// vanishing like whitespace.
+const REPLACEMENT_GLYPH: [u8; FONT_H * FONT_ROW_BYTES] = replacement_glyph();
+
 pub fn get_font(c: char) -> AsciiLayout {
-    let c = u8::try_from(u32::from(c)).unwrap_or(b'?') as usize;
-    AsciiLayout {
-        inner: &ASCII_FONT[c],
+    let code = u32::from(c);
+    if let Ok(b) = u8::try_from(code) {
+        return AsciiLayout {
+            inner: &ASCII_FONT[b as usize],
+        };
     }
+
+    let inner = EXT_FONT
+        .binary_search_by_key(&code, |&(cp, _)| cp)
+        .map(|i| &EXT_FONT[i].1 as &[u8])
+        .unwrap_or(&REPLACEMENT_GLYPH);
+    AsciiLayout { inner }
 }
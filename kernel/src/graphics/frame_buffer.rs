@@ -1,14 +1,12 @@
 use common_data::graphics::PixelFormat;
 
-use super::font;
-use super::{Bgr, Color, Draw, Offset, Paint, Position, Rgb};
+use super::{font, Bgr, Color, Draw, MaskPaint, Paint, Position, Rect, Rgb};
 
 #[derive(Debug)]
 pub struct FrameBuffer(::common_data::graphics::FrameBuffer);
 
 impl FrameBuffer {
     #[inline]
-    #[allow(dead_code)]
     pub const fn size(&self) -> usize {
         self.0.size
     }
@@ -28,6 +26,16 @@ impl FrameBuffer {
         self.0.format
     }
 
+    #[inline]
+    pub const fn bytes_per_pixel(&self) -> usize {
+        self.0.bytes_per_pixel
+    }
+
+    #[inline]
+    pub fn base_addr(&self) -> usize {
+        self.0.base_addr()
+    }
+
     #[inline]
     fn inner_slice_mut(&mut self) -> &mut [u8] {
         unsafe { self.0.as_mut_slice() }
@@ -35,7 +43,20 @@ impl FrameBuffer {
 
     #[inline]
     fn index(&self, x: usize, y: usize) -> usize {
-        (self.stride() * y + x) * 4
+        (self.stride() * y + x) * self.bytes_per_pixel()
+    }
+
+    /// The `stride() * bytes_per_pixel()` bytes backing scanline `y`.
+    ///
+    /// Bounds-checked against [`Self::resolution`], keeping the
+    /// `unsafe` pointer access in [`Self::inner_slice_mut`] contained to
+    /// one reviewed method instead of every blitting call site.
+    pub fn row_mut(&mut self, y: usize) -> &mut [u8] {
+        let height = self.resolution().1;
+        assert!(y < height, "row {} out of bounds (height {})", y, height);
+        let start = self.index(0, y);
+        let len = self.stride() * self.bytes_per_pixel();
+        &mut self.inner_slice_mut()[start..start + len]
     }
 }
 
@@ -51,58 +72,186 @@ impl From<::common_data::graphics::FrameBuffer> for FrameBuffer {
     }
 }
 
-type Painter = fn(&mut [u8], Color);
-type PainterWithLifeTime<'a> = fn(&'a mut [u8], Color);
+/// Dispatches to whichever [`Paint`] this framebuffer's
+/// [`PixelFormat`](common_data::graphics::PixelFormat) needs. Not a
+/// plain `fn(&mut [u8], Color)` the way [`Bgr`]/[`Rgb`] alone could be:
+/// [`MaskPaint`] carries precomputed per-channel shift/width state a
+/// bare function pointer has nowhere to hold.
+#[derive(Debug, Clone, Copy)]
+enum Painter {
+    Bgr,
+    Rgb,
+    Mask(MaskPaint),
+}
+
+impl Painter {
+    fn new(format: PixelFormat) -> Self {
+        match format {
+            PixelFormat::Bgr => Painter::Bgr,
+            PixelFormat::Rgb => Painter::Rgb,
+            PixelFormat::Bitmask {
+                r_mask,
+                g_mask,
+                b_mask,
+            } => Painter::Mask(MaskPaint::new(r_mask, g_mask, b_mask)),
+        }
+    }
+
+    #[inline]
+    fn paint(&self, pixel: &mut [u8], c: Color) {
+        match self {
+            Painter::Bgr => Bgr::paint(pixel, c),
+            Painter::Rgb => Rgb::paint(pixel, c),
+            Painter::Mask(mask) => mask.paint(pixel, c),
+        }
+    }
+
+    /// Inverse of [`Self::paint`]: reads one already-painted pixel's
+    /// raw bytes back out as the `Color` it represents. [`Bgr`]/[`Rgb`]
+    /// have no `unpaint` of their own -- unlike `paint`, reading three
+    /// fixed byte offsets needs no per-format dispatch through
+    /// [`Paint`] -- so this matches on `self` directly instead.
+    #[inline]
+    fn unpaint(&self, pixel: &[u8]) -> Color {
+        match self {
+            Painter::Bgr => Color::new(pixel[2], pixel[1], pixel[0]),
+            Painter::Rgb => Color::new(pixel[0], pixel[1], pixel[2]),
+            Painter::Mask(mask) => mask.unpaint(pixel),
+        }
+    }
+}
+
+/// Converts one packed pixel's raw bytes back to the [`Color`] that
+/// produced it, for callers outside the drawing path that need to read
+/// the framebuffer instead of write it -- currently just
+/// [`super::screenshot`].
+pub fn pixel_color(format: PixelFormat, pixel: &[u8]) -> Color {
+    Painter::new(format).unpaint(pixel)
+}
+
+/// Bound on the back buffer, absent a heap allocator to size it
+/// dynamically from `FrameBuffer::size()`. Comfortably covers common
+/// UEFI GOP modes (up to 1920x1080 @ 4 Bpp); revisit once a kernel
+/// allocator exists.
+const MAX_BACK_BUFFER_BYTES: usize = 1920 * 1080 * 4;
+static mut BACK_BUFFER: [u8; MAX_BACK_BUFFER_BYTES] = [0; MAX_BACK_BUFFER_BYTES];
 
 pub struct FrameBufDrawer<'fb> {
     pub(super) fb: &'fb mut FrameBuffer,
     pub(super) painter: Painter,
+    back_buffer: Option<&'static mut [u8]>,
 }
 
 impl<'fb> FrameBufDrawer<'fb> {
     pub fn new(fb: &'fb mut FrameBuffer) -> Self {
-        let painter = match fb.format() {
-            PixelFormat::Bgr => Bgr::paint,
-            PixelFormat::Rgb => Rgb::paint,
-        };
-        Self { fb, painter }
+        let painter = Painter::new(fb.format());
+        Self {
+            fb,
+            painter,
+            back_buffer: None,
+        }
     }
 
-    pub(super) fn draw_all(&mut self, color: Color) {
-        let lower_right = self.fb.resolution().into();
-        self.fill_rect(Position::zero(), lower_right, color);
+    /// Opt into drawing through a back buffer instead of the MMIO
+    /// framebuffer directly; call [`Self::present`] once per update to
+    /// flush it. Low-memory configurations can simply not call this.
+    ///
+    /// # Panics
+    /// If the framebuffer is larger than [`MAX_BACK_BUFFER_BYTES`].
+    pub fn enable_back_buffer(&mut self) {
+        let size = self.fb.size();
+        assert!(
+            size <= MAX_BACK_BUFFER_BYTES,
+            "framebuffer ({} B) exceeds back buffer capacity ({} B)",
+            size,
+            MAX_BACK_BUFFER_BYTES,
+        );
+        let back_buffer = unsafe { &mut BACK_BUFFER[..size] };
+        back_buffer.fill(0);
+        self.back_buffer = Some(back_buffer);
     }
 
-    pub fn draw_ascii(&mut self, c: char, start: Position, color: Color) {
-        let ascii = font::get_font(c);
-        for (dy, &layout) in ascii.as_slice().iter().enumerate() {
-            let mut l = layout;
-            let mut dx = 0;
-            while l != 0 {
-                if l & 0x80 != 0 {
-                    let p = start + Offset::new(dx, dy);
-                    self.draw_pixel(p, color);
-                }
-                dx += 1;
-                l <<= 1;
-            }
+    /// Flush the back buffer onto the real framebuffer. No-op unless
+    /// [`Self::enable_back_buffer`] was called.
+    pub fn present(&mut self) {
+        if let Some(back_buffer) = self.back_buffer.take() {
+            self.fb.inner_slice_mut()[..back_buffer.len()].copy_from_slice(back_buffer);
+            self.back_buffer = Some(back_buffer);
+        }
+    }
+
+    /// The real implementation behind [`Draw::scroll_rows`]: a single
+    /// `copy_within` moving whole scanlines at once, instead of the
+    /// trait default's per-glyph redraw. Scanlines are contiguous in
+    /// the backing buffer regardless of pixel format, so this needs no
+    /// knowledge of BGR/RGB.
+    fn scroll_rows_fast(&mut self, dst_y: usize, src_y: usize, scanlines: usize) -> bool {
+        let bytes_per_row = self.fb.stride() * self.fb.bytes_per_pixel();
+        let dst_start = dst_y * bytes_per_row;
+        let src_start = src_y * bytes_per_row;
+        let len = scanlines * bytes_per_row;
+        let buf: &mut [u8] = match &mut self.back_buffer {
+            Some(back_buffer) => back_buffer,
+            None => self.fb.inner_slice_mut(),
+        };
+        if dst_start.max(src_start) + len > buf.len() {
+            return false;
         }
+        buf.copy_within(src_start..src_start + len, dst_start);
+        true
     }
 }
 
 impl<'fb> Draw for FrameBufDrawer<'fb> {
     fn draw_pixel(&mut self, p: Position, color: Color) {
         let i = self.fb.index(p.x, p.y);
-        (self.painter)(&mut self.fb.inner_slice_mut()[i..i + 3], color);
+        let bpp = self.fb.bytes_per_pixel();
+        let painter = self.painter;
+        match &mut self.back_buffer {
+            Some(back_buffer) => painter.paint(&mut back_buffer[i..i + bpp], color),
+            None => painter.paint(&mut self.fb.inner_slice_mut()[i..i + bpp], color),
+        }
+    }
+
+    /// One slice per glyph row instead of one [`Draw::draw_pixel`] call
+    /// (with its own index/format lookup) per pixel -- the index is
+    /// computed once per row, and every pixel in it is painted through
+    /// the one slice that row's bytes live in.
+    fn draw_ascii_bg(&mut self, c: char, start: Position, fg: Color, bg: Color) {
+        let ascii = font::get_font(c);
+        let bpp = self.fb.bytes_per_pixel();
+        let painter = self.painter;
+        for (dy, row) in ascii.as_slice().chunks(font::FONT_ROW_BYTES).enumerate() {
+            let row_start = self.fb.index(start.x, start.y + dy);
+            let row_len = font::FONT_W * bpp;
+            let buf: &mut [u8] = match &mut self.back_buffer {
+                Some(back_buffer) => &mut back_buffer[row_start..row_start + row_len],
+                None => &mut self.fb.inner_slice_mut()[row_start..row_start + row_len],
+            };
+            for x in 0..font::FONT_W {
+                let byte = row[x / 8];
+                let color = if byte & (0x80 >> (x % 8)) != 0 { fg } else { bg };
+                painter.paint(&mut buf[x * bpp..(x + 1) * bpp], color);
+            }
+        }
+    }
+
+    fn scroll_rows(&mut self, dst_y: usize, src_y: usize, scanlines: usize) -> bool {
+        self.scroll_rows_fast(dst_y, src_y, scanlines)
+    }
+
+    fn draw_all(&mut self, color: Color) {
+        let size = self.fb.resolution().into();
+        self.fill_rect(Rect::new(Position::zero(), size), color);
     }
 }
 
 // This implementation can write on only single window now(i.e. cannot scroll).
 impl<'fb> core::fmt::Debug for FrameBufDrawer<'fb> {
-    fn fmt<'a>(&'a self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("FrameBufDrawer")
             .field("fb", &self.fb)
-            .field("painter", &self.painter as &PainterWithLifeTime<'a>)
+            .field("painter", &self.painter)
             .finish()
     }
 }
@@ -0,0 +1,249 @@
+//! A minimal layer/compositor system so the console, mouse cursor and
+//! status bar can each draw into their own offscreen buffer instead of
+//! having to repair pixels the others overwrote.
+use super::{Color, Draw, FrameBufDrawer, Position, Rect};
+
+/// An offscreen pixel buffer positioned and stacked over a [`Compositor`].
+///
+/// Pixels are addressed in layer-local coordinates (`0..rect.size` on
+/// each axis) so moving a layer is just updating `rect.origin` — the
+/// pixel data itself doesn't need to move or get redrawn, only
+/// recomposited (see [`Self::move_to`]).
+pub struct Layer {
+    rect: Rect,
+    z: i32,
+    pixels: &'static mut [Option<Color>],
+}
+
+impl Layer {
+    /// `pixels` is the layer's backing storage, at least
+    /// `rect.size.x * rect.size.y` long; absent a heap allocator it's the
+    /// caller's job to provide storage sized for `rect` (typically a
+    /// `static mut` array, as the kernel already does for
+    /// [`super::frame_buffer::FrameBufDrawer`]'s back buffer).
+    pub fn new(rect: Rect, z: i32, pixels: &'static mut [Option<Color>]) -> Self {
+        assert!(
+            pixels.len() >= rect.size.x * rect.size.y,
+            "Layer: backing storage ({} px) smaller than its rect ({}x{})",
+            pixels.len(),
+            rect.size.x,
+            rect.size.y,
+        );
+        Self { rect, z, pixels }
+    }
+
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn z(&self) -> i32 {
+        self.z
+    }
+
+    fn index(&self, local: Position) -> usize {
+        local.y * self.rect.size.x + local.x
+    }
+
+    /// `p` is in layer-local coordinates.
+    pub fn set_pixel(&mut self, p: Position, color: Color) {
+        let i = self.index(p);
+        self.pixels[i] = Some(color);
+    }
+
+    /// `p` is in layer-local coordinates.
+    pub fn clear_pixel(&mut self, p: Position) {
+        let i = self.index(p);
+        self.pixels[i] = None;
+    }
+
+    /// The color this layer contributes at the absolute framebuffer
+    /// position `p`, or `None` if `p` falls outside the layer or the
+    /// layer is transparent there.
+    pub fn pixel_at(&self, p: Position) -> Option<Color> {
+        if !self.rect.contains(p) {
+            return None;
+        }
+        let local = p.checked_sub(self.rect.origin)?;
+        self.pixels[self.index(local)]
+    }
+
+    /// Move the layer so its origin becomes `new_origin`, returning the
+    /// union of its old and new rects: the region a [`Compositor`] needs
+    /// to recomposite to erase the old position and paint the new one,
+    /// without touching this layer's own pixel buffer or any other
+    /// layer's.
+    pub fn move_to(&mut self, new_origin: Position) -> Rect {
+        let old_rect = self.rect;
+        self.rect.origin = new_origin;
+        old_rect.union(&self.rect)
+    }
+}
+
+impl Draw for Layer {
+    /// `p` is in absolute framebuffer coordinates, like every other
+    /// `Draw` implementor — pixels outside this layer's rect are
+    /// dropped rather than panicking, so existing `Draw`-based drawing
+    /// code (`draw_ascii`, `fill_rect`, `draw_rect`) can safely target a
+    /// layer sized smaller than the screen.
+    fn draw_pixel(&mut self, p: Position, color: Color) {
+        if let Some(local) = p.checked_sub(self.rect.origin) {
+            if local.x < self.rect.size.x && local.y < self.rect.size.y {
+                self.set_pixel(local, color);
+            }
+        }
+    }
+}
+
+/// Composites a fixed set of [`Layer`]s, stacked by [`Layer::z`] (higher
+/// on top), into the framebuffer. Registered layers must outlive the
+/// compositor, mirroring how the rest of the kernel hands out `'static`
+/// references to its global devices (see `services::globals`).
+pub struct Compositor<'fb> {
+    drawer: FrameBufDrawer<'fb>,
+    layers: [Option<&'static mut Layer>; Self::MAX_LAYERS],
+}
+
+impl<'fb> Compositor<'fb> {
+    const MAX_LAYERS: usize = 4;
+
+    pub fn new(drawer: FrameBufDrawer<'fb>) -> Self {
+        Self {
+            drawer,
+            layers: [None, None, None, None],
+        }
+    }
+
+    /// Register a layer. Panics if the compositor already holds
+    /// [`Self::MAX_LAYERS`] layers.
+    pub fn push_layer(&mut self, layer: &'static mut Layer) {
+        let slot = self
+            .layers
+            .iter_mut()
+            .find(|l| l.is_none())
+            .expect("Compositor: too many layers");
+        *slot = Some(layer);
+    }
+
+    /// Recomposite `dirty` from every registered layer, back (lowest z)
+    /// to front (highest z), and paint the result onto the framebuffer.
+    /// Callers pass the union of a moved layer's old and new rects
+    /// ([`Layer::move_to`]'s return value) so only the pixels that could
+    /// have changed are touched.
+    pub fn recomposite(&mut self, dirty: Rect) {
+        self.layers
+            .sort_unstable_by_key(|l| l.as_ref().map_or(i32::MIN, |l| l.z));
+
+        for y in dirty.scanlines() {
+            for x in dirty.origin.x..dirty.lower_right().x {
+                let p = Position::new(x, y);
+                let mut color = None;
+                for layer in self.layers.iter().flatten() {
+                    if let Some(c) = layer.pixel_at(p) {
+                        color = Some(c);
+                    }
+                }
+                if let Some(color) = color {
+                    self.drawer.draw_pixel(p, color);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_layer(rect: Rect, z: i32, storage: &'static mut [Option<Color>]) -> Layer {
+        Layer::new(rect, z, storage)
+    }
+
+    #[test]
+    fn pixel_at_is_none_outside_the_layer() {
+        static mut STORAGE: [Option<Color>; 4] = [None; 4];
+        let storage = unsafe { &mut STORAGE };
+        let layer = make_layer(
+            Rect::new(Position::new(10, 10), super::super::Offset::new(2, 2)),
+            0,
+            storage,
+        );
+        assert_eq!(layer.pixel_at(Position::new(0, 0)), None);
+        assert_eq!(layer.pixel_at(Position::new(10, 10)), None); // untouched: transparent
+    }
+
+    #[test]
+    fn set_pixel_is_addressed_in_layer_local_coordinates() {
+        static mut STORAGE: [Option<Color>; 4] = [None; 4];
+        let storage = unsafe { &mut STORAGE };
+        let mut layer = make_layer(
+            Rect::new(Position::new(10, 10), super::super::Offset::new(2, 2)),
+            0,
+            storage,
+        );
+        layer.set_pixel(Position::new(1, 1), Color::RED);
+        assert_eq!(layer.pixel_at(Position::new(11, 11)), Some(Color::RED));
+        assert_eq!(layer.pixel_at(Position::new(10, 10)), None);
+    }
+
+    #[test]
+    fn move_to_reports_the_union_of_old_and_new_rects() {
+        static mut STORAGE: [Option<Color>; 4] = [None; 4];
+        let storage = unsafe { &mut STORAGE };
+        let mut layer = make_layer(
+            Rect::new(Position::new(0, 0), super::super::Offset::new(2, 2)),
+            0,
+            storage,
+        );
+        let dirty = layer.move_to(Position::new(5, 5));
+        assert_eq!(
+            dirty,
+            Rect::from_corners(Position::new(0, 0), Position::new(7, 7))
+        );
+        assert_eq!(layer.rect().origin, Position::new(5, 5));
+    }
+
+    #[test]
+    fn draw_pixel_outside_the_layer_is_dropped_not_panicking() {
+        static mut STORAGE: [Option<Color>; 4] = [None; 4];
+        let storage = unsafe { &mut STORAGE };
+        let mut layer = make_layer(
+            Rect::new(Position::new(10, 10), super::super::Offset::new(2, 2)),
+            0,
+            storage,
+        );
+        layer.draw_pixel(Position::new(0, 0), Color::RED); // would be out of bounds if not clipped
+        layer.draw_pixel(Position::new(11, 11), Color::BLUE);
+        assert_eq!(layer.pixel_at(Position::new(0, 0)), None);
+        assert_eq!(layer.pixel_at(Position::new(11, 11)), Some(Color::BLUE));
+    }
+
+    #[test]
+    fn higher_z_layer_wins_on_overlap() {
+        static mut BACK: [Option<Color>; 4] = [None; 4];
+        static mut FRONT: [Option<Color>; 4] = [None; 4];
+        let mut back = make_layer(
+            Rect::new(Position::new(0, 0), super::super::Offset::new(2, 2)),
+            0,
+            unsafe { &mut BACK },
+        );
+        let mut front = make_layer(
+            Rect::new(Position::new(0, 0), super::super::Offset::new(2, 2)),
+            1,
+            unsafe { &mut FRONT },
+        );
+        back.set_pixel(Position::new(0, 0), Color::RED);
+        front.set_pixel(Position::new(0, 0), Color::BLUE);
+
+        // Composite by hand, mirroring Compositor::recomposite's
+        // back-to-front fold, to test layer ordering without a real
+        // framebuffer to draw onto.
+        let layers = [&back, &front];
+        let mut winner = None;
+        for layer in layers {
+            if let Some(c) = layer.pixel_at(Position::new(0, 0)) {
+                winner = Some(c);
+            }
+        }
+        assert_eq!(winner, Some(Color::BLUE));
+    }
+}
@@ -1,13 +1,160 @@
 #[macro_use]
 pub mod console;
+pub mod emergency;
 pub mod font;
 pub mod frame_buffer;
+pub mod layer;
 mod paint;
+pub mod screenshot;
+pub mod status_bar;
 
 pub use console::*;
 pub use frame_buffer::*;
+pub use layer::*;
 pub use paint::*;
+pub use status_bar::{Stats as StatusBarStats, StatusBar};
 
 use crate::data_types::Vec2D;
 pub type Position = Vec2D<usize>;
 pub type Offset = Vec2D<usize>;
+/// Signed delta, e.g. a relative mouse movement report.
+pub type SignedOffset = Vec2D<i32>;
+
+impl Position {
+    /// Apply a signed delta (such as a mouse movement report), clamping the
+    /// result to stay within `0..resolution` on both axes instead of
+    /// wrapping or panicking on underflow.
+    pub fn offset_signed(&self, d: SignedOffset, resolution: (usize, usize)) -> Position {
+        let max_x = resolution.0.saturating_sub(1) as i32;
+        let max_y = resolution.1.saturating_sub(1) as i32;
+        let x = (self.x as i32 + d.x).clamp(0, max_x) as usize;
+        let y = (self.y as i32 + d.y).clamp(0, max_y) as usize;
+        Position::new(x, y)
+    }
+}
+
+/// An axis-aligned rectangle, used for clipping and hit-testing instead of
+/// hand-rolling corner arithmetic at every call site.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub origin: Position,
+    pub size: Offset,
+}
+
+impl Rect {
+    pub const fn new(origin: Position, size: Offset) -> Self {
+        Self { origin, size }
+    }
+
+    /// Build from the two corners `draw_rect`/`fill_rect` used to take,
+    /// for call sites migrating off that API.
+    pub fn from_corners(upper_left: Position, lower_right: Position) -> Self {
+        Self {
+            origin: upper_left,
+            size: lower_right.saturating_sub(upper_left),
+        }
+    }
+
+    pub fn lower_right(&self) -> Position {
+        self.origin.saturating_add(self.size)
+    }
+
+    pub fn contains(&self, p: Position) -> bool {
+        let lower_right = self.lower_right();
+        p.x >= self.origin.x && p.y >= self.origin.y && p.x < lower_right.x && p.y < lower_right.y
+    }
+
+    /// The overlapping region of `self` and `other`, or `None` if they
+    /// don't overlap.
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let origin = self.origin.max(other.origin);
+        let lower_right = self.lower_right().min(other.lower_right());
+        if origin.x >= lower_right.x || origin.y >= lower_right.y {
+            return None;
+        }
+        Some(Self::from_corners(origin, lower_right))
+    }
+
+    /// The `y` coordinate of every scanline this rect covers, top to bottom.
+    pub fn scanlines(&self) -> core::ops::Range<usize> {
+        self.origin.y..self.lower_right().y
+    }
+
+    /// The smallest rect covering both `self` and `other`, e.g. the dirty
+    /// region to recomposite when a layer moves from `self` to `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        let origin = self.origin.min(other.origin);
+        let lower_right = self.lower_right().max(other.lower_right());
+        Self::from_corners(origin, lower_right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESOLUTION: (usize, usize) = (800, 600);
+
+    #[test]
+    fn clamps_at_left_and_top_edges() {
+        let p = Position::new(0, 0);
+        let moved = p.offset_signed(SignedOffset::new(-10, -10), RESOLUTION);
+        assert_eq!(moved, Position::new(0, 0));
+    }
+
+    #[test]
+    fn clamps_at_right_and_bottom_edges() {
+        let p = Position::new(799, 599);
+        let moved = p.offset_signed(SignedOffset::new(10, 10), RESOLUTION);
+        assert_eq!(moved, Position::new(799, 599));
+    }
+
+    #[test]
+    fn moves_freely_within_bounds() {
+        let p = Position::new(100, 100);
+        let moved = p.offset_signed(SignedOffset::new(-5, 3), RESOLUTION);
+        assert_eq!(moved, Position::new(95, 103));
+    }
+
+    #[test]
+    fn rect_contains_is_exclusive_of_lower_right() {
+        let rect = Rect::from_corners(Position::new(10, 10), Position::new(20, 20));
+        assert!(rect.contains(Position::new(10, 10)));
+        assert!(rect.contains(Position::new(19, 19)));
+        assert!(!rect.contains(Position::new(20, 20)));
+        assert!(!rect.contains(Position::new(9, 15)));
+    }
+
+    #[test]
+    fn rect_intersect_overlapping() {
+        let a = Rect::from_corners(Position::new(0, 0), Position::new(10, 10));
+        let b = Rect::from_corners(Position::new(5, 5), Position::new(15, 15));
+        assert_eq!(
+            a.intersect(&b),
+            Some(Rect::from_corners(Position::new(5, 5), Position::new(10, 10)))
+        );
+    }
+
+    #[test]
+    fn rect_intersect_disjoint_is_none() {
+        let a = Rect::from_corners(Position::new(0, 0), Position::new(10, 10));
+        let b = Rect::from_corners(Position::new(20, 20), Position::new(30, 30));
+        assert_eq!(a.intersect(&b), None);
+    }
+
+    #[test]
+    fn rect_union_covers_both() {
+        let a = Rect::from_corners(Position::new(0, 0), Position::new(10, 10));
+        let b = Rect::from_corners(Position::new(5, 20), Position::new(30, 25));
+        assert_eq!(
+            a.union(&b),
+            Rect::from_corners(Position::new(0, 0), Position::new(30, 25))
+        );
+    }
+
+    #[test]
+    fn rect_scanlines_cover_origin_to_lower_right() {
+        let rect = Rect::from_corners(Position::new(0, 3), Position::new(5, 6));
+        assert_eq!(rect.scanlines().collect::<std::vec::Vec<_>>(), [3, 4, 5]);
+    }
+}
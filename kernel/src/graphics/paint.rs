@@ -1,4 +1,5 @@
-use super::Position;
+use super::font;
+use super::{Offset, Position, Rect};
 
 pub trait Paint {
     /// fill the continuous elements with RGB properties.
@@ -10,7 +11,52 @@ pub trait Paint {
 pub trait Draw {
     fn draw_pixel(&mut self, p: Position, color: Color);
 
-    fn draw_rect(&mut self, upper_left: Position, lower_right: Position, color: Color) {
+    fn draw_ascii(&mut self, c: char, start: Position, color: Color) {
+        let ascii = font::get_font(c);
+        for (dy, row) in ascii.as_slice().chunks(font::FONT_ROW_BYTES).enumerate() {
+            for (byte_index, &byte) in row.iter().enumerate() {
+                let mut l = byte;
+                let mut bit = 0;
+                while l != 0 {
+                    if l & 0x80 != 0 {
+                        let p = start + Offset::new(byte_index * 8 + bit, dy);
+                        self.draw_pixel(p, color);
+                    }
+                    bit += 1;
+                    l <<= 1;
+                }
+            }
+        }
+    }
+
+    /// Like [`Self::draw_ascii`], but paints the full `FONT_W`x`FONT_H`
+    /// cell instead of only the glyph's foreground bits: `bg` where a
+    /// bit is 0, `fg` where it's 1. Overwriting a cell that used to hold
+    /// a "wider" glyph (status bar refresh, shell line editing, cursor
+    /// blink) with [`Self::draw_ascii`] alone leaves whatever foreground
+    /// pixels the old glyph set and the new one doesn't; this repaints
+    /// every pixel in the cell instead, so nothing from the previous
+    /// glyph can show through.
+    ///
+    /// The default implementation here still goes through
+    /// [`Self::draw_pixel`] one bit at a time, same as
+    /// [`Self::draw_ascii`]; [`super::FrameBufDrawer`] overrides this
+    /// with a row-wise version that writes a whole glyph row through one
+    /// slice instead.
+    fn draw_ascii_bg(&mut self, c: char, start: Position, fg: Color, bg: Color) {
+        let ascii = font::get_font(c);
+        for (dy, row) in ascii.as_slice().chunks(font::FONT_ROW_BYTES).enumerate() {
+            for x in 0..font::FONT_W {
+                let byte = row[x / 8];
+                let color = if byte & (0x80 >> (x % 8)) != 0 { fg } else { bg };
+                self.draw_pixel(start + Offset::new(x, dy), color);
+            }
+        }
+    }
+
+    fn draw_rect(&mut self, rect: Rect, color: Color) {
+        let upper_left = rect.origin;
+        let lower_right = rect.lower_right();
         if upper_left.x == lower_right.x || upper_left.y == lower_right.y {
             return;
         }
@@ -25,16 +71,36 @@ pub trait Draw {
         }
     }
 
-    fn fill_rect(&mut self, upper_left: Position, lower_right: Position, color: Color) {
-        for x in upper_left.x..lower_right.x {
-            for y in upper_left.y..lower_right.y {
+    fn fill_rect(&mut self, rect: Rect, color: Color) {
+        let lower_right = rect.lower_right();
+        for y in rect.scanlines() {
+            for x in rect.origin.x..lower_right.x {
                 self.draw_pixel(Position::new(x, y), color);
             }
         }
     }
+
+    /// Shift `scanlines` consecutive already-drawn rows of pixels from
+    /// `src_y` to `dst_y`, for scrolling a console without redrawing
+    /// every glyph that didn't change. Returns whether the shift
+    /// happened; the default no-op `false` tells the caller to fall
+    /// back to redrawing the destination region itself (e.g. from a
+    /// console's own scrollback), which every [`Draw`] can do through
+    /// [`Self::draw_ascii`] regardless of whether it can blit.
+    fn scroll_rows(&mut self, _dst_y: usize, _src_y: usize, _scanlines: usize) -> bool {
+        false
+    }
+
+    /// Clear this drawable's entire surface to `color`, e.g. before a
+    /// console repaints from a blank screen. The default is a no-op:
+    /// unlike [`super::FrameBufDrawer`], most `Draw` implementors (a
+    /// [`super::Layer`], a test double) have no stable notion of "the
+    /// whole surface" to clear, and can't conjure one from [`Draw`]
+    /// alone -- [`Self::fill_rect`] an explicit [`Rect`] instead.
+    fn draw_all(&mut self, _color: Color) {}
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Color {
     r: u8,
     g: u8,
@@ -46,7 +112,10 @@ impl Color {
     pub const BLACK: Self = Self::new(0, 0, 0);
     pub const RED: Self = Self::new(255, 0, 0);
     pub const GREEN: Self = Self::new(0, 255, 0);
+    pub const YELLOW: Self = Self::new(255, 255, 0);
     pub const BLUE: Self = Self::new(0, 0, 255);
+    pub const MAGENTA: Self = Self::new(255, 0, 255);
+    pub const CYAN: Self = Self::new(0, 255, 255);
     pub const WHITE: Self = Self::new(255, 255, 255);
 }
 
@@ -54,6 +123,13 @@ impl Color {
     pub const fn new(r: u8, g: u8, b: u8) -> Self {
         Self { r, g, b }
     }
+
+    /// This color's channels as `(r, g, b)`, for callers outside this
+    /// module that need to read them back out (e.g.
+    /// [`super::screenshot`] writing a PPM's `r g b` triples).
+    pub const fn channels(&self) -> (u8, u8, u8) {
+        (self.r, self.g, self.b)
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -79,3 +155,117 @@ impl Paint for Rgb {
         pixel[2] = c.b;
     }
 }
+
+/// Where one color channel lives within a packed pixel, derived from a
+/// GOP `PixelBitMask` channel mask.
+#[derive(Debug, Clone, Copy)]
+struct MaskChannel {
+    shift: u32,
+    width: u32,
+}
+
+impl MaskChannel {
+    fn from_mask(mask: u32) -> Self {
+        Self {
+            shift: mask.trailing_zeros(),
+            width: mask.count_ones(),
+        }
+    }
+
+    /// Scales an 8-bit channel value to this mask's bit width and
+    /// shifts it into its place in the packed pixel.
+    fn pack(&self, value: u8) -> u32 {
+        let scaled = if self.width >= 8 {
+            (value as u32) << (self.width - 8)
+        } else {
+            (value as u32) >> (8 - self.width)
+        };
+        scaled << self.shift
+    }
+
+    /// Inverse of [`Self::pack`]: pulls this channel's bits out of
+    /// `packed` and widens them back to 8 bits. Exact when `width >=
+    /// 8`; for a narrower channel (e.g. 5-6-5) this only fills the
+    /// high bits of the result, the same precision a real display at
+    /// that depth would show, so round-tripping `pack` then `unpack`
+    /// doesn't reproduce every original value exactly.
+    fn unpack(&self, packed: u32) -> u8 {
+        let mask = (1u32 << self.width) - 1;
+        let raw = (packed >> self.shift) & mask;
+        if self.width >= 8 {
+            (raw >> (self.width - 8)) as u8
+        } else {
+            (raw << (8 - self.width)) as u8
+        }
+    }
+}
+
+/// Paints GOP's `PixelBitMask` format: each channel's shift and
+/// width are derived once from its mask at construction (see
+/// [`MaskChannel::from_mask`]) instead of being re-derived on every
+/// pixel, the way [`Bgr`]/[`Rgb`]'s fixed byte offsets cost nothing to
+/// "derive" at all. Unlike [`Paint`]'s implementors this carries
+/// state, so it doesn't implement [`Paint`] and can't be named by the
+/// stateless `fn(&mut [u8], Color)` those use -- callers hold a
+/// `MaskPaint` and call [`Self::paint`] directly.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct MaskPaint {
+    r: MaskChannel,
+    g: MaskChannel,
+    b: MaskChannel,
+}
+
+impl MaskPaint {
+    pub(super) fn new(r_mask: u32, g_mask: u32, b_mask: u32) -> Self {
+        Self {
+            r: MaskChannel::from_mask(r_mask),
+            g: MaskChannel::from_mask(g_mask),
+            b: MaskChannel::from_mask(b_mask),
+        }
+    }
+
+    #[inline]
+    pub(super) fn paint(&self, pixel: &mut [u8], c: Color) {
+        let packed = self.r.pack(c.r) | self.g.pack(c.g) | self.b.pack(c.b);
+        pixel[..4].copy_from_slice(&packed.to_le_bytes());
+    }
+
+    /// Inverse of [`Self::paint`], for [`super::pixel_color`].
+    #[inline]
+    pub(super) fn unpaint(&self, pixel: &[u8]) -> Color {
+        let packed = u32::from_le_bytes(pixel[..4].try_into().unwrap());
+        Color::new(self.r.unpack(packed), self.g.unpack(packed), self.b.unpack(packed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn five_six_five_packs_each_channel_into_its_bit_range() {
+        // 5-6-5: R at bits 11..=15, G at bits 5..=10, B at bits 0..=4.
+        let paint = MaskPaint::new(0xF800, 0x07E0, 0x001F);
+        let mut pixel = [0u8; 4];
+        paint.paint(&mut pixel, Color::new(0xFF, 0xFF, 0xFF));
+        assert_eq!(u32::from_le_bytes(pixel), 0xFFFF);
+
+        let mut pixel = [0u8; 4];
+        paint.paint(&mut pixel, Color::new(0xFF, 0x00, 0x00));
+        assert_eq!(u32::from_le_bytes(pixel), 0xF800);
+    }
+
+    #[test]
+    fn ten_ten_ten_packs_each_channel_into_its_bit_range() {
+        // 10-10-10: R at bits 20..=29, G at bits 10..=19, B at bits 0..=9.
+        let paint = MaskPaint::new(0x3FF00000, 0x000FFC00, 0x000003FF);
+        let mut pixel = [0u8; 4];
+        paint.paint(&mut pixel, Color::new(0xFF, 0x00, 0x00));
+        // 0xFF scaled up from 8 to 10 bits is 0xFF << 2 = 0x3FC, shifted to bit 20.
+        assert_eq!(u32::from_le_bytes(pixel), 0x3FC00000);
+
+        let mut pixel = [0u8; 4];
+        paint.paint(&mut pixel, Color::new(0x00, 0x00, 0xFF));
+        assert_eq!(u32::from_le_bytes(pixel), 0x3FC);
+    }
+}
@@ -0,0 +1,78 @@
+//! Dumps a framebuffer's visible pixels as a binary-safe ASCII P3 PPM,
+//! for pulling a screenshot off real hardware to diff against an
+//! expected render. Streams the image row by row through `out` instead
+//! of building it in memory first -- no heap allocator in this kernel
+//! yet (same tradeoff as `status_bar::Stats`'s doc describes).
+//!
+//! The `screenshot` shell command writes this over COM1
+//! ([`crate::devices::serial`]), which never touches the framebuffer
+//! being read. [`dump_ppm`] reads each pixel from the framebuffer right
+//! before writing it out, so a sink that itself draws into the same
+//! framebuffer (such as the on-screen console) would end up capturing
+//! its own output.
+use core::fmt;
+
+use super::{pixel_color, FrameBuffer};
+
+/// Writes `fb`'s visible pixels to `out` as an ASCII P3 PPM: a
+/// `P3\n<width> <height>\n255\n` header, then one `r g b` triple per
+/// pixel, row-major.
+///
+/// Reads `fb.stride()` pixels per row but only ever looks at the first
+/// `fb.resolution().0` of them, so padding columns (`stride > width`)
+/// are skipped rather than dumped into the image.
+pub fn dump_ppm(fb: &mut FrameBuffer, out: &mut dyn fmt::Write) -> fmt::Result {
+    let (width, height) = fb.resolution();
+    let bpp = fb.bytes_per_pixel();
+    let format = fb.format();
+    writeln!(out, "P3\n{} {}\n255", width, height)?;
+    for y in 0..height {
+        let row = fb.row_mut(y);
+        for x in 0..width {
+            let (r, g, b) = pixel_color(format, &row[x * bpp..(x + 1) * bpp]).channels();
+            writeln!(out, "{} {} {}", r, g, b)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::common_data::graphics::{FrameBuffer as RawFrameBuffer, PixelFormat};
+
+    #[test]
+    fn header_and_pixels_are_correct_for_a_small_bgr_framebuffer() {
+        const WIDTH: usize = 2;
+        const HEIGHT: usize = 2;
+        const STRIDE: usize = 3; // wider than WIDTH: the padding column must be skipped
+        let mut bytes = vec![0u8; STRIDE * HEIGHT * 4];
+        bytes[0..4].copy_from_slice(&[0, 0, 255, 0]); // (0,0) BGR -> red
+        bytes[4..8].copy_from_slice(&[0, 255, 0, 0]); // (1,0) BGR -> green
+        bytes[STRIDE * 4..STRIDE * 4 + 4].copy_from_slice(&[255, 0, 0, 0]); // (0,1) BGR -> blue
+
+        let mut fb: FrameBuffer = unsafe {
+            RawFrameBuffer::from_raw_parts(
+                bytes.as_mut_ptr(),
+                bytes.len(),
+                STRIDE,
+                (WIDTH, HEIGHT),
+                PixelFormat::Bgr,
+            )
+        }
+        .into();
+
+        let mut out = String::new();
+        dump_ppm(&mut fb, &mut out).unwrap();
+
+        let mut lines = out.lines();
+        assert_eq!(lines.next(), Some("P3"));
+        assert_eq!(lines.next(), Some("2 2"));
+        assert_eq!(lines.next(), Some("255"));
+        assert_eq!(lines.next(), Some("255 0 0"));
+        assert_eq!(lines.next(), Some("0 255 0"));
+        assert_eq!(lines.next(), Some("0 0 255"));
+        assert_eq!(lines.next(), Some("0 0 0"));
+        assert_eq!(lines.next(), None);
+    }
+}
@@ -0,0 +1,110 @@
+//! A one-line status bar rendered above the console so runtime health is
+//! visible at a glance without scrolling logs. Owns its own [`Layer`];
+//! callers refresh it (the main loop does so on every iteration) by
+//! calling [`StatusBar::render`] and compositing [`StatusBar::rect`]
+//! afterward.
+use core::fmt::Write as _;
+
+use super::font::{FONT_H as FH, FONT_W as FW};
+use super::{Color, Draw, Layer, Offset, Position, Rect};
+
+pub const HEIGHT: usize = FH + 4;
+const X_PAD: usize = 4;
+const Y_PAD: usize = 2;
+
+/// Bound on the bar's width, absent a heap allocator to size its backing
+/// storage dynamically from the framebuffer's actual resolution (same
+/// tradeoff as `frame_buffer::MAX_BACK_BUFFER_BYTES`).
+pub const MAX_WIDTH: usize = 1920;
+
+/// Backing storage for the one [`StatusBar`] the kernel constructs at
+/// boot. `Layer::new` only indexes the first `width * HEIGHT` entries of
+/// whatever it's handed, so handing over the whole static array
+/// regardless of the screen's actual width is harmless.
+pub fn static_storage() -> &'static mut [Option<Color>] {
+    static mut STORAGE: [Option<Color>; MAX_WIDTH * HEIGHT] = [None; MAX_WIDTH * HEIGHT];
+    unsafe { &mut STORAGE }
+}
+
+/// Runtime counters the bar displays.
+///
+/// Free heap bytes and configured USB slot counts were also asked for,
+/// but this kernel has neither a heap allocator nor a USB `DeviceManager`
+/// yet — those fields join `Stats` once those subsystems exist.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Stats {
+    /// Main-loop iterations since boot. Not wall-clock time: there's no
+    /// timer/PIT driver in this kernel yet, so this is the closest thing
+    /// to an uptime counter available.
+    pub loop_ticks: u64,
+    pub pci_device_count: usize,
+    pub xhci_events_processed: usize,
+}
+
+pub struct StatusBar {
+    layer: Layer,
+    cursor_x: usize,
+}
+
+impl StatusBar {
+    pub fn new(width: usize, storage: &'static mut [Option<Color>]) -> Self {
+        let rect = Rect::new(Position::zero(), Offset::new(width, HEIGHT));
+        Self {
+            layer: Layer::new(rect, i32::MAX, storage),
+            cursor_x: X_PAD,
+        }
+    }
+
+    pub fn rect(&self) -> Rect {
+        self.layer.rect()
+    }
+
+    pub fn layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    /// Redraws the whole bar from `stats`. Doesn't composite it onto the
+    /// framebuffer itself — pass [`Self::rect`] to
+    /// [`super::Compositor::recomposite`] afterward.
+    pub fn render(&mut self, stats: &Stats) {
+        self.layer.fill_rect(self.rect(), Color::BLACK);
+        self.cursor_x = X_PAD;
+        let _ = write!(
+            self,
+            "uptime(ticks)={} pci={} xhci_events={}",
+            stats.loop_ticks, stats.pci_device_count, stats.xhci_events_processed,
+        );
+    }
+}
+
+impl core::fmt::Write for StatusBar {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for c in s.chars() {
+            let pos = Position::new(self.cursor_x, Y_PAD);
+            self.layer.draw_ascii_bg(c, pos, Color::WHITE, Color::BLACK);
+            self.cursor_x += FW;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_stays_within_its_own_rect() {
+        const WIDTH: usize = 200;
+        static mut STORAGE: [Option<Color>; WIDTH * HEIGHT] = [None; WIDTH * HEIGHT];
+        let storage = unsafe { &mut STORAGE };
+        let mut bar = StatusBar::new(WIDTH, storage);
+        bar.render(&Stats {
+            loop_ticks: 42,
+            pci_device_count: 3,
+            xhci_events_processed: 7,
+        });
+        // A long rendered line is silently clipped by Layer's Draw impl
+        // rather than panicking or corrupting adjacent memory.
+        assert_eq!(bar.rect(), Rect::new(Position::zero(), Offset::new(WIDTH, HEIGHT)));
+    }
+}
@@ -0,0 +1,133 @@
+#![allow(dead_code)]
+//! Key-repeat (typematic) generation, layered on top of raw press/release
+//! events and driven by timer ticks rather than interrupt context: USB
+//! HID boot keyboards only report a state change, so holding a key down
+//! alone produces exactly one event.
+//!
+//! There's no shared key-event type (only `devices::ps2::KeyEvent`,
+//! keyed by raw scancode), timer tick service, or keyboard driver in
+//! this tree yet (see [`crate::shell`]'s module doc), so [`Typematic`] is
+//! written generic over whatever key identifier type the eventual HID
+//! driver uses (`K: Copy + PartialEq`) and driven by an explicit
+//! millisecond timestamp rather than a `TimerTick` message -- real,
+//! tested repeat logic the main loop can drive once both exist, without
+//! needing either first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct HeldKey<K> {
+    key: K,
+    next_repeat_at_ms: u64,
+}
+
+/// Generates repeat events for a held, non-modifier key: after
+/// `initial_delay_ms` with no release or other key press, [`Self::tick`]
+/// starts returning the held key once every `repeat_interval_ms`.
+#[derive(Debug)]
+pub struct Typematic<K> {
+    initial_delay_ms: u64,
+    repeat_interval_ms: u64,
+    held: Option<HeldKey<K>>,
+}
+
+impl<K: Copy + PartialEq> Typematic<K> {
+    pub fn new(initial_delay_ms: u64, repeat_interval_ms: u64) -> Self {
+        Self {
+            initial_delay_ms,
+            repeat_interval_ms,
+            held: None,
+        }
+    }
+
+    /// `key` was pressed at `now_ms`. A modifier never starts a repeat of
+    /// its own and leaves whichever non-modifier key is already held
+    /// alone (so e.g. Shift pressed mid-hold doesn't cancel the letter's
+    /// repeat); any other key press replaces the held key, cancelling
+    /// whatever was being repeated before it.
+    pub fn key_down(&mut self, key: K, is_modifier: bool, now_ms: u64) {
+        if is_modifier {
+            return;
+        }
+        self.held = Some(HeldKey {
+            key,
+            next_repeat_at_ms: now_ms + self.initial_delay_ms,
+        });
+    }
+
+    /// `key` was released. Only cancels the repeat if it's the key
+    /// currently being tracked -- a stale or unrelated release (e.g. a
+    /// modifier that never started a repeat) is a no-op.
+    pub fn key_up(&mut self, key: K) {
+        if matches!(&self.held, Some(h) if h.key == key) {
+            self.held = None;
+        }
+    }
+
+    /// Call on every timer tick with the current time. Returns the held
+    /// key once its repeat is due, rearming for the next interval.
+    pub fn tick(&mut self, now_ms: u64) -> Option<K> {
+        let held = self.held.as_mut()?;
+        if now_ms < held.next_repeat_at_ms {
+            return None;
+        }
+        held.next_repeat_at_ms = now_ms + self.repeat_interval_ms;
+        Some(held.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELAY: u64 = 400;
+    const INTERVAL: u64 = 50; // 20 Hz
+
+    #[test]
+    fn no_repeat_before_the_initial_delay_elapses() {
+        let mut t = Typematic::new(DELAY, INTERVAL);
+        t.key_down('a', false, 0);
+        assert_eq!(t.tick(399), None);
+    }
+
+    #[test]
+    fn repeats_at_the_configured_interval_once_the_delay_elapses() {
+        let mut t = Typematic::new(DELAY, INTERVAL);
+        t.key_down('a', false, 0);
+        assert_eq!(t.tick(400), Some('a'));
+        assert_eq!(t.tick(430), None);
+        assert_eq!(t.tick(450), Some('a'));
+        assert_eq!(t.tick(500), Some('a'));
+    }
+
+    #[test]
+    fn release_cancels_the_repeat() {
+        let mut t = Typematic::new(DELAY, INTERVAL);
+        t.key_down('a', false, 0);
+        t.key_up('a');
+        assert_eq!(t.tick(400), None);
+    }
+
+    #[test]
+    fn pressing_another_key_cancels_the_previous_repeat() {
+        let mut t = Typematic::new(DELAY, INTERVAL);
+        t.key_down('a', false, 0);
+        t.key_down('b', false, 100);
+        // 'a' would have been due at 400, but 'b' reset the clock.
+        assert_eq!(t.tick(400), None);
+        assert_eq!(t.tick(500), Some('b'));
+    }
+
+    #[test]
+    fn modifiers_never_repeat_and_do_not_disturb_a_held_key() {
+        let mut t = Typematic::new(DELAY, INTERVAL);
+        t.key_down('a', false, 0);
+        t.key_down('\u{0}', true, 100); // e.g. Shift, mid-hold
+        assert_eq!(t.tick(400), Some('a'), "Shift must not have cancelled 'a'");
+    }
+
+    #[test]
+    fn releasing_an_unrelated_key_does_not_cancel_the_held_one() {
+        let mut t = Typematic::new(DELAY, INTERVAL);
+        t.key_down('a', false, 0);
+        t.key_up('b');
+        assert_eq!(t.tick(400), Some('a'));
+    }
+}
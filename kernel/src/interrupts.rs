@@ -0,0 +1,25 @@
+//! Interrupt-adjacent glue for the USB stack.
+//!
+//! There is no IDT/APIC wiring yet, so `process_interrupt_messages` is a
+//! polling fallback: the main loop calls it every iteration and it drains
+//! whatever the xHC's event ring has ready.
+
+use crate::services::XHC;
+use crate::x64::InterruptGuard;
+
+/// Drain pending xHCI events, if a controller has been started.
+///
+/// Safe to call unconditionally from the main loop even before
+/// [`crate::services::XHC`] is populated. Runs under an [`InterruptGuard`]
+/// since draining the event ring isn't atomic with respect to the xHC
+/// appending to it; that matters once a real interrupt can preempt this
+/// polling fallback mid-drain.
+pub fn process_interrupt_messages() {
+    let _guard = InterruptGuard::new();
+    let xhc = unsafe { XHC.as_mut() };
+    if let Some(xhc) = xhc {
+        while xhc.has_unprocessed_events() {
+            xhc.process_events();
+        }
+    }
+}
@@ -5,24 +5,40 @@
 extern crate derive_more;
 
 use core::arch::asm;
+#[cfg(not(test))]
 use core::panic::PanicInfo;
 
+mod acpi;
 mod data_types;
 mod devices;
+mod error;
 #[macro_use]
 mod graphics;
+mod input;
+mod interrupts;
+mod mem;
 mod services;
+mod shell;
+mod x64;
 
+use error::KernelError;
 use graphics::Color;
 
-use crate::graphics::{Draw, Position};
+use crate::graphics::{Draw, Position, Rect};
 
 #[no_mangle]
 pub extern "sysv64" fn kernel_main(
     mmap: *const ::common_data::mmap::MemMap,
     fb: *mut ::common_data::graphics::FrameBuffer,
+    initrd: *const ::common_data::module::BootModule,
 ) {
-    unsafe { services::init(mmap, fb) };
+    // Before anything else (including `services::init`, which is the
+    // first thing in this kernel that can panic on a bad `BootInfo`):
+    // get a console working that doesn't depend on `services::init`
+    // having succeeded, so a panic here still shows up on screen
+    // instead of hanging with a dark screen.
+    unsafe { graphics::emergency::init_once(fb) };
+    unsafe { services::init(mmap, fb, initrd) };
     kprintln!("{}", HELLO_KERNEL);
     kprintln!(
         r"
@@ -39,13 +55,64 @@ ______                     _____ _____
     draw_something();
     kprintln!("Screen successfully rendered!");
 
-    scan_devices();
+    if let Err(e) = scan_devices() {
+        kprintln_colored!(Color::RED, "[ERROR]: device scan failed: {}", e);
+    }
     kprintln!("Devices successfully scanned!");
 
-    detect_usb();
+    match detect_usb() {
+        Ok(Some((mmio_base, xhc_device))) => {
+            if let Err(e) = start_xhc(mmio_base, xhc_device) {
+                kprintln_colored!(Color::RED, "[ERROR]: failed to start xHC: {}", e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => kprintln_colored!(Color::RED, "[ERROR]: USB detection failed: {}", e),
+    }
     inspect_memmap();
 
-    hlt!();
+    main_loop();
+}
+
+/// Park the CPU, draining xHCI events whenever the controller has any.
+///
+/// This is the polling fallback for interrupt processing: without IDT/APIC
+/// wiring yet, `hlt` only sleeps until the next interrupt, at which point
+/// we wake up and drain the event ring ourselves.
+fn main_loop() -> ! {
+    loop {
+        interrupts::process_interrupt_messages();
+        unsafe { services::LOOP_TICKS += 1 };
+        refresh_status_bar();
+        unsafe { asm!("hlt") };
+    }
+}
+
+/// Redraw the status bar and blit it onto the console's framebuffer.
+///
+/// There's no timer interrupt to gate this on a real one-second cadence
+/// yet, so it simply redraws every main-loop iteration.
+fn refresh_status_bar() {
+    use services::{CONSOLE, PCI_DEVICES, STATUS_BAR, XHC};
+
+    let stats = graphics::StatusBarStats {
+        loop_ticks: unsafe { services::LOOP_TICKS },
+        pci_device_count: unsafe { PCI_DEVICES.count() },
+        xhci_events_processed: unsafe { XHC.as_ref().map_or(0, |xhc| xhc.events_processed()) },
+    };
+
+    let bar = unsafe { STATUS_BAR.as_mut().unwrap() };
+    bar.render(&stats);
+
+    let console = unsafe { CONSOLE.as_mut().unwrap() };
+    let rect = bar.rect();
+    for y in rect.scanlines() {
+        for x in rect.origin.x..rect.lower_right().x {
+            let p = Position::new(x, y);
+            let color = bar.layer_mut().pixel_at(p).unwrap_or(Color::BLACK);
+            console.drawer.draw_pixel(p, color);
+        }
+    }
 }
 
 const HELLO_KERNEL: &str = "Hello, Kernel! This is OS kernel crafted with Rust. Have fun and I wish you learn much during implementing this. Good luck!";
@@ -53,56 +120,58 @@ const HELLO_KERNEL: &str = "Hello, Kernel! This is OS kernel crafted with Rust.
 fn draw_something() {
     use services::CONSOLE;
     let console = unsafe { CONSOLE.as_mut().unwrap() };
-    console
-        .drawer
-        .fill_rect(Position::new(0, 500), Position::new(100, 600), Color::GREEN);
     console.drawer.fill_rect(
-        Position::new(100, 500),
-        Position::new(800, 600),
+        Rect::from_corners(Position::new(0, 500), Position::new(100, 600)),
+        Color::GREEN,
+    );
+    console.drawer.fill_rect(
+        Rect::from_corners(Position::new(100, 500), Position::new(800, 600)),
         Color::BLACK,
     );
-    console
-        .drawer
-        .draw_rect(Position::new(10, 510), Position::new(90, 590), Color::WHITE);
+    console.drawer.draw_rect(
+        Rect::from_corners(Position::new(10, 510), Position::new(90, 590)),
+        Color::WHITE,
+    );
 }
 
-fn scan_devices() {
+fn scan_devices() -> Result<(), KernelError> {
     use services::PCI_DEVICES;
     let pci_devices = unsafe { &mut PCI_DEVICES };
-    if let Err(e) = pci_devices.scan_all_bus() {
-        kprintln!("[WARN]: {:?}", e);
-    }
+    pci_devices.scan_all_bus()?;
 
     kprintln!();
     kprintln!("Detected devices:");
     for (i, device) in pci_devices.iter().flatten().enumerate() {
-        kprintln!(
-            "[{}] {:02}.{:02}.{:02}: vendor={:#06x}, class={:#010x}, header={:#04x}",
-            i,
-            device.bus(),
-            device.device_number(),
-            device.function(),
-            device.vendor_id().as_raw(),
-            device.class_code().as_raw(),
-            device.header_type().as_raw(),
-        )
+        kprintln!("[{}] {}", i, device);
     }
     pci_devices.reset();
+    Ok(())
 }
 
-fn detect_usb() {
+/// Looks for a usable xHC, resolving its BAR0 up front so a device with
+/// a BAR we can't use (neither Memory32 nor Memory64) is treated the
+/// same as "no USB controller" instead of surfacing later as a separate
+/// failure in [`start_xhc`]. A bad BAR on one device is logged and
+/// skipped rather than propagated -- only a failure to scan the bus at
+/// all is worth aborting detection over.
+fn detect_usb() -> Result<Option<(u64, devices::pci::PciDevice)>, KernelError> {
     use services::PCI_DEVICES;
     let pci_devices = unsafe { &mut PCI_DEVICES };
-    if let Err(e) = pci_devices.scan_all_bus() {
-        kprintln!("[WARN]: {:?}", e);
-    }
+    pci_devices.scan_all_bus()?;
 
     let mut usb = None;
     for device in pci_devices.iter().flatten() {
         if device.class_code().is_usb() {
-            kprintln!("USB detected!: {:?}", device);
-            kprintln!("MMIO: {:?}", device.bar(0));
-            usb.insert(*device);
+            kprintln!("USB detected!: {}", device);
+            match device.try_bar(0) {
+                Ok(bar) => {
+                    usb.insert((bar.address(), *device));
+                }
+                Err(e) => {
+                    kprintln_colored!(Color::RED, "[WARN]: {} has no usable BAR0 ({:?}), skipping.", device, e);
+                    continue;
+                }
+            };
             if device.vendor_id().is_intel() {
                 break;
             }
@@ -110,10 +179,22 @@ fn detect_usb() {
     }
 
     if usb.is_none() {
-        kprintln!("USB unavailable...");
+        kprintln!("USB unavailable, continuing without it.");
     }
 
     pci_devices.reset();
+    Ok(usb)
+}
+
+/// Bring the xHC up and start polling its event ring from the main loop.
+fn start_xhc(mmio_base: u64, xhc_device: devices::pci::PciDevice) -> Result<(), KernelError> {
+    use devices::usb::HostController;
+    use services::XHC;
+
+    let xhc = unsafe { XHC.insert(HostController::new(mmio_base as usize)) };
+    xhc.run();
+    kprintln!("xHC started: state={:?}, device={}", xhc.state(), xhc_device);
+    Ok(())
 }
 
 fn inspect_memmap() {
@@ -134,9 +215,54 @@ fn inspect_memmap() {
     }
 }
 
+/// Dumps everything useful about the panic, then halts with interrupts
+/// off so a pending one can't fire into whatever's broken.
+///
+/// Writes straight to [`graphics::emergency`] instead of going through
+/// `kprintln!`/[`services::CONSOLE`]: if `services::init` itself is what
+/// panicked, or some other part of `services` is what's corrupted,
+/// `CONSOLE` is the last thing this should depend on. `emergency`'s only
+/// dependency is the framebuffer pointer handed to `kernel_main`, which
+/// doesn't change after boot.
+///
+/// There's no interrupt message queue/log to dump here (`interrupts.rs`
+/// only drains the xHC's own event ring on demand; it doesn't keep a
+/// history), so this covers registers and a backtrace instead.
+///
+/// Writes to COM1 first, unconditionally: [`devices::serial`] doesn't
+/// depend on the framebuffer at all, so it's the one channel that still
+/// works if `graphics::emergency::init_once` itself never ran (or if
+/// `fb` was bad enough that it's not safe to trust) -- a case
+/// `graphics::emergency`, which only draws to the framebuffer, can't cover.
+#[cfg(not(test))]
 #[panic_handler]
 fn panic(info: &PanicInfo) -> ! {
-    kprintln!("{}", info);
+    unsafe { x64::disable() };
+
+    use core::fmt::Write;
+
+    unsafe { devices::serial::init() };
+    let mut serial = devices::serial::Serial;
+
+    let regs = x64::diagnostics::Registers::capture();
+
+    let dump = |w: &mut dyn Write| {
+        let _ = writeln!(w, "{}", info);
+        let _ = writeln!(w, "rsp={:#018x} rbp={:#018x}", regs.rsp, regs.rbp);
+        let _ = writeln!(w, "cr2={:#018x} cr3={:#018x}", regs.cr2, regs.cr3);
+        let _ = writeln!(w, "backtrace:");
+        unsafe {
+            x64::diagnostics::walk_frames(regs.rbp, |addr| {
+                let _ = writeln!(w, "  {:#018x}", addr);
+            });
+        }
+    };
+
+    dump(&mut serial);
+    if let Some(console) = graphics::emergency::console_mut() {
+        dump(console);
+    }
+
     hlt!();
 }
 
@@ -0,0 +1,191 @@
+//! Fixed-size buffers aligned for hardware that takes a linear/physical
+//! pointer instead of a Rust reference (xHCI's DCBAA and its rings all
+//! want this): `PageAligned<N>` for a whole [`FRAME_SIZE`](super::frame_allocator::FRAME_SIZE)-aligned
+//! buffer, `Aligned64<N>` for the coarser alignment most xHCI data
+//! structures need.
+//!
+//! No USB allocator or DCBAA type draws from these yet -- there's no
+//! heap allocator in this kernel (same tradeoff as
+//! `frame_buffer::MAX_BACK_BUFFER_BYTES`), so whatever eventually wants
+//! one of these is expected to embed it in its own static the way
+//! `frame_buffer::BACK_BUFFER` and `status_bar::static_storage` already
+//! do, not allocate it from here.
+
+/// Declares an aligned fixed-size byte buffer wrapper.
+///
+/// `Index`/`IndexMut` index into the backing bytes directly;
+/// [`$name::as_bytes`]/[`$name::as_bytes_mut`] hand out the whole
+/// buffer as a slice for callers that want to `copy_from_slice` into
+/// it instead, and [`$name::base_addr`] reads its address back out as
+/// a `u64` for handing to hardware -- consolidating the ad-hoc
+/// `&buf[0] as *const u8 as u64` cast that pattern used to take at
+/// each call site.
+macro_rules! aligned_buffer {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident($align:literal);) => {
+        $(#[$meta])*
+        #[repr(align($align))]
+        $vis struct $name<const N: usize>(pub [u8; N]);
+
+        impl<const N: usize> $name<N> {
+            pub const fn new() -> Self {
+                Self([0; N])
+            }
+
+            /// Builds a buffer sized and aligned for `layout`.
+            ///
+            /// # Panics
+            /// If `N` is smaller than `layout.size()`, or this
+            /// wrapper's fixed `$align`-byte alignment is coarser than
+            /// `layout` actually needs -- callers size `N` (and pick
+            /// between [`PageAligned`]/[`Aligned64`]) from the type
+            /// they're about to place in here, so either failing means
+            /// the wrong wrapper was reached for.
+            pub fn for_layout(layout: ::core::alloc::Layout) -> Self {
+                assert!(
+                    layout.size() <= N,
+                    "buffer ({N} B) is smaller than the requested layout ({} B)",
+                    layout.size(),
+                );
+                assert!(
+                    layout.align() <= $align,
+                    "{}-byte alignment is insufficient for the requested layout's {}-byte alignment",
+                    $align,
+                    layout.align(),
+                );
+                Self::new()
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.0
+            }
+
+            pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+                &mut self.0
+            }
+
+            /// This buffer's starting address, for hardware that takes
+            /// a physical/linear pointer (DCBAA entries, a TRB ring's
+            /// base register) instead of a Rust reference. Valid only
+            /// as long as this kernel keeps its identity mapping (see
+            /// `x64::paging::init_identity_mapped`), same as every
+            /// other physical address floating around this tree.
+            pub fn base_addr(&self) -> u64 {
+                self.0.as_ptr() as u64
+            }
+        }
+
+        impl<const N: usize> Default for $name<N> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<const N: usize> ::core::ops::Index<usize> for $name<N> {
+            type Output = u8;
+
+            fn index(&self, i: usize) -> &u8 {
+                &self.0[i]
+            }
+        }
+
+        impl<const N: usize> ::core::ops::IndexMut<usize> for $name<N> {
+            fn index_mut(&mut self, i: usize) -> &mut u8 {
+                &mut self.0[i]
+            }
+        }
+    };
+}
+
+aligned_buffer! {
+    /// Aligned to a full page, for structures the xHC addresses by a
+    /// page-granular pointer (e.g. the DCBAA itself, USB 2.0 spec
+    /// xHCI 1.1 §6.1).
+    pub struct PageAligned(4096);
+}
+
+aligned_buffer! {
+    /// Aligned to 64 bytes, the coarsest alignment xHCI's device/input
+    /// contexts and transfer rings need (xHCI 1.1 §6.2.1, §6.4).
+    pub struct Aligned64(64);
+}
+
+aligned_buffer! {
+    /// Aligned to 128 bytes, for contexts/tables whose alignment
+    /// requirement falls between [`Aligned64`] and [`Aligned256`].
+    pub struct Aligned128(128);
+}
+
+aligned_buffer! {
+    /// Aligned to 256 bytes, for contexts/tables whose alignment
+    /// requirement falls between [`Aligned128`] and [`PageAligned`].
+    pub struct Aligned256(256);
+}
+
+// A single `Align<const N: usize, T>` that replaces all of the above
+// would need `#[repr(align(N))]` with `N` a const generic, which isn't
+// expressible in Rust today -- `repr(align(..))` only accepts a literal.
+// The usual workaround is a per-power-of-two marker-type trait (a
+// `trait Alignment` with one zero-sized `#[repr(align(N))]` impl per N,
+// composed via `#[repr(C)] struct Align<A: Alignment, T> { _align: [A; 0], value: T }`)
+// -- but that trades this file's four straight-line repr(align) structs
+// for a type-level dispatch table, for wrappers this kernel only ever
+// needs at four fixed alignments. Not worth it until a fifth shows up.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_aligned_buffers_start_on_a_page_boundary() {
+        let buf: PageAligned<4096> = PageAligned::new();
+        assert_eq!(buf.base_addr() % 4096, 0);
+    }
+
+    #[test]
+    fn aligned64_buffers_start_on_a_64_byte_boundary() {
+        let buf: Aligned64<64> = Aligned64::new();
+        assert_eq!(buf.base_addr() % 64, 0);
+    }
+
+    #[test]
+    fn aligned128_buffers_start_on_a_128_byte_boundary() {
+        let buf: Aligned128<128> = Aligned128::new();
+        assert_eq!(buf.base_addr() % 128, 0);
+    }
+
+    #[test]
+    fn aligned256_buffers_start_on_a_256_byte_boundary() {
+        let buf: Aligned256<256> = Aligned256::new();
+        assert_eq!(buf.base_addr() % 256, 0);
+    }
+
+    #[test]
+    fn index_and_as_bytes_see_the_same_backing_storage() {
+        let mut buf: Aligned64<64> = Aligned64::new();
+        buf[3] = 0xAB;
+        assert_eq!(buf.as_bytes()[3], 0xAB);
+        buf.as_bytes_mut()[4] = 0xCD;
+        assert_eq!(buf[4], 0xCD);
+    }
+
+    #[test]
+    fn for_layout_accepts_a_layout_that_fits() {
+        let layout = ::core::alloc::Layout::from_size_align(32, 64).unwrap();
+        let buf: Aligned64<64> = Aligned64::for_layout(layout);
+        assert_eq!(buf.base_addr() % 64, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "smaller than the requested layout")]
+    fn for_layout_rejects_a_layout_too_big_to_fit() {
+        let layout = ::core::alloc::Layout::from_size_align(128, 64).unwrap();
+        let _: Aligned64<64> = Aligned64::for_layout(layout);
+    }
+
+    #[test]
+    #[should_panic(expected = "alignment is insufficient")]
+    fn for_layout_rejects_a_layout_that_needs_finer_alignment_than_the_wrapper_guarantees() {
+        let layout = ::core::alloc::Layout::from_size_align(16, 4096).unwrap();
+        let _: Aligned64<64> = Aligned64::for_layout(layout);
+    }
+}
@@ -0,0 +1,186 @@
+use common_data::mmap::MemMap;
+
+/// Frames are this many bytes, matching the standard x86_64 page size
+/// everything else in this kernel (paging, the UEFI memory map) already
+/// assumes.
+pub const FRAME_SIZE: u64 = 4096;
+
+/// Caps how much physical memory this allocator can track, absent a
+/// heap allocator to size the bitmap dynamically from the memory map's
+/// actual extent (same tradeoff as `frame_buffer::MAX_BACK_BUFFER_BYTES`
+/// and `status_bar::MAX_WIDTH`). Comfortably covers a QEMU dev VM;
+/// any frame at or above this ceiling is simply never marked free.
+const MAX_TRACKED_BYTES: u64 = 512 * 1024 * 1024;
+const MAX_FRAMES: usize = (MAX_TRACKED_BYTES / FRAME_SIZE) as usize;
+const BITMAP_WORDS: usize = MAX_FRAMES / u64::BITS as usize;
+
+/// A bitmap over physical frames, one bit per [`FRAME_SIZE`] frame, set
+/// when free. Single- and multi-frame allocation are both a linear scan
+/// for a run of free bits, which is fine at boot-time allocation rates
+/// and doesn't need a free list threaded through the frames themselves.
+pub struct FrameAllocator {
+    bitmap: [u64; BITMAP_WORDS],
+}
+
+impl FrameAllocator {
+    /// Marks every frame [`MemMap::merged_free_regions`] reports as
+    /// free; everything else (MMIO, reserved ranges, firmware-owned
+    /// memory, and anything at or above [`MAX_TRACKED_BYTES`]) stays
+    /// marked used.
+    ///
+    /// Free regions are trimmed to whole frames: a region whose start
+    /// or end doesn't fall on a frame boundary gives up that partial
+    /// frame rather than rounding it into use.
+    pub fn from_mem_map(mmap: &MemMap) -> Self {
+        let mut allocator = Self {
+            bitmap: [0; BITMAP_WORDS],
+        };
+        for region in mmap.merged_free_regions() {
+            let start_frame = region.phys_start.div_ceil(FRAME_SIZE);
+            let end_frame = region.phys_end / FRAME_SIZE;
+            for frame in start_frame..end_frame {
+                allocator.mark_free(frame as usize);
+            }
+        }
+        allocator
+    }
+
+    fn mark_free(&mut self, frame: usize) {
+        if frame >= MAX_FRAMES {
+            return;
+        }
+        self.bitmap[frame / 64] |= 1 << (frame % 64);
+    }
+
+    fn mark_used(&mut self, frame: usize) {
+        if frame >= MAX_FRAMES {
+            return;
+        }
+        self.bitmap[frame / 64] &= !(1 << (frame % 64));
+    }
+
+    fn is_free(&self, frame: usize) -> bool {
+        frame < MAX_FRAMES && self.bitmap[frame / 64] & (1 << (frame % 64)) != 0
+    }
+
+    /// How many tracked frames are currently free, for status reporting.
+    pub fn free_frame_count(&self) -> usize {
+        self.bitmap.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Hands out one free frame, or `None` if every tracked frame is in use.
+    pub fn alloc_frame(&mut self) -> Option<u64> {
+        self.alloc_frames(1)
+    }
+
+    /// Hands out `n` physically contiguous free frames, returning the
+    /// first one's physical address.
+    pub fn alloc_frames(&mut self, n: usize) -> Option<u64> {
+        if n == 0 {
+            return None;
+        }
+        let mut run_start = None;
+        let mut run_len = 0;
+        for frame in 0..MAX_FRAMES {
+            if self.is_free(frame) {
+                let run_start = *run_start.get_or_insert(frame);
+                run_len += 1;
+                if run_len == n {
+                    for f in run_start..run_start + n {
+                        self.mark_used(f);
+                    }
+                    return Some(run_start as u64 * FRAME_SIZE);
+                }
+            } else {
+                run_start = None;
+                run_len = 0;
+            }
+        }
+        None
+    }
+
+    /// Returns one frame to the free pool.
+    ///
+    /// # Panics
+    /// If `addr` isn't frame-aligned.
+    pub fn free_frame(&mut self, addr: u64) {
+        self.free_frames(addr, 1);
+    }
+
+    /// Returns `n` frames starting at `addr` to the free pool.
+    ///
+    /// # Panics
+    /// If `addr` isn't frame-aligned.
+    pub fn free_frames(&mut self, addr: u64, n: usize) {
+        assert_eq!(addr % FRAME_SIZE, 0, "frame address {:#x} is not frame-aligned", addr);
+        let start = (addr / FRAME_SIZE) as usize;
+        for frame in start..start + n {
+            self.mark_free(frame);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn desc(phys_start: u64, phys_end: u64) -> common_data::mmap::MemDesc {
+        common_data::mmap::MemDesc {
+            ty: 0,
+            phys_start,
+            phys_end,
+            offset: 0,
+            attribute: 0,
+        }
+    }
+
+    #[test]
+    fn tracks_only_the_regions_the_memory_map_reports_as_free() {
+        let descs = [desc(0, FRAME_SIZE * 4), desc(FRAME_SIZE * 10, FRAME_SIZE * 12)];
+        let allocator = FrameAllocator::from_mem_map(&MemMap::from_slice(&descs));
+        assert_eq!(allocator.free_frame_count(), 6);
+    }
+
+    #[test]
+    fn trims_partial_frames_at_region_edges() {
+        let descs = [desc(100, FRAME_SIZE * 2 + 100)];
+        let allocator = FrameAllocator::from_mem_map(&MemMap::from_slice(&descs));
+        // Only the one whole frame in between the unaligned edges counts.
+        assert_eq!(allocator.free_frame_count(), 1);
+    }
+
+    #[test]
+    fn alloc_frame_hands_out_distinct_addresses_and_drains_the_pool() {
+        let descs = [desc(0, FRAME_SIZE * 2)];
+        let mut allocator = FrameAllocator::from_mem_map(&MemMap::from_slice(&descs));
+        let a = allocator.alloc_frame().unwrap();
+        let b = allocator.alloc_frame().unwrap();
+        assert_ne!(a, b);
+        assert_eq!(allocator.alloc_frame(), None);
+    }
+
+    #[test]
+    fn alloc_frames_requires_one_contiguous_run() {
+        let descs = [desc(0, FRAME_SIZE), desc(FRAME_SIZE * 3, FRAME_SIZE * 5)];
+        let mut allocator = FrameAllocator::from_mem_map(&MemMap::from_slice(&descs));
+        // Frame 0 is isolated, so the first run of 2 free frames starts at frame 3.
+        assert_eq!(allocator.alloc_frames(2), Some(FRAME_SIZE * 3));
+    }
+
+    #[test]
+    fn freed_frames_become_available_again() {
+        let descs = [desc(0, FRAME_SIZE)];
+        let mut allocator = FrameAllocator::from_mem_map(&MemMap::from_slice(&descs));
+        let addr = allocator.alloc_frame().unwrap();
+        assert_eq!(allocator.alloc_frame(), None);
+        allocator.free_frame(addr);
+        assert_eq!(allocator.alloc_frame(), Some(addr));
+    }
+
+    #[test]
+    #[should_panic(expected = "not frame-aligned")]
+    fn free_frame_rejects_unaligned_addresses() {
+        let mut allocator = FrameAllocator::from_mem_map(&MemMap::from_slice(&[]));
+        allocator.free_frame(1);
+    }
+}
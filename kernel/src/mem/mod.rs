@@ -0,0 +1,12 @@
+//! Physical memory management. [`FrameAllocator`] is the first
+//! general-purpose consumer of the `MemMap` the loader hands off;
+//! page tables and the USB pools are expected to eventually draw their
+//! backing memory from it instead of each carving out their own static.
+pub mod align;
+pub mod frame_allocator;
+
+// Not wired to a caller yet -- nothing outside align.rs's own tests
+// needs an aligned buffer of a fixed size yet.
+#[allow(unused_imports)]
+pub use align::{Aligned128, Aligned256, Aligned64, PageAligned};
+pub use frame_allocator::FrameAllocator;
@@ -1,16 +1,44 @@
 use super::logging;
 use crate::devices::pci::PciDeviceService;
+use crate::devices::rtc::{self, DateTime};
+use crate::devices::usb::HostController;
 use crate::graphics::console::Console;
-use crate::graphics::FrameBuffer;
+use crate::graphics::{status_bar, FrameBufDrawer, FrameBuffer, StatusBar};
+use crate::mem::FrameAllocator;
 
 // Place FrameBuffer in global field is valid because FrameBuffer itself
 // does not contains the content of frame buffer and we can assume that
 // the exact location of frame buffer does not change from its original
 // even if we move the location of FrameBuffer.
 pub(crate) static mut FRAME_BUFFER: Option<FrameBuffer> = None;
-pub(crate) static mut CONSOLE: Option<Console> = None;
+pub(crate) static mut CONSOLE: Option<Console<FrameBufDrawer<'static>>> = None;
 pub(crate) static mut PCI_DEVICES: PciDeviceService = PciDeviceService::new();
 pub(crate) static mut MMAP: Option<::common_data::mmap::MemMap> = None;
+pub(crate) static mut FRAME_ALLOCATOR: Option<FrameAllocator> = None;
+/// The initrd handed off by the loader. Nothing mounts or parses it yet —
+/// that's for whatever filesystem/archive format ends up inside it.
+pub(crate) static mut INITRD: Option<::common_data::module::BootModule> = None;
+pub(crate) static mut XHC: Option<HostController> = None;
+pub(crate) static mut STATUS_BAR: Option<StatusBar> = None;
+/// Main-loop iterations since boot; stands in for uptime until a
+/// timer/PIT driver exists to report real wall-clock time.
+pub(crate) static mut LOOP_TICKS: u64 = 0;
+/// Wall-clock time read from the RTC at boot; see [`super::time::boot_time`].
+pub(crate) static mut BOOT_TIME: Option<DateTime> = None;
+
+/// Whether [`init`] has already run. This only guards double-`init`,
+/// not use-before-`init`: every global above is read through bare
+/// `unsafe { FOO.as_mut().unwrap() }` at dozens of call sites across
+/// this tree, and turning each of those into a checked accessor is a
+/// much bigger refactor than this flag. [`initialized`] lets a caller
+/// that genuinely isn't sure ask first, rather than panicking blind on
+/// an `unwrap`.
+static mut INITIALIZED: bool = false;
+
+/// Whether [`init`] has run yet.
+pub fn initialized() -> bool {
+    unsafe { INITIALIZED }
+}
 
 /// # Safety
 /// This function is expected to be called at the very start of the entry of the kernel.
@@ -18,12 +46,38 @@ pub(crate) static mut MMAP: Option<::common_data::mmap::MemMap> = None;
 pub unsafe fn init(
     mmap: *const ::common_data::mmap::MemMap,
     fb: *mut ::common_data::graphics::FrameBuffer,
+    initrd: *const ::common_data::module::BootModule,
 ) {
+    assert!(!INITIALIZED, "services::init called twice");
+
+    // Set up our own GDT and TSS before anything else: the TSS's IST1
+    // stack is what will let a double-fault be handled (and inspected)
+    // even if the faulting context's own stack is blown.
+    crate::x64::gdt::init();
+
     // screen services
     let _ = FRAME_BUFFER.insert(fb.read().into());
+
+    // Take over paging from the firmware before anything else touches
+    // the framebuffer, keeping identity mapping so every physical
+    // address already floating around (PCI BARs, this framebuffer's own
+    // base, ...) stays valid.
+    let fb_ref = FRAME_BUFFER.as_ref().unwrap();
+    crate::x64::paging::init_identity_mapped(fb_ref.base_addr(), fb_ref.size());
+
     let console = Console::from_frame_buffer(FRAME_BUFFER.as_mut().unwrap());
     logging::logger_init(console);
+    let width = FRAME_BUFFER.as_ref().unwrap().resolution().0;
+    let _ = STATUS_BAR.insert(StatusBar::new(width, status_bar::static_storage()));
 
     // memory map
-    let _ = MMAP.insert(mmap.read());
+    let mmap = MMAP.insert(mmap.read());
+    let _ = FRAME_ALLOCATOR.insert(FrameAllocator::from_mem_map(mmap));
+
+    // boot modules
+    let _ = INITRD.insert(initrd.read());
+
+    let _ = BOOT_TIME.insert(rtc::now());
+
+    INITIALIZED = true;
 }
@@ -1,10 +1,10 @@
 use core::fmt::Write;
 use log::Log;
 
-use super::CONSOLE;
-use crate::graphics::{Color, Console};
+use super::{CONSOLE, LOOP_TICKS};
+use crate::graphics::{Color, Console, FrameBufDrawer};
 
-pub(super) fn logger_init(mut console: Console<'static>) {
+pub(super) fn logger_init(mut console: Console<FrameBufDrawer<'static>>) {
     console.set_background_color(Color::BLUE);
     console.set_output_color(Color::WHITE);
     console.fill_screen();
@@ -13,6 +13,137 @@ pub(super) fn logger_init(mut console: Console<'static>) {
     log::set_max_level(log::LevelFilter::Info);
 }
 
+/// Whether [`KernelLogger`] prefixes each line with [`super::time::wall_now`].
+/// Off by default, same as every other opt-in diagnostic in this tree
+/// (see [`super::globals::LOOP_TICKS`]'s status-bar-only-by-default use).
+static mut TIMESTAMP_PREFIX: bool = false;
+
+/// Turns the wall-clock timestamp prefix on log lines on or off, e.g.
+/// from the shell's `date --log-timestamps` (see
+/// [`crate::shell::commands`]).
+pub(crate) fn set_timestamp_prefix(enabled: bool) {
+    unsafe { TIMESTAMP_PREFIX = enabled };
+}
+
+/// How many [`LOOP_TICKS`] a run of identical log lines collapses into
+/// one "repeated N times" line before it's allowed to print on its own
+/// again. Arbitrary -- long enough that a device retrying the same
+/// failure hundreds of times a second collapses to essentially nothing,
+/// short enough that a real ongoing problem still resurfaces instead of
+/// going silent for the rest of the boot.
+const RATE_LIMIT_WINDOW_TICKS: u64 = 256;
+
+/// Longest prefix of a formatted message [`MessageKey::of`] hashes.
+/// There's no heap in this kernel to format a message into a `String`
+/// first, so this formats into a fixed stack buffer instead; a message
+/// longer than this still dedups correctly on its first
+/// [`Self::CAPACITY`] bytes, it just can't distinguish two long messages
+/// that only differ past that point.
+struct FixedBuf<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> FixedBuf<N> {
+    const CAPACITY: usize = N;
+
+    fn new() -> Self {
+        Self { bytes: [0; N], len: 0 }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl<const N: usize> Write for FixedBuf<N> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let room = Self::CAPACITY - self.len;
+        let take = room.min(s.len());
+        self.bytes[self.len..self.len + take].copy_from_slice(&s.as_bytes()[..take]);
+        self.len += take;
+        Ok(())
+    }
+}
+
+/// The 64-bit FNV-1a hash (see
+/// <http://www.isthe.com/chongo/tech/comp/fnv/>), picked for
+/// [`MessageKey`] because it needs no allocation and no lookup table --
+/// just a multiply-and-xor per byte.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, &b| (hash ^ b as u64).wrapping_mul(PRIME))
+}
+
+/// Identifies "the same log line" for [`RateLimiter`]. The target and
+/// level are cheap to compare directly; the message itself is hashed
+/// rather than kept verbatim, since there's nowhere to own a copy of it
+/// without a heap.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct MessageKey {
+    target_hash: u64,
+    level: log::Level,
+    message_hash: u64,
+}
+
+impl MessageKey {
+    fn of(record: &log::Record) -> Self {
+        let mut buf: FixedBuf<120> = FixedBuf::new();
+        let _ = write!(buf, "{}", record.args());
+        Self {
+            target_hash: fnv1a(record.target().as_bytes()),
+            level: record.level(),
+            message_hash: fnv1a(buf.as_bytes()),
+        }
+    }
+}
+
+/// What [`RateLimiter::check`] decided to do with a log line.
+enum RateLimitVerdict {
+    /// Print the line as usual.
+    Emit,
+    /// The same line as last time, still inside the window: don't print
+    /// it at all, just count it.
+    Suppress,
+    /// A different line than last time (or the window closed): print a
+    /// "repeated N times" notice for what got suppressed, then this line.
+    EmitAfterRepeats(u32),
+}
+
+/// Collapses a run of identical log lines within
+/// [`RATE_LIMIT_WINDOW_TICKS`] of each other into a single "last message
+/// repeated N times" line -- a misbehaving device retrying the same
+/// failed command can otherwise log it hundreds of times a second and
+/// scroll away everything useful.
+struct RateLimiter {
+    last: Option<MessageKey>,
+    window_start: u64,
+    repeats: u32,
+}
+
+impl RateLimiter {
+    fn check(&mut self, key: MessageKey, now: u64) -> RateLimitVerdict {
+        if self.last == Some(key) && now.wrapping_sub(self.window_start) < RATE_LIMIT_WINDOW_TICKS {
+            self.repeats += 1;
+            return RateLimitVerdict::Suppress;
+        }
+
+        let repeats = self.repeats;
+        self.last = Some(key);
+        self.window_start = now;
+        self.repeats = 0;
+
+        if repeats > 0 {
+            RateLimitVerdict::EmitAfterRepeats(repeats)
+        } else {
+            RateLimitVerdict::Emit
+        }
+    }
+}
+
+static mut RATE_LIMITER: RateLimiter = RateLimiter { last: None, window_start: 0, repeats: 0 };
+
 struct KernelLogger;
 
 impl Log for KernelLogger {
@@ -21,8 +152,46 @@ impl Log for KernelLogger {
     }
 
     fn log(&self, record: &log::Record) {
+        #[cfg(not(feature = "unthrottled-logs"))]
+        let repeated = match unsafe { RATE_LIMITER.check(MessageKey::of(record), LOOP_TICKS) } {
+            RateLimitVerdict::Suppress => return,
+            RateLimitVerdict::Emit => None,
+            RateLimitVerdict::EmitAfterRepeats(n) => Some(n),
+        };
+        #[cfg(feature = "unthrottled-logs")]
+        let repeated: Option<u32> = None;
+
         let console = unsafe { CONSOLE.as_mut().unwrap() };
-        writeln!(console, "{}: {}", record.level(), record.args()).unwrap();
+        if let Some(n) = repeated {
+            writeln!(console, "\x1b[90m(last message repeated {} times)\x1b[0m", n).unwrap();
+        }
+
+        let color_code = match record.level() {
+            log::Level::Error => "31",
+            log::Level::Warn => "33",
+            log::Level::Info => "32",
+            log::Level::Debug | log::Level::Trace => "36",
+        };
+        if unsafe { TIMESTAMP_PREFIX } {
+            writeln!(
+                console,
+                "\x1b[{}m[{}] {}: {}\x1b[0m",
+                color_code,
+                super::time::wall_now(),
+                record.level(),
+                record.args()
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                console,
+                "\x1b[{}m{}: {}\x1b[0m",
+                color_code,
+                record.level(),
+                record.args()
+            )
+            .unwrap();
+        }
     }
 
     fn flush(&self) {}
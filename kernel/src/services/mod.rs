@@ -1,4 +1,12 @@
 mod globals;
 mod logging;
+mod time;
 
 pub use globals::*;
+pub(crate) use logging::set_timestamp_prefix;
+pub use time::wall_now;
+
+// Not wired to a caller yet -- nothing outside `services::globals::init`
+// (which stores it, but doesn't read it back) needs the boot timestamp.
+#[allow(unused_imports)]
+pub use time::boot_time;
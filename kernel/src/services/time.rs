@@ -0,0 +1,23 @@
+//! Wall-clock time, cheap enough to call on every log line.
+//!
+//! There's no calibrated timer in this kernel to turn `LOOP_TICKS` into
+//! a real duration yet (see its doc comment in [`super::globals`]), so
+//! [`wall_now`] can't derive from [`boot_time`] plus elapsed uptime the
+//! way a kernel with one would -- it re-reads the RTC on every call
+//! instead, same cost as calling [`crate::devices::rtc::now`] directly.
+//! Once a timer exists, this is where the boot-time-plus-elapsed math
+//! belongs; `boot_time` is already cached from [`super::init`] for it.
+
+use crate::devices::rtc::{self, DateTime};
+
+use super::globals::BOOT_TIME;
+
+/// The current wall-clock time.
+pub fn wall_now() -> DateTime {
+    rtc::now()
+}
+
+/// The wall-clock time read at boot, cached by [`super::init`].
+pub fn boot_time() -> Option<DateTime> {
+    unsafe { BOOT_TIME }
+}
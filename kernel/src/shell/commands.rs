@@ -0,0 +1,286 @@
+//! Built-in shell commands. Each one is a plain function over `&[&str]`
+//! args so adding a new command is "write a function, add it to
+//! [`COMMANDS`]" rather than touching a dispatch `match`.
+use crate::graphics::Color;
+use crate::services::{CONSOLE, FRAME_ALLOCATOR, FRAME_BUFFER, MMAP, PCI_DEVICES, XHC};
+use crate::{kprint, kprintln, kprintln_colored};
+
+pub struct Command {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub run: fn(&[&str]),
+}
+
+pub static COMMANDS: &[Command] = &[
+    Command {
+        name: "lspci",
+        help: "list PCI devices found on the last bus scan (-v for a full header dump, --rescan to re-scan first)",
+        run: lspci,
+    },
+    Command {
+        name: "lsusb",
+        help: "show xHC status (no per-port device listing yet)",
+        run: lsusb,
+    },
+    Command {
+        name: "usbstat",
+        help: "show xHC event/error counters",
+        run: usbstat,
+    },
+    Command {
+        name: "mem",
+        help: "show frame allocator stats",
+        run: mem,
+    },
+    Command {
+        name: "mmap",
+        help: "dump the boot memory map",
+        run: mmap,
+    },
+    Command {
+        name: "clear",
+        help: "clear the screen",
+        run: clear,
+    },
+    Command {
+        name: "poke",
+        help: "poke <addr>: hexdump 64 bytes starting at addr",
+        run: poke,
+    },
+    Command {
+        name: "screenshot",
+        help: "dump the framebuffer as a PPM (P3) over COM1 serial",
+        run: screenshot,
+    },
+    Command {
+        name: "date",
+        help: "print the current wall-clock time (--log-timestamps [off] to toggle it as a log-line prefix)",
+        run: date,
+    },
+    Command {
+        name: "help",
+        help: "list commands",
+        run: help,
+    },
+];
+
+fn help(_args: &[&str]) {
+    for command in COMMANDS {
+        kprintln!("{:<8} {}", command.name, command.help);
+    }
+}
+
+fn lspci(args: &[&str]) {
+    let verbose = args.contains(&"-v");
+
+    if args.contains(&"--rescan") {
+        let pci_devices = unsafe { &mut PCI_DEVICES };
+        match pci_devices.rescan() {
+            Ok(report) => {
+                for device in report.removed().iter().flatten() {
+                    kprintln_colored!(Color::RED, "- {}", device);
+                }
+                for device in report.added().iter().flatten() {
+                    kprintln_colored!(Color::GREEN, "+ {}", device);
+                }
+                if report.added().is_empty() && report.removed().is_empty() {
+                    kprintln!("no change");
+                }
+            }
+            Err(e) => kprintln_colored!(Color::RED, "lspci --rescan failed: {:?}", e),
+        }
+    }
+
+    let pci_devices = unsafe { &PCI_DEVICES };
+    for (i, device) in pci_devices.iter().flatten().enumerate() {
+        if verbose {
+            kprintln!("[{}] {}", i, device.dump());
+        } else {
+            kprintln!("[{}] {}", i, device);
+        }
+    }
+}
+
+/// There's no USB `DeviceManager` with a per-port device listing in
+/// this kernel yet (the request that asked for this command assumed
+/// one), so this reports what the xHC itself already tracks instead.
+fn lsusb(_args: &[&str]) {
+    match unsafe { XHC.as_ref() } {
+        Some(xhc) => kprintln!(
+            "xHC: state={:?}, events_processed={}",
+            xhc.state(),
+            xhc.events_processed()
+        ),
+        None => kprintln!("no xHC started"),
+    }
+}
+
+/// Counters only `process_events` currently increments (`events_processed`)
+/// read back as non-zero; the rest (`command_errors`, `transfer_errors`,
+/// `port_resets`, `enumerated_devices`) always print 0 until this tree
+/// has a command ring, stall recovery, and an enumeration path to
+/// increment them from -- see [`crate::devices::usb::xhci::Stats`].
+fn usbstat(_args: &[&str]) {
+    match unsafe { XHC.as_ref() } {
+        Some(xhc) => {
+            let stats = xhc.stats();
+            kprintln!("events_processed:   {}", stats.events_processed);
+            kprintln!("command_errors:     {}", stats.command_errors);
+            kprintln!("transfer_errors:    {}", stats.transfer_errors);
+            kprintln!("port_resets:        {}", stats.port_resets);
+            kprintln!("enumerated_devices: {}", stats.enumerated_devices);
+        }
+        None => kprintln!("no xHC started"),
+    }
+}
+
+fn mem(_args: &[&str]) {
+    match unsafe { FRAME_ALLOCATOR.as_ref() } {
+        Some(allocator) => {
+            kprintln!("frame allocator: {} free 4KB frames", allocator.free_frame_count());
+        }
+        None => kprintln!("frame allocator not initialized"),
+    }
+}
+
+fn mmap(_args: &[&str]) {
+    let mmap = unsafe { MMAP.as_ref() };
+    let Some(mmap) = mmap else {
+        kprintln!("no memory map");
+        return;
+    };
+    kprintln!("{:?}", mmap);
+    kprintln!("index, type, phys_start...phys_end,   offset,  att");
+    for (i, desc) in mmap.as_slice().iter().enumerate() {
+        kprintln!(
+            "{:02},    {:#03x}, {:#010x}..{:#010x}, {:#08x}, {:#08x}",
+            i,
+            desc.ty,
+            desc.phys_start,
+            desc.phys_end,
+            desc.offset,
+            desc.attribute
+        );
+    }
+}
+
+fn clear(_args: &[&str]) {
+    let console = unsafe { CONSOLE.as_mut().unwrap() };
+    console.fill_screen();
+}
+
+/// Parses a hex address (with or without a leading `0x`), rejecting
+/// `0` and anything not 8-byte aligned; there's no page table
+/// introspection in this kernel yet to check the address is actually
+/// mapped, so alignment and non-nullness are the only guard this has.
+fn parse_poke_addr(s: &str) -> Result<u64, &'static str> {
+    let digits = s.strip_prefix("0x").unwrap_or(s);
+    let addr = u64::from_str_radix(digits, 16).map_err(|_| "not a hex address")?;
+    if addr == 0 {
+        return Err("refusing to read address 0");
+    }
+    if addr % 8 != 0 {
+        return Err("address must be 8-byte aligned");
+    }
+    Ok(addr)
+}
+
+fn poke(args: &[&str]) {
+    let Some(&addr_arg) = args.first() else {
+        kprintln_colored!(Color::RED, "usage: poke <addr>");
+        return;
+    };
+    let addr = match parse_poke_addr(addr_arg) {
+        Ok(addr) => addr,
+        Err(e) => {
+            kprintln_colored!(Color::RED, "poke: {}", e);
+            return;
+        }
+    };
+
+    const LEN: usize = 64;
+    let bytes = unsafe { core::slice::from_raw_parts(addr as *const u8, LEN) };
+    for (row, chunk) in bytes.chunks(16).enumerate() {
+        kprint!("{:#010x}: ", addr + (row * 16) as u64);
+        for byte in chunk {
+            kprint!("{:02x} ", byte);
+        }
+        kprintln!();
+    }
+}
+
+/// Dumps the PPM over COM1 ([`crate::devices::serial`]) rather than
+/// the on-screen console, so writing it never draws into the
+/// framebuffer being read.
+fn screenshot(_args: &[&str]) {
+    let Some(fb) = (unsafe { FRAME_BUFFER.as_mut() }) else {
+        kprintln!("no framebuffer");
+        return;
+    };
+    unsafe { crate::devices::serial::init() };
+    let mut serial = crate::devices::serial::Serial;
+    if crate::graphics::screenshot::dump_ppm(fb, &mut serial).is_err() {
+        kprintln_colored!(Color::RED, "screenshot: failed to write PPM");
+    }
+}
+
+fn date(args: &[&str]) {
+    if args.contains(&"--log-timestamps") {
+        let enabled = !args.contains(&"off");
+        crate::services::set_timestamp_prefix(enabled);
+        kprintln!("log timestamp prefix {}", if enabled { "enabled" } else { "disabled" });
+        return;
+    }
+    kprintln!("{}", crate::services::wall_now());
+}
+
+fn dispatch_unknown(name: &str) {
+    kprintln_colored!(Color::RED, "unknown command: {} (try `help`)", name);
+}
+
+/// Splits `line` on whitespace and runs the matching [`Command`], or
+/// reports it as unknown. A no-op on a blank line (e.g. Enter pressed
+/// with nothing typed).
+pub fn dispatch(line: &str) {
+    let mut parts = line.split_whitespace();
+    let Some(name) = parts.next() else {
+        return;
+    };
+
+    const MAX_ARGS: usize = 8;
+    let mut args = [""; MAX_ARGS];
+    let mut argc = 0;
+    for part in parts {
+        if argc < MAX_ARGS {
+            args[argc] = part;
+            argc += 1;
+        }
+    }
+
+    match COMMANDS.iter().find(|c| c.name == name) {
+        Some(command) => (command.run)(&args[..argc]),
+        None => dispatch_unknown(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_addresses_with_and_without_0x_prefix() {
+        assert_eq!(parse_poke_addr("0x1000"), Ok(0x1000));
+        assert_eq!(parse_poke_addr("1000"), Ok(0x1000));
+    }
+
+    #[test]
+    fn rejects_null_and_misaligned_addresses() {
+        assert!(parse_poke_addr("0").is_err());
+        assert!(parse_poke_addr("0x1001").is_err());
+    }
+
+    #[test]
+    fn rejects_non_hex_input() {
+        assert!(parse_poke_addr("not-an-address").is_err());
+    }
+}
@@ -0,0 +1,139 @@
+/// How many bytes a single command line can hold, same fixed-capacity
+/// tradeoff as `console::COLS`/`frame_buffer::MAX_BACK_BUFFER_BYTES`:
+/// there's no heap to grow a buffer into.
+const LINE_CAPACITY: usize = 120;
+
+/// What [`LineEditor::feed`] learned from the character it was just
+/// given; the caller (normally [`super::Shell`]) turns this into the
+/// actual echo/backspace/dispatch side effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEditorAction {
+    /// Accepted into the line; echo it back.
+    Echo(char),
+    /// A character was removed; erase it on screen too.
+    Backspace,
+    /// Enter was pressed; the line is ready to parse and run.
+    Submit,
+    /// Backspace on an empty line, or a character that didn't fit and
+    /// wasn't accepted.
+    Ignored,
+}
+
+/// A plain line buffer with backspace, decoupled from wherever its
+/// characters actually come from (there's no keyboard driver in this
+/// kernel yet to source them from), so it can be unit-tested on its own
+/// and wired to a real key event stream later without changing it.
+#[derive(Debug)]
+pub struct LineEditor {
+    buf: [u8; LINE_CAPACITY],
+    len: usize,
+}
+
+impl LineEditor {
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; LINE_CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// Feed one input character, returning what happened to it.
+    pub fn feed(&mut self, c: char) -> LineEditorAction {
+        match c {
+            '\n' | '\r' => LineEditorAction::Submit,
+            '\u{8}' | '\u{7f}' => {
+                if self.len == 0 {
+                    LineEditorAction::Ignored
+                } else {
+                    self.len -= 1;
+                    LineEditorAction::Backspace
+                }
+            }
+            c if c.is_ascii() && !c.is_ascii_control() => {
+                if self.len < LINE_CAPACITY {
+                    self.buf[self.len] = c as u8;
+                    self.len += 1;
+                    LineEditorAction::Echo(c)
+                } else {
+                    LineEditorAction::Ignored
+                }
+            }
+            _ => LineEditorAction::Ignored,
+        }
+    }
+
+    /// The line accumulated so far. Always valid ASCII, since [`feed`]
+    /// only ever accepts ASCII, non-control characters into the buffer.
+    ///
+    /// [`feed`]: Self::feed
+    pub fn line(&self) -> &str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or("")
+    }
+
+    /// Discards the accumulated line, e.g. after [`LineEditorAction::Submit`]
+    /// has been dispatched.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl Default for LineEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn echoes_printable_characters_and_builds_the_line() {
+        let mut editor = LineEditor::new();
+        for c in "ls".chars() {
+            assert_eq!(editor.feed(c), LineEditorAction::Echo(c));
+        }
+        assert_eq!(editor.line(), "ls");
+    }
+
+    #[test]
+    fn enter_submits_without_being_added_to_the_line() {
+        let mut editor = LineEditor::new();
+        editor.feed('m');
+        assert_eq!(editor.feed('\n'), LineEditorAction::Submit);
+        assert_eq!(editor.line(), "m");
+    }
+
+    #[test]
+    fn backspace_removes_the_last_character() {
+        let mut editor = LineEditor::new();
+        editor.feed('a');
+        editor.feed('b');
+        assert_eq!(editor.feed('\u{8}'), LineEditorAction::Backspace);
+        assert_eq!(editor.line(), "a");
+    }
+
+    #[test]
+    fn backspace_on_an_empty_line_is_ignored() {
+        let mut editor = LineEditor::new();
+        assert_eq!(editor.feed('\u{8}'), LineEditorAction::Ignored);
+    }
+
+    #[test]
+    fn clear_resets_the_line_after_a_command_runs() {
+        let mut editor = LineEditor::new();
+        editor.feed('x');
+        editor.clear();
+        assert_eq!(editor.line(), "");
+    }
+
+    #[test]
+    fn characters_past_capacity_are_ignored_not_truncated_silently_into_garbage() {
+        let mut editor = LineEditor::new();
+        for _ in 0..LINE_CAPACITY {
+            editor.feed('a');
+        }
+        assert_eq!(editor.feed('b'), LineEditorAction::Ignored);
+        assert_eq!(editor.line().len(), LINE_CAPACITY);
+    }
+}
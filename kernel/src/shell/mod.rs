@@ -0,0 +1,56 @@
+#![allow(dead_code)]
+//! A keyboard-driven debug shell: `lspci`, `mem`, `mmap`, `clear`,
+//! `poke <addr>` and friends, for bring-up without a serial cable.
+//!
+//! This kernel has no keyboard driver yet (no HID boot-protocol
+//! parsing, no USB interrupt-in transfers wired up), so there's no key
+//! event stream to drive [`Shell::feed_char`] from today, hence the
+//! blanket `allow` above instead of wiring this into `kernel_main`.
+//! [`Shell`] and [`LineEditor`] are written against a plain `char`
+//! input instead of a kernel-specific key event type for exactly that
+//! reason: whatever the keyboard driver eventually produces only needs
+//! to decode to `char` and call `feed_char`, not know anything about
+//! the shell itself.
+mod commands;
+mod line_editor;
+
+use crate::kprint;
+pub use line_editor::{LineEditor, LineEditorAction};
+
+pub struct Shell {
+    editor: LineEditor,
+}
+
+impl Shell {
+    pub const fn new() -> Self {
+        Self {
+            editor: LineEditor::new(),
+        }
+    }
+
+    /// Feed one input character, echoing/erasing/dispatching as needed.
+    ///
+    /// Safe to call from the main loop; never call this from interrupt
+    /// context, since the commands it can dispatch (e.g. `clear`) take
+    /// the same console lock-free statics the main loop's own status
+    /// bar refresh does.
+    pub fn feed_char(&mut self, c: char) {
+        match self.editor.feed(c) {
+            LineEditorAction::Echo(c) => kprint!("{}", c),
+            LineEditorAction::Backspace => kprint!("\u{8} \u{8}"),
+            LineEditorAction::Submit => {
+                kprint!("\n");
+                commands::dispatch(self.editor.line());
+                self.editor.clear();
+                kprint!("> ");
+            }
+            LineEditorAction::Ignored => {}
+        }
+    }
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
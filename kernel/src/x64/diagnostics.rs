@@ -0,0 +1,71 @@
+//! Panic-time diagnostics: a register snapshot and a frame-pointer walk,
+//! for turning "it panicked somewhere" into "it panicked here, called
+//! from here, called from here". Kept separate from [`super::paging`]/
+//! [`super::gdt`] since nothing outside a panic needs this.
+use core::arch::asm;
+
+use super::{read_cr2, read_cr3};
+
+/// How many return addresses [`walk_frames`] will follow before giving
+/// up, in case a corrupted frame pointer chain would otherwise loop
+/// forever instead of terminating on a null frame pointer.
+const MAX_FRAMES: usize = 16;
+
+/// RSP/RBP/CR2/CR3 at the moment of capture. RIP itself isn't part of
+/// this: by the time a function can call `capture`, its own RIP is just
+/// "somewhere in capture", which isn't useful; the caller's address is
+/// the first entry [`walk_frames`] reports instead.
+#[derive(Debug, Clone, Copy)]
+pub struct Registers {
+    pub rsp: u64,
+    pub rbp: u64,
+    pub cr2: u64,
+    pub cr3: u64,
+}
+
+impl Registers {
+    /// Reads-only, so safe to call from anywhere, including a panic
+    /// handler that can't assume anything else in the kernel still
+    /// works.
+    pub fn capture() -> Self {
+        let (rsp, rbp): (u64, u64);
+        unsafe {
+            asm!("mov {}, rsp", out(reg) rsp);
+            asm!("mov {}, rbp", out(reg) rbp);
+        }
+        Self {
+            rsp,
+            rbp,
+            cr2: read_cr2(),
+            cr3: read_cr3(),
+        }
+    }
+}
+
+/// Walks the standard `push rbp; mov rbp, rsp` frame pointer chain
+/// starting at `rbp`, calling `f` with each return address found
+/// (innermost caller first). Requires `force-frame-pointers=yes` (set
+/// in `kernel/.cargo/config.toml`) to have anything to walk once
+/// optimizations are on.
+///
+/// Stops at a null or misaligned frame pointer, or after [`MAX_FRAMES`]
+/// frames, rather than trusting a possibly-corrupted chain (this is
+/// called from a panic handler) to terminate on its own.
+///
+/// # Safety
+/// `rbp` must be a value this kernel's own prologues produced (e.g.
+/// from [`Registers::capture`]); this reads raw memory starting there.
+pub unsafe fn walk_frames(mut rbp: u64, mut f: impl FnMut(u64)) {
+    for _ in 0..MAX_FRAMES {
+        if rbp == 0 || rbp % 8 != 0 {
+            break;
+        }
+        let frame = rbp as *const u64;
+        let return_addr = frame.add(1).read();
+        if return_addr == 0 {
+            break;
+        }
+        f(return_addr);
+        rbp = frame.read();
+    }
+}
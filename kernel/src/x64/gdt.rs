@@ -0,0 +1,141 @@
+//! A GDT and TSS the kernel owns, rather than whatever segmentation the
+//! firmware left behind. This is the prerequisite for an IST-backed
+//! double-fault handler: the IDT entry for #DF names a TSS IST slot to
+//! switch onto, and that slot has to point at a stack the kernel set up
+//! itself.
+use bit_field::BitField;
+use core::arch::asm;
+use core::mem::size_of;
+
+/// Segment selector for the kernel code segment, for use wherever a
+/// selector value is needed (e.g. once an IDT exists, in its gate
+/// descriptors).
+pub const KERNEL_CODE_SELECTOR: u16 = 1 << 3;
+/// Segment selector for the kernel data segment.
+pub const KERNEL_DATA_SELECTOR: u16 = 2 << 3;
+const TSS_SELECTOR: u16 = 3 << 3;
+
+/// Null, kernel code, kernel data, plus two slots for the TSS's 16-byte
+/// system descriptor.
+const GDT_ENTRIES: usize = 5;
+
+/// Backs IST1, the only IST stack set up so far. Sized generously for a
+/// fault handler that itself shouldn't need much stack.
+const IST_STACK_SIZE: usize = 4096 * 4;
+static mut IST_STACK: [u8; IST_STACK_SIZE] = [0; IST_STACK_SIZE];
+
+/// The x86_64 Task State Segment. Long mode doesn't use this for task
+/// switching, only for RSP/IST stack pointers the CPU loads on a
+/// privilege-level or IST-gated interrupt, so every field but `ist` and
+/// `iomap_base` (both set in [`init`]) is left zeroed.
+#[repr(C, packed)]
+struct Tss {
+    _reserved0: u32,
+    _rsp: [u64; 3],
+    _reserved1: u64,
+    ist: [u64; 7],
+    _reserved2: u64,
+    _reserved3: u16,
+    iomap_base: u16,
+}
+
+static mut TSS: Tss = Tss {
+    _reserved0: 0,
+    _rsp: [0; 3],
+    _reserved1: 0,
+    ist: [0; 7],
+    _reserved2: 0,
+    _reserved3: 0,
+    iomap_base: 0,
+};
+
+static mut GDT: [u64; GDT_ENTRIES] = [0; GDT_ENTRIES];
+
+#[repr(C, packed)]
+struct GdtPointer {
+    limit: u16,
+    base: u64,
+}
+
+/// A flat (base 0, limit ignored) 64-bit code or data segment descriptor.
+/// Long mode ignores base/limit for these, so only the access byte and
+/// the L/G bits carry any meaning.
+fn flat_descriptor(segment_type: u64, privilege_level: u64) -> u64 {
+    let mut d = 0u64;
+    d.set_bits(40..44, segment_type);
+    d.set_bit(44, true); // S: code/data, not a system descriptor
+    d.set_bits(45..47, privilege_level);
+    d.set_bit(47, true); // P: present
+    d.set_bit(53, true); // L: 64-bit code segment (data segments ignore this)
+    d.set_bit(55, true); // G: conventionally set even though ignored here
+    d
+}
+
+/// A 16-byte system descriptor pointing at `base`, used for the TSS.
+/// Unlike code/data descriptors, the base address here is real: it's
+/// how the CPU finds the TSS for `ltr`/IST lookups.
+fn tss_descriptor(base: u64, limit: u32) -> [u64; 2] {
+    let mut low = 0u64;
+    low.set_bits(0..16, u64::from(limit) & 0xffff);
+    low.set_bits(16..40, base & 0x00ff_ffff);
+    low.set_bits(40..44, 0b1001); // type: available 64-bit TSS
+    low.set_bits(48..52, (u64::from(limit) >> 16) & 0xf);
+    low.set_bit(47, true); // P: present
+    low.set_bits(56..64, (base >> 24) & 0xff);
+
+    let high = (base >> 32) & 0xffff_ffff;
+    [low, high]
+}
+
+/// Reload CS via a far return and the data segment registers via `mov`,
+/// since CS can't be loaded directly. The far-return trick (push the
+/// target selector and a return address, then `retfq`) is the standard
+/// way to switch CS from 64-bit code.
+unsafe fn reload_segments() {
+    asm!(
+        "mov {tmp:x}, {data_sel:x}",
+        "mov ss, {tmp:x}",
+        "mov ds, {tmp:x}",
+        "mov es, {tmp:x}",
+        "mov fs, {tmp:x}",
+        "mov gs, {tmp:x}",
+        "push {code_sel}",
+        "lea {tmp}, [2f + rip]",
+        "push {tmp}",
+        "retfq",
+        "2:",
+        tmp = out(reg) _,
+        data_sel = in(reg) u64::from(KERNEL_DATA_SELECTOR),
+        code_sel = in(reg) u64::from(KERNEL_CODE_SELECTOR),
+        options(nostack),
+    );
+}
+
+/// Build the GDT/TSS and load them.
+///
+/// # Safety
+/// Must run once, early in [`crate::services::init`], before anything
+/// depends on the firmware's segmentation still being in place.
+pub unsafe fn init() {
+    let ist_top = IST_STACK.as_mut_ptr().add(IST_STACK_SIZE) as u64;
+    TSS.ist[0] = ist_top;
+    TSS.iomap_base = size_of::<Tss>() as u16;
+
+    GDT[0] = 0; // null descriptor, required by the architecture
+    GDT[1] = flat_descriptor(0b1010, 0); // kernel code: execute/read
+    GDT[2] = flat_descriptor(0b0010, 0); // kernel data: read/write
+
+    let tss_base = core::ptr::addr_of!(TSS) as u64;
+    let [tss_low, tss_high] = tss_descriptor(tss_base, size_of::<Tss>() as u32 - 1);
+    GDT[3] = tss_low;
+    GDT[4] = tss_high;
+
+    let pointer = GdtPointer {
+        limit: (size_of::<[u64; GDT_ENTRIES]>() - 1) as u16,
+        base: GDT.as_ptr() as u64,
+    };
+    asm!("lgdt [{}]", in(reg) &pointer, options(readonly, nostack));
+
+    reload_segments();
+    asm!("ltr {0:x}", in(reg) TSS_SELECTOR, options(nostack));
+}
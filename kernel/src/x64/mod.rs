@@ -0,0 +1,146 @@
+//! Low-level x86_64 primitives that don't belong to any particular
+//! device: the interrupt flag and control registers here, in [`paging`]
+//! our own page tables, in [`gdt`] our own segmentation/TSS setup, in
+//! [`msr`] model-specific registers, and in [`diagnostics`] the
+//! register/backtrace dump the panic handler uses.
+use core::arch::asm;
+
+pub mod diagnostics;
+pub mod gdt;
+pub mod msr;
+pub mod paging;
+
+/// Whether interrupts are currently enabled (the IF bit of `rflags`).
+pub fn interrupts_enabled() -> bool {
+    let flags: u64;
+    unsafe {
+        asm!("pushfq", "pop {}", out(reg) flags);
+    }
+    flags & (1 << 9) != 0
+}
+
+/// # Safety
+/// Enabling interrupts before an IDT is installed lets the CPU fault on
+/// the first one it receives; only call this once interrupt handling is
+/// actually wired up, or via [`InterruptGuard`] restoring a flag that was
+/// already set.
+pub unsafe fn enable() {
+    asm!("sti");
+}
+
+/// # Safety
+/// Leaves interrupts disabled until something re-enables them; prefer
+/// [`InterruptGuard`] so a critical section can't forget to.
+pub unsafe fn disable() {
+    asm!("cli");
+}
+
+/// CR2: the faulting address of the most recent page fault. Stale
+/// outside of (or before the first) page fault; only meaningful to read
+/// from a `#PF` handler or, as [`diagnostics`] does, a panic handler
+/// that might be reporting one.
+pub fn read_cr2() -> u64 {
+    let value: u64;
+    unsafe {
+        asm!("mov {}, cr2", out(reg) value);
+    }
+    value
+}
+
+/// CR3: the physical address of the active PML4, plus its low control
+/// bits. See [`paging::init_identity_mapped`] for the one place this
+/// kernel writes it.
+pub fn read_cr3() -> u64 {
+    let value: u64;
+    unsafe {
+        asm!("mov {}, cr3", out(reg) value);
+    }
+    value
+}
+
+/// CR0: the core protected-mode/paging/write-protect control bits.
+pub fn read_cr0() -> u64 {
+    let value: u64;
+    unsafe {
+        asm!("mov {}, cr0", out(reg) value);
+    }
+    value
+}
+
+/// # Safety
+/// A wrong bit here can disable paging or write protection out from
+/// under code that assumes they're on; only flip bits whose effect on
+/// currently-running code has been checked.
+pub unsafe fn write_cr0(value: u64) {
+    asm!("mov cr0, {}", in(reg) value);
+}
+
+/// CR4: PAE, global pages, SIMD and other extension enable bits.
+pub fn read_cr4() -> u64 {
+    let value: u64;
+    unsafe {
+        asm!("mov {}, cr4", out(reg) value);
+    }
+    value
+}
+
+/// # Safety
+/// Same caveats as [`write_cr0`] -- e.g. clearing PAE while paging is
+/// active and depends on it will fault.
+pub unsafe fn write_cr4(value: u64) {
+    asm!("mov cr4, {}", in(reg) value);
+}
+
+/// Invalidate the TLB entry covering `addr`, so a page table edit made
+/// without reloading CR3 (e.g. changing one entry rather than rebuilding
+/// the whole identity map) takes effect immediately instead of only
+/// after the next full TLB flush.
+///
+/// # Safety
+/// `addr` should be a virtual address whose mapping was actually just
+/// changed; invalidating an unrelated address is harmless but pointless.
+pub unsafe fn invlpg(addr: u64) {
+    asm!("invlpg [{}]", in(reg) addr);
+}
+
+/// Hint to the CPU that this is a spin-wait loop, so it can avoid the
+/// memory-order misspeculation penalty a tight loop would otherwise
+/// cause on exit. Purely a performance hint -- safe to call anywhere,
+/// including when there's nothing to spin on.
+pub fn pause() {
+    unsafe {
+        asm!("pause");
+    }
+}
+
+/// RAII guard for a critical section: disables interrupts on
+/// construction and restores whatever the interrupt flag was beforehand
+/// on drop, so an early return out of the guarded section can't
+/// accidentally leave interrupts off. Reads the flag before disabling
+/// so nested guards restore correctly instead of unconditionally
+/// re-enabling.
+pub struct InterruptGuard {
+    was_enabled: bool,
+}
+
+impl InterruptGuard {
+    pub fn new() -> Self {
+        let was_enabled = interrupts_enabled();
+        unsafe { disable() };
+        Self { was_enabled }
+    }
+}
+
+impl Default for InterruptGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        if self.was_enabled {
+            unsafe { enable() };
+        }
+    }
+}
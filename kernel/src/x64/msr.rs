@@ -0,0 +1,35 @@
+//! Typed access to model-specific registers via `rdmsr`/`wrmsr`.
+use core::arch::asm;
+
+/// A model-specific register, identified by its 32-bit index. Reading
+/// or writing one is unsafe regardless of index: an index this CPU
+/// doesn't implement raises `#GP`, and a write can change CPU behavior
+/// in ways specific to that MSR.
+#[derive(Debug, Clone, Copy)]
+pub struct Msr(u32);
+
+impl Msr {
+    /// Extended Feature Enable Register: long mode and NX enable bits.
+    pub const IA32_EFER: Self = Self::new(0xc000_0080);
+
+    pub const fn new(index: u32) -> Self {
+        Self(index)
+    }
+
+    /// # Safety
+    /// `self` must name an MSR this CPU actually implements.
+    pub unsafe fn read(self) -> u64 {
+        let (lo, hi): (u32, u32);
+        asm!("rdmsr", in("ecx") self.0, out("eax") lo, out("edx") hi);
+        ((hi as u64) << 32) | lo as u64
+    }
+
+    /// # Safety
+    /// Same caveat as [`Self::read`], plus whatever behavior change
+    /// writing this particular MSR causes.
+    pub unsafe fn write(self, value: u64) {
+        let lo = value as u32;
+        let hi = (value >> 32) as u32;
+        asm!("wrmsr", in("ecx") self.0, in("eax") lo, in("edx") hi);
+    }
+}
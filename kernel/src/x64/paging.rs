@@ -0,0 +1,91 @@
+//! A from-scratch PML4 so the kernel stops depending on whatever page
+//! tables the firmware happened to leave behind.
+//!
+//! Everything here keeps identity semantics (virtual == physical) so
+//! existing code that hands raw physical addresses around -- PCI BARs,
+//! the xHC's MMIO base, the framebuffer pointer handed off by the loader
+//! -- keeps working unchanged. This is groundwork only: it does not
+//! introduce any notion of a non-identity mapping, it just moves "who
+//! owns the page tables" from the firmware to us.
+
+use core::arch::asm;
+
+const ENTRIES: usize = 512;
+const PAGE_2MIB: u64 = 0x20_0000;
+const PAGE_1GIB: u64 = 0x4000_0000;
+
+/// Number of gibibytes identity-mapped unconditionally, with 2 MiB pages.
+/// Covers the kernel and loader's own low placement plus the vast
+/// majority of real-world framebuffer physical addresses.
+const LOW_MEM_GIB: usize = 4;
+
+const PRESENT: u64 = 1 << 0;
+const WRITABLE: u64 = 1 << 1;
+const PAGE_SIZE: u64 = 1 << 7;
+
+type Table = [u64; ENTRIES];
+
+const EMPTY_TABLE: Table = [0; ENTRIES];
+
+static mut PML4: Table = EMPTY_TABLE;
+static mut PDPT: Table = EMPTY_TABLE;
+static mut LOW_PDS: [Table; LOW_MEM_GIB] = [EMPTY_TABLE; LOW_MEM_GIB];
+/// Backs a single extra 1 GiB window for a framebuffer that lands outside
+/// [`LOW_MEM_GIB`]. One window is all real hardware ever needs: a
+/// framebuffer is at most tens of MiB, so it can't straddle a 1 GiB
+/// boundary unless it's placed within the last few MiB of a window, which
+/// [`init_identity_mapped`] refuses to handle rather than silently
+/// leaving part of it unmapped.
+static mut EXTRA_PD: Table = EMPTY_TABLE;
+
+fn table_entry(phys_addr: u64) -> u64 {
+    (phys_addr & !0xfff) | PRESENT | WRITABLE
+}
+
+fn huge_page_entry(phys_addr: u64) -> u64 {
+    (phys_addr & !(PAGE_2MIB - 1)) | PRESENT | WRITABLE | PAGE_SIZE
+}
+
+fn fill_identity_pd(pd: &mut Table, gib_base: u64) {
+    for (i, entry) in pd.iter_mut().enumerate() {
+        *entry = huge_page_entry(gib_base + (i as u64) * PAGE_2MIB);
+    }
+}
+
+/// Build a fresh PML4 identity-mapping the low [`LOW_MEM_GIB`] GiB of
+/// physical memory plus the framebuffer at `fb_base..fb_base + fb_size`,
+/// then load it into CR3.
+///
+/// # Safety
+/// Must be called while the firmware's own (also identity) page tables
+/// are still active, since the static tables built here are themselves
+/// addressed identically before and after the switch. Must also be
+/// called before anything else dereferences a physical address that
+/// isn't covered by the low range or the framebuffer -- there is no
+/// page fault handler yet, so an uncovered access triple-faults instead
+/// of raising a recoverable exception.
+pub unsafe fn init_identity_mapped(fb_base: usize, fb_size: usize) {
+    for (gib, pd) in LOW_PDS.iter_mut().enumerate() {
+        fill_identity_pd(pd, gib as u64 * PAGE_1GIB);
+        PDPT[gib] = table_entry(pd.as_ptr() as u64);
+    }
+
+    let fb_base = fb_base as u64;
+    let fb_end = fb_base + fb_size as u64;
+    let low_limit = LOW_MEM_GIB as u64 * PAGE_1GIB;
+    if fb_end > low_limit {
+        let gib_index = (fb_base / PAGE_1GIB) as usize;
+        assert_eq!(
+            gib_index,
+            ((fb_end - 1) / PAGE_1GIB) as usize,
+            "framebuffer spans more than one 1 GiB window outside the low identity map"
+        );
+        fill_identity_pd(&mut EXTRA_PD, gib_index as u64 * PAGE_1GIB);
+        PDPT[gib_index] = table_entry(EXTRA_PD.as_ptr() as u64);
+    }
+
+    PML4[0] = table_entry(PDPT.as_ptr() as u64);
+
+    let pml4_addr = PML4.as_ptr() as u64;
+    asm!("mov cr3, {}", in(reg) pml4_addr);
+}
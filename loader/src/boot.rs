@@ -2,28 +2,32 @@ use core::mem;
 use core::slice;
 
 use alloc::vec::Vec;
+use goblin::elf::header::{EI_CLASS, EI_DATA, ELFCLASS64, ELFDATA2LSB, EM_X86_64, ET_EXEC};
 use goblin::elf::{program_header, Elf};
-use log::info;
+use log::{error, info};
 use uefi::data_types::Align;
-use uefi::proto::media::file::{Directory, File, FileAttribute, FileInfo, FileMode, FileType};
+use uefi::proto::media::file::{Directory, File, FileAttribute, FileInfo, FileMode, FileType, RegularFile};
 use uefi::table::boot::{AllocateType, MemoryDescriptor, MemoryType};
 use uefi::table::Runtime;
-use uefi::{prelude::*, CString16, Result};
+use uefi::{prelude::*, CString16, Result, Status};
 
 use common_data::mmap::MemMap;
+use common_data::module::BootModule;
 
 const EFI_PAGE_SIZE: usize = 0x1000; // 4096 B
 
-/// Loading kernel executable.
-/// Return value is address of entry point.
-pub(crate) fn load_kernel(root: &mut Directory, boot: &BootServices) -> Result<*const u8> {
-    let filename = CString16::try_from("kernel.elf").unwrap();
+/// Open `name` off `root` and return it together with its reported size,
+/// failing the same way `load_kernel` always has if it turns out to be a
+/// directory. Shared by `load_kernel` and `load_module` since both just
+/// want "the bytes of a file on the boot volume".
+fn open_file(root: &mut Directory, name: &str) -> Result<(uefi::proto::media::file::RegularFile, usize)> {
+    let filename = CString16::try_from(name).unwrap();
     let mut file = match root
         .open(&filename, FileMode::Read, FileAttribute::empty())?
         .into_type()?
     {
         FileType::Regular(file) => file,
-        FileType::Dir(_) => panic!("entry for \"kernel.elf\" is already exists as a directory."),
+        FileType::Dir(_) => panic!("entry for \"{}\" is already exists as a directory.", name),
     };
 
     // Unlike C, (maybe) we cannot extract the size of ?Sized struct excluding last ?Sized member.
@@ -40,14 +44,66 @@ pub(crate) fn load_kernel(root: &mut Directory, boot: &BootServices) -> Result<*
         .expect("cannot get file info")
         .file_size() as usize;
 
+    Ok((file, size))
+}
+
+/// Loading kernel executable.
+/// Return value is address of entry point.
+pub(crate) fn load_kernel(root: &mut Directory, boot: &BootServices) -> Result<*const u8> {
+    let (mut file, size) = open_file(root, "kernel.elf")?;
+
     let mut src = vec![0; size];
     let _ = file.read(&mut src).expect("cannot read kernel executable.");
     load_elf(&src, boot)
 }
 
+/// Read an arbitrary file from the boot volume (e.g. an initrd) into
+/// freshly allocated pages and hand back its base/size. Unlike
+/// `load_kernel`, the bytes are committed as-is with no ELF segment
+/// relocation, so `name` should be something the kernel knows how to
+/// parse on its own once it's handed the resulting `BootModule`.
+pub(crate) fn load_module(root: &mut Directory, boot: &BootServices, name: &str) -> Result<BootModule> {
+    let (mut file, size) = open_file(root, name)?;
+
+    let page_count = (size + EFI_PAGE_SIZE - 1) / EFI_PAGE_SIZE;
+    let base = boot.allocate_pages(AllocateType::AnyPages, MemoryType::LOADER_DATA, page_count)?;
+
+    let dst = unsafe { slice::from_raw_parts_mut(base as *mut u8, size) };
+    let _ = file.read(dst).expect("cannot read module file.");
+
+    Ok(BootModule::new(base as *const u8, size))
+}
+
+/// Rejects anything that isn't a 64-bit, little-endian, x86-64
+/// executable before `load_elf` walks its program headers: loading a
+/// mismatched kernel binary would otherwise copy garbage segments into
+/// place and jump into nonsense (a silent triple-fault) instead of
+/// failing here with a readable reason.
+fn validate_elf_header(elf: &Elf) -> Result<()> {
+    let ident = &elf.header.e_ident;
+    if ident[EI_CLASS] != ELFCLASS64 {
+        error!("kernel.elf is not a 64-bit ELF (e_ident[EI_CLASS] = {})", ident[EI_CLASS]);
+        return Err(Status::LOAD_ERROR.into());
+    }
+    if ident[EI_DATA] != ELFDATA2LSB {
+        error!("kernel.elf is not little-endian (e_ident[EI_DATA] = {})", ident[EI_DATA]);
+        return Err(Status::LOAD_ERROR.into());
+    }
+    if elf.header.e_machine != EM_X86_64 {
+        error!("kernel.elf is not built for x86-64 (e_machine = {})", elf.header.e_machine);
+        return Err(Status::LOAD_ERROR.into());
+    }
+    if elf.header.e_type != ET_EXEC {
+        error!("kernel.elf is not an executable (e_type = {})", elf.header.e_type);
+        return Err(Status::LOAD_ERROR.into());
+    }
+    Ok(())
+}
+
 pub(crate) fn load_elf(src: &[u8], boot: &BootServices) -> Result<*const u8> {
     let elf = Elf::parse(src).expect("failed to parse elf");
     info!("elf parsed!");
+    validate_elf_header(&elf)?;
     let load_segments = elf
         .program_headers
         .iter()
@@ -106,10 +162,8 @@ pub(crate) fn exit_boot_services(
 
     let (runtime, mmap) = systab.exit_boot_services(image, &mut mmap_buf)?;
 
-    for &desc in mmap {
-        if desc.ty.available() {
-            descs.push(desc.into());
-        }
+    for &desc in available_descriptors(mmap) {
+        descs.push(desc.into());
     }
 
     let mmap = MemMap::from_slice(&descs);
@@ -134,34 +188,82 @@ impl AfterBootServiceExit for MemoryType {
     }
 }
 
-/// Functionalities which were implemented in past chapters.
-mod unused {
-    #![allow(unused)]
-    use uefi::proto::media::file::RegularFile;
-    use uefi::table::boot::MemoryDescriptor;
+/// The descriptors [`exit_boot_services`] actually keeps, i.e. the ones
+/// that become the kernel's `MemMap`. Shared with [`save_memmap_to_file`]
+/// so a dumped `memmap.csv` always lists exactly the descriptors the
+/// kernel ends up with for that boot, not the full unfiltered UEFI map.
+fn available_descriptors<'a>(
+    mmap: impl Iterator<Item = &'a MemoryDescriptor>,
+) -> impl Iterator<Item = &'a MemoryDescriptor> {
+    mmap.filter(|desc| desc.ty.available())
+}
 
-    const MEMMAP_SIZE: usize = 4096 * 4;
-    const HEADER: &[u8; 65] = b"Index, Type, Type(name), PhysicalStart, NumberOfPages, Attribute\n";
+const MEMMAP_DUMP_FILE_NAME: &str = "memmap.csv";
+const MEMMAP_DUMP_MARKER_FILE_NAME: &str = "memmap.dump";
+const MEMMAP_CSV_HEADER: &[u8] = b"Index, Type, Type(name), PhysicalStart, NumberOfPages, Attribute\n";
 
-    /// Dump memmap to file
-    fn save_memmap<'a, M>(desc: M, mut file: RegularFile)
-    where
-        M: ExactSizeIterator<Item = &'a MemoryDescriptor> + Clone,
-    {
-        // It is OK to write u8 because user will read this file through other machine rather than this application runs on (e.g. Host for QEMU).
-        file.write(HEADER).expect("failed to write to file.");
-        for (i, d) in desc.enumerate() {
-            let line = format!(
-                "{}, {:#x}, {:?}, {:#08x}, {:#x}, {:#x}\n",
-                i,
-                d.ty.0,
-                d.ty,
-                d.phys_start,
-                d.virt_start,
-                d.att.bits().clamp(0, 0xfffff)
-            );
-            file.write(line.as_bytes())
-                .expect("failed to write to file.");
-        }
+/// Whether `memmap.csv` should be written this boot: the presence of an
+/// empty `memmap.dump` marker file on the ESP, checked for and left
+/// alone here (nothing reads or deletes it), so dumping can be turned on
+/// or off without rebuilding the loader.
+pub(crate) fn should_dump_memmap(root: &mut Directory) -> bool {
+    let filename = CString16::try_from(MEMMAP_DUMP_MARKER_FILE_NAME).unwrap();
+    root.open(&filename, FileMode::Read, FileAttribute::empty())
+        .is_ok()
+}
+
+/// Writes `descs` to `file` as `Index, Type, Type(name), PhysicalStart,
+/// NumberOfPages, Attribute` rows -- the same columns and formatting the
+/// loader has always used for this dump -- filtered to the descriptors
+/// [`available_descriptors`] keeps, so the row count and contents match
+/// what `exit_boot_services` hands the kernel.
+fn write_memmap_csv<'a>(
+    descs: impl Iterator<Item = &'a MemoryDescriptor>,
+    file: &mut RegularFile,
+) -> Result<()> {
+    // It is OK to write u8 because user will read this file through other
+    // machine rather than this application runs on (e.g. Host for QEMU).
+    file.write(MEMMAP_CSV_HEADER).map_err(|e| e.status())?;
+    for (i, d) in available_descriptors(descs).enumerate() {
+        let line = format!(
+            "{}, {:#x}, {:?}, {:#08x}, {:#x}, {:#x}\n",
+            i,
+            d.ty.0,
+            d.ty,
+            d.phys_start,
+            d.virt_start,
+            d.att.bits().clamp(0, 0xfffff)
+        );
+        file.write(line.as_bytes()).map_err(|e| e.status())?;
     }
+    Ok(())
+}
+
+/// Dumps the current memory map to `memmap.csv` on the ESP, for
+/// diagnosing allocation failures that need to see the map as the
+/// firmware reports it. Must run before [`exit_boot_services`] -- the
+/// file system protocol this opens `root` through isn't available once
+/// boot services have exited. Callers should log and keep booting on
+/// `Err` rather than failing the boot over a diagnostics dump.
+pub(crate) fn save_memmap_to_file(root: &mut Directory, boot: &BootServices) -> Result<()> {
+    let size = boot.memory_map_size().map_size + 8 * mem::size_of::<MemoryDescriptor>();
+    let mut mmap_buf = vec![0; size];
+    let (_key, mmap) = boot.memory_map(&mut mmap_buf)?;
+
+    let filename = CString16::try_from(MEMMAP_DUMP_FILE_NAME).unwrap();
+    let file = root
+        .open(
+            &filename,
+            FileMode::CreateReadWrite,
+            FileAttribute::empty(),
+        )?
+        .into_type()?;
+    let mut file = match file {
+        FileType::Regular(file) => file,
+        FileType::Dir(_) => panic!("\"{}\" already exists as a directory.", MEMMAP_DUMP_FILE_NAME),
+    };
+
+    write_memmap_csv(mmap, &mut file)?;
+    file.close();
+    Ok(())
 }
@@ -1,13 +1,47 @@
 use core::cell::UnsafeCell;
 
+use log::error;
 use uefi::prelude::*;
-use uefi::proto::console::gop::GraphicsOutput;
+use uefi::proto::console::gop::{GraphicsOutput, PixelFormat};
 use uefi::Result;
 
 pub fn open_gop(boot: &BootServices) -> Result<&UnsafeCell<GraphicsOutput>> {
     boot.locate_protocol::<GraphicsOutput>()
 }
 
+/// Switches `gop` to a mode this kernel can actually draw through,
+/// leaving it alone if the current mode already qualifies.
+///
+/// `common_data::graphics::FrameBuffer` represents every packed
+/// 32-bit-per-pixel GOP format -- `Rgb`, `Bgr`, and `Bitmask` (whose
+/// per-channel masks it carries along) -- but not `BltOnly`, which
+/// exposes no linear framebuffer at all and draws only through
+/// `Blt()`, a call this kernel never makes. Rather than build a
+/// `FrameBuffer` over that and let it panic the first time a pixel
+/// gets written, this looks for the first drawable mode GOP offers and
+/// switches to it up front.
+pub fn select_drawable_mode(gop: &mut GraphicsOutput) -> Result<()> {
+    if is_drawable(gop.current_mode_info().pixel_format()) {
+        return Ok(().into());
+    }
+
+    let drawable = gop
+        .modes()
+        .find(|mode| is_drawable(mode.info().pixel_format()));
+
+    match drawable {
+        Some(mode) => gop.set_mode(&mode),
+        None => {
+            error!("no drawable graphics mode offered by this adapter -- only BltOnly modes were available");
+            Err(Status::UNSUPPORTED.into())
+        }
+    }
+}
+
+fn is_drawable(format: PixelFormat) -> bool {
+    !matches!(format, PixelFormat::BltOnly)
+}
+
 /// Functionalities which were implemented in past chapters.
 mod unused {
     #![allow(unused)]
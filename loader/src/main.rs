@@ -8,11 +8,12 @@ extern crate uefi_services;
 
 use core::mem;
 
-use log::info;
+use log::{error, info};
 use uefi::prelude::*;
 
 use common_data::graphics::FrameBuffer;
 use common_data::mmap::MemMap;
+use common_data::module::BootModule;
 
 mod boot;
 mod fs;
@@ -37,6 +38,9 @@ fn efi_main(image: Handle, mut systab: SystemTable<Boot>) -> Status {
     info!("getting graphic output protocol...");
     let gop = graphics::open_gop(boot).expect("failed to open graphic output protocol.");
     let gop = unsafe { &mut *gop.get() };
+    if let Err(err) = graphics::select_drawable_mode(gop) {
+        return err.status();
+    }
     let mode = gop.current_mode_info();
     info!(
         "Resolution: (w, h)={:?}, Pixel Format: {:?}, {} px/line",
@@ -49,12 +53,23 @@ fn efi_main(image: Handle, mut systab: SystemTable<Boot>) -> Status {
     info!("accessing file system...");
     let mut root = fs::open_root_dir(image, boot).expect("failed to open root directory");
 
+    if boot::should_dump_memmap(&mut root) {
+        info!("memmap.dump marker found, dumping memory map to memmap.csv...");
+        if let Err(status) = boot::save_memmap_to_file(&mut root, boot) {
+            error!("failed to save memmap.csv: {:?}", status);
+        }
+    }
+
     info!("loading kernel file...");
     let entry_addr = boot::load_kernel(&mut root, boot).expect("failed to loading kernel.");
 
+    info!("loading initrd module...");
+    let module =
+        boot::load_module(&mut root, boot, "initrd.img").expect("failed to loading initrd module.");
+
     info!("entry point: {:?}", entry_addr);
     let entry = unsafe {
-        type EntryPoint = extern "sysv64" fn(*const MemMap, *mut FrameBuffer);
+        type EntryPoint = extern "sysv64" fn(*const MemMap, *mut FrameBuffer, *const BootModule);
         mem::transmute::<*const u8, EntryPoint>(entry_addr)
     };
 
@@ -62,7 +77,11 @@ fn efi_main(image: Handle, mut systab: SystemTable<Boot>) -> Status {
     let (_, mmap) = boot::exit_boot_services(image, systab).expect("failed to exit boot service.");
 
     info!("calling kernel entry...");
-    entry(&mmap as *const MemMap, &mut fb as *mut FrameBuffer);
+    entry(
+        &mmap as *const MemMap,
+        &mut fb as *mut FrameBuffer,
+        &module as *const BootModule,
+    );
 
     #[allow(clippy::empty_loop)]
     loop {}